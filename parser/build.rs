@@ -0,0 +1,12 @@
+fn main() {
+    // Protobuf bindings are only needed for the `proto` output format
+    #[cfg(feature = "proto")]
+    {
+        println!("cargo:rerun-if-changed=proto/swiftconcur.proto");
+        let descriptors = protox::compile(["proto/swiftconcur.proto"], ["proto"])
+            .expect("failed to compile proto/swiftconcur.proto");
+        prost_build::Config::new()
+            .compile_fds(descriptors)
+            .expect("failed to generate protobuf bindings");
+    }
+}
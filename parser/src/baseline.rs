@@ -0,0 +1,113 @@
+use crate::cli::BaselineFormat;
+use crate::error::{ParseError, Result};
+use crate::models::{WarningRun, SCHEMA_VERSION};
+use std::path::Path;
+
+/// Load a baseline report, migrating documents written by older versions
+pub fn load_baseline(path: &Path, format: BaselineFormat) -> Result<WarningRun> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| ParseError::BaselineError(format!("cannot read {}: {e}", path.display())))?;
+    parse_baseline(&content, format)
+}
+
+/// Write `run` as a baseline that [`load_baseline`] reads back
+pub fn save_baseline(path: &Path, run: &WarningRun) -> Result<()> {
+    let content = serde_json::to_string_pretty(run)?;
+    std::fs::write(path, format!("{content}\n"))
+        .map_err(|e| ParseError::BaselineError(format!("cannot write {}: {e}", path.display())))
+}
+
+pub fn parse_baseline(content: &str, format: BaselineFormat) -> Result<WarningRun> {
+    let mut run: WarningRun = serde_json::from_str(content)
+        .map_err(|e| ParseError::BaselineError(format!("invalid baseline JSON: {e}")))?;
+
+    match format {
+        BaselineFormat::Auto => {}
+        BaselineFormat::V1 => run.schema_version = 1,
+        BaselineFormat::V2 => run.schema_version = 2,
+    }
+
+    if run.schema_version > SCHEMA_VERSION {
+        return Err(ParseError::BaselineError(format!(
+            "baseline schema version {} is newer than supported version {}",
+            run.schema_version, SCHEMA_VERSION
+        )));
+    }
+
+    Ok(run.migrate())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A baseline as written before `schema_version` and `code_context` defaults existed
+    const V1_BASELINE: &str = r#"{
+        "commit_sha": "abc123",
+        "branch": "main",
+        "pull_request": null,
+        "warnings": [
+            {
+                "id": "/src/A.swift:42:54",
+                "warning_type": "actor_isolation",
+                "severity": "high",
+                "file_path": "/src/A.swift",
+                "line_number": 42,
+                "column_number": null,
+                "message": "actor-isolated property 'shared' can not be referenced"
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn test_v1_baseline_migrates() {
+        let run = parse_baseline(V1_BASELINE, BaselineFormat::Auto).unwrap();
+
+        assert_eq!(run.schema_version, SCHEMA_VERSION);
+        assert!(!run.id.is_empty());
+        assert_eq!(run.total_warnings, 1);
+        assert_eq!(run.commit_sha.as_deref(), Some("abc123"));
+        assert_eq!(run.warnings[0].line_number, 42);
+        assert!(run.warnings[0].code_context.line.is_empty());
+        assert!(run.warnings[0].suggested_fix.is_none());
+    }
+
+    #[test]
+    fn test_current_baseline_round_trips() {
+        let original = WarningRun::new(Vec::new());
+        let json = serde_json::to_string(&original).unwrap();
+
+        let run = parse_baseline(&json, BaselineFormat::Auto).unwrap();
+        assert_eq!(run.id, original.id);
+        assert_eq!(run.schema_version, SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_forced_v1_format_recomputes_totals() {
+        let json = r#"{"schema_version": 2, "id": "x", "commit_sha": null, "branch": null,
+            "pull_request": null, "total_warnings": 7, "warnings": [],
+            "created_at": "2025-01-01T00:00:00Z"}"#;
+
+        assert_eq!(
+            parse_baseline(json, BaselineFormat::Auto)
+                .unwrap()
+                .total_warnings,
+            7
+        );
+        assert_eq!(
+            parse_baseline(json, BaselineFormat::V1)
+                .unwrap()
+                .total_warnings,
+            0
+        );
+    }
+
+    #[test]
+    fn test_rejects_newer_schema() {
+        let json = r#"{"schema_version": 99, "warnings": []}"#;
+        assert!(matches!(
+            parse_baseline(json, BaselineFormat::Auto),
+            Err(ParseError::BaselineError(_))
+        ));
+    }
+}
@@ -0,0 +1,294 @@
+use crate::error::{ParseError, Result};
+use crate::models::Warning;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+lazy_static! {
+    // `@@ -a[,b] +c[,d] @@` hunk header of a zero-context diff
+    static ref HUNK_HEADER: Regex = Regex::new(r"^@@ -\d+(?:,\d+)? \+(\d+)(?:,(\d+))? @@").unwrap();
+}
+
+/// Lines added or modified relative to a base ref, keyed by repo-relative path
+#[derive(Debug, Default)]
+pub struct ChangedLines {
+    toplevel: PathBuf,
+    files: HashMap<PathBuf, Vec<RangeInclusive<usize>>>,
+}
+
+impl ChangedLines {
+    /// Whether `line` of `path` differs from the base ref. Relative paths are
+    /// taken as relative to the repository root.
+    pub fn contains(&self, path: &Path, line: usize) -> bool {
+        let relative = self.relative_path(path);
+        self.files
+            .get(relative.as_path())
+            .is_some_and(|ranges| ranges.iter().any(|range| range.contains(&line)))
+    }
+
+    pub fn retain_changed(&self, warnings: Vec<Warning>) -> Vec<Warning> {
+        warnings
+            .into_iter()
+            .filter(|w| self.contains(&w.file_path, w.line_number))
+            .collect()
+    }
+
+    fn relative_path(&self, path: &Path) -> PathBuf {
+        if let Ok(relative) = path.strip_prefix(&self.toplevel) {
+            return relative.to_path_buf();
+        }
+        // Paths through a symlinked prefix (e.g. /tmp on macOS) only match once resolved
+        path.canonicalize()
+            .ok()
+            .and_then(|resolved| {
+                resolved
+                    .strip_prefix(&self.toplevel)
+                    .ok()
+                    .map(Path::to_path_buf)
+            })
+            .unwrap_or_else(|| path.to_path_buf())
+    }
+}
+
+fn git(dir: &Path, args: &[&str]) -> std::io::Result<std::process::Output> {
+    Command::new("git").arg("-C").arg(dir).args(args).output()
+}
+
+/// Collect the lines changed in the working tree of the repository containing
+/// `dir` since `base`. Returns `None` when `dir` is not inside a git repository
+/// (or git is unavailable), so callers can skip the filter.
+pub fn changed_lines(dir: &Path, base: &str) -> Result<Option<ChangedLines>> {
+    let toplevel = match git(dir, &["rev-parse", "--show-toplevel"]) {
+        Ok(output) if output.status.success() => {
+            PathBuf::from(String::from_utf8_lossy(&output.stdout).trim())
+        }
+        _ => return Ok(None),
+    };
+
+    let output = git(
+        &toplevel,
+        &[
+            // Keep non-ASCII paths literal; git still quotes some characters
+            "-c",
+            "core.quotepath=off",
+            "diff",
+            "--unified=0",
+            "--no-color",
+            "--no-ext-diff",
+            "--no-prefix",
+            base,
+            "--",
+        ],
+    )?;
+    if !output.status.success() {
+        return Err(ParseError::GitError(format!(
+            "git diff {base} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    let toplevel = toplevel.canonicalize().unwrap_or(toplevel);
+    Ok(Some(ChangedLines {
+        files: parse_diff(&String::from_utf8_lossy(&output.stdout)),
+        toplevel,
+    }))
+}
+
+fn parse_diff(diff: &str) -> HashMap<PathBuf, Vec<RangeInclusive<usize>>> {
+    let mut files: HashMap<PathBuf, Vec<RangeInclusive<usize>>> = HashMap::new();
+    let mut current: Option<PathBuf> = None;
+
+    for line in diff.lines() {
+        if let Some(path) = line.strip_prefix("+++ ") {
+            // Deleted files have no new-side lines to keep
+            current = (path != "/dev/null").then(|| diff_path(path));
+        } else if let (Some(path), Some(captures)) = (&current, HUNK_HEADER.captures(line)) {
+            let start: usize = captures[1].parse().unwrap_or(0);
+            let count: usize = captures
+                .get(2)
+                .and_then(|m| m.as_str().parse().ok())
+                .unwrap_or(1);
+            // Pure deletions (count 0) add no lines on the new side
+            if count > 0 {
+                files
+                    .entry(path.clone())
+                    .or_default()
+                    .push(start..=start + count - 1);
+            }
+        }
+    }
+
+    files
+}
+
+/// The path of a `+++ ` header: git appends a tab to paths with spaces and
+/// C-quotes those with special or (without `core.quotepath=off`) non-ASCII
+/// characters, e.g. `"\303\244.swift"`
+fn diff_path(header: &str) -> PathBuf {
+    let path = header.strip_suffix('\t').unwrap_or(header);
+    let Some(quoted) = path
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+    else {
+        return PathBuf::from(path);
+    };
+
+    let mut bytes = Vec::with_capacity(quoted.len());
+    let mut chars = quoted.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            let mut buf = [0; 4];
+            bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+            continue;
+        }
+        match chars.next() {
+            Some(digit @ '0'..='7') => {
+                // Up to three octal digits for one raw byte of a UTF-8 sequence
+                let mut value = digit.to_digit(8).unwrap_or(0);
+                for _ in 0..2 {
+                    match chars.peek().and_then(|c| c.to_digit(8)) {
+                        Some(d) => {
+                            value = value * 8 + d;
+                            chars.next();
+                        }
+                        None => break,
+                    }
+                }
+                bytes.push(value as u8);
+            }
+            Some('a') => bytes.push(0x07),
+            Some('b') => bytes.push(0x08),
+            Some('f') => bytes.push(0x0c),
+            Some('n') => bytes.push(b'\n'),
+            Some('r') => bytes.push(b'\r'),
+            Some('t') => bytes.push(b'\t'),
+            Some('v') => bytes.push(0x0b),
+            Some(other) => {
+                let mut buf = [0; 4];
+                bytes.extend_from_slice(other.encode_utf8(&mut buf).as_bytes());
+            }
+            None => bytes.push(b'\\'),
+        }
+    }
+    PathBuf::from(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn run_git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    fn warning(path: PathBuf, line: usize) -> Warning {
+        Warning::fixture(path, line)
+    }
+
+    #[test]
+    fn test_parse_diff_ranges() {
+        let diff = "\
+--- Sources/A.swift
++++ Sources/A.swift
+@@ -3 +3 @@
+@@ -8,0 +9,2 @@
+@@ -12,3 +13,0 @@
+--- Sources/Old.swift
++++ /dev/null
+@@ -1,4 +0,0 @@
+";
+        let files = parse_diff(diff);
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[Path::new("Sources/A.swift")], vec![3..=3, 9..=10]);
+    }
+
+    #[test]
+    fn test_parse_diff_quoted_paths() {
+        let diff = "\
+--- Sources/My View.swift\t
++++ Sources/My View.swift\t
+@@ -3 +3 @@
+--- \"Sources/\\303\\244rger.swift\"
++++ \"Sources/\\303\\244rger.swift\"
+@@ -5 +5 @@
++++ Sources/\u{e4}nderung.swift
+@@ -7 +7 @@
++++ \"Sources/say \\\"hi\\\".swift\"
+@@ -9 +9 @@
+";
+        let files = parse_diff(diff);
+        assert_eq!(files[Path::new("Sources/My View.swift")], vec![3..=3]);
+        assert_eq!(files[Path::new("Sources/\u{e4}rger.swift")], vec![5..=5]);
+        assert_eq!(files[Path::new("Sources/\u{e4}nderung.swift")], vec![7..=7]);
+        assert_eq!(files[Path::new("Sources/say \"hi\".swift")], vec![9..=9]);
+    }
+
+    #[test]
+    fn test_changed_since_keeps_only_changed_lines() {
+        let repo = TempDir::new().unwrap();
+        let root = repo.path();
+        let lines: Vec<String> = (1..=10).map(|i| format!("let value{i} = {i}")).collect();
+        std::fs::write(root.join("Modified.swift"), lines.join("\n") + "\n").unwrap();
+        std::fs::write(root.join("Committed.swift"), lines.join("\n") + "\n").unwrap();
+        run_git(root, &["init", "-q"]);
+        run_git(root, &["add", "."]);
+        run_git(root, &["commit", "-q", "-m", "initial"]);
+
+        let mut modified = lines.clone();
+        modified[2] = "let value3 = await compute()".to_string();
+        std::fs::write(root.join("Modified.swift"), modified.join("\n") + "\n").unwrap();
+
+        let changed = changed_lines(root, "HEAD").unwrap().unwrap();
+        let warnings = vec![
+            warning(root.join("Modified.swift"), 3),
+            warning(root.join("Modified.swift"), 7),
+            warning(root.join("Committed.swift"), 3),
+            warning(PathBuf::from("Modified.swift"), 3),
+        ];
+
+        let kept = changed.retain_changed(warnings);
+        assert_eq!(kept.len(), 2);
+        assert!(kept
+            .iter()
+            .all(|w| w.line_number == 3 && w.file_path.file_name().unwrap() == "Modified.swift"));
+    }
+
+    #[test]
+    fn test_changed_since_special_file_names() {
+        let repo = TempDir::new().unwrap();
+        let root = repo.path();
+        let names = ["My View.swift", "\u{c4}nderung.swift"];
+        for name in names {
+            std::fs::write(root.join(name), "let a = 1\nlet b = 2\n").unwrap();
+        }
+        run_git(root, &["init", "-q"]);
+        run_git(root, &["-c", "core.quotepath=on", "add", "."]);
+        run_git(root, &["commit", "-q", "-m", "initial"]);
+        for name in names {
+            std::fs::write(root.join(name), "let a = 1\nlet b = await two()\n").unwrap();
+        }
+
+        let changed = changed_lines(root, "HEAD").unwrap().unwrap();
+        for name in names {
+            assert!(changed.contains(&root.join(name), 2), "{name}");
+            assert!(!changed.contains(&root.join(name), 1), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_changed_since_outside_repo() {
+        let dir = TempDir::new().unwrap();
+        assert!(changed_lines(dir.path(), "HEAD").unwrap().is_none());
+    }
+}
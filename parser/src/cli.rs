@@ -1,50 +1,582 @@
-use clap::{Parser, ValueEnum};
+use clap::parser::ValueSource;
+use clap::{ArgGroup, Args, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Serialize)]
 #[command(name = "swiftconcur")]
 #[command(about = "Parse Swift concurrency warnings from xcodebuild output")]
+#[serde(rename_all = "kebab-case")]
 pub struct Cli {
-    /// Input file (use - for stdin)
+    #[command(subcommand)]
+    #[serde(skip)]
+    pub command: Option<Command>,
+
+    /// Input file or directory of logs (use - for stdin)
     #[arg(short = 'f', long = "file", default_value = "-")]
+    #[serde(rename = "file")]
     pub input: String,
 
+    /// Read every file matching this glob (e.g. `logs/*.json`), expanded by the
+    /// tool itself rather than the shell; takes precedence over `--file`
+    #[arg(long = "input-glob", value_name = "PATTERN")]
+    pub input_glob: Option<String>,
+
+    /// Report zero warnings instead of failing when `--input-glob` matches nothing
+    #[arg(long = "allow-empty")]
+    pub allow_empty: bool,
+
+    /// Maximum number of files parsed in parallel when the input is a directory (defaults to CPU count)
+    #[arg(short = 'j', long = "jobs")]
+    pub jobs: Option<usize>,
+
     /// Output format
     #[arg(long = "format", value_enum, default_value = "json")]
     pub format: OutputFormat,
 
+    /// Write the formatted output to this file instead of stdout
+    #[arg(short = 'o', long = "output")]
+    pub output: Option<PathBuf>,
+
+    /// Comma-separated `issueType` substrings (case-insensitive) that
+    /// xcresult issues must contain to be read as warnings
+    #[arg(
+        long = "xcresult-issue-types",
+        value_name = "TYPES",
+        value_delimiter = ',',
+        default_value = "warning"
+    )]
+    pub xcresult_issue_types: Vec<String>,
+
+    /// Comma-separated fields, in order, for CSV and grep output
+    /// (file, line, column, type, severity, message, id, fix)
+    #[arg(long = "columns", value_enum, value_delimiter = ',')]
+    pub columns: Vec<Column>,
+
+    /// Show each warning's line relative to its enclosing func/init/var in
+    /// Markdown and Slack output
+    #[arg(long = "function-relative")]
+    pub function_relative: bool,
+
+    /// Show the declaration line of the type a warning names (e.g. in a Sendable
+    /// warning), when it is declared in the same file
+    #[arg(long = "include-decl")]
+    pub include_decl: bool,
+
+    /// Post only the Slack header and summary, colored by the worst severity
+    #[arg(long = "slack-summary-only")]
+    pub slack_summary_only: bool,
+
+    /// Truncate messages longer than this many characters in Markdown and Slack
+    /// output (JSON always keeps the full message)
+    #[arg(long = "max-message-len", value_name = "N")]
+    pub max_message_len: Option<usize>,
+
+    /// Append a Markdown summary to the file named by `GITHUB_STEP_SUMMARY`,
+    /// when set, for the GitHub Actions run page
+    #[arg(long = "github-summary")]
+    pub github_summary: bool,
+
+    /// Also write a reviewable shell script listing each warning's location and
+    /// suggested fix
+    #[arg(long = "emit-fix-script", value_name = "PATH")]
+    pub emit_fix_script: Option<PathBuf>,
+
+    /// Print how long each stage of the run took to stderr
+    #[arg(long = "profile")]
+    pub profile: bool,
+
+    /// Print nothing for line-oriented formats when there are no warnings,
+    /// instead of a `# swiftconcur: 0 warnings` line, and hide the scan progress
+    /// bar and the baseline delta line
+    #[arg(long = "quiet")]
+    pub quiet: bool,
+
+    /// Print a short human-readable summary to stdout
+    #[arg(long = "summary")]
+    pub summary: bool,
+
+    /// Write this run as the new baseline, only when every gate passes
+    #[arg(long = "emit-baseline-on-pass", value_name = "PATH")]
+    pub emit_baseline_on_pass: Option<PathBuf>,
+
+    /// Attach a label to every warning from this invocation (repeatable),
+    /// e.g. `--label scheme=Release`
+    #[arg(long = "label", value_name = "KEY=VALUE", value_parser = parse_label)]
+    #[serde(rename = "label")]
+    pub labels: Vec<(String, String)>,
+
+    /// TOML config file, e.g. with a `[fixes]` table of per-type
+    /// remediation templates
+    #[arg(long = "config", value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Print the effective options and `--config` tables as JSON, after
+    /// defaults and environment variables are applied, and exit
+    #[arg(long = "print-config")]
+    #[serde(skip)]
+    pub print_config: bool,
+
     /// Baseline file for comparison
     #[arg(short, long)]
     pub baseline: Option<PathBuf>,
 
+    /// Schema version of the baseline file (auto-detected by default)
+    #[arg(long = "baseline-format", value_enum, default_value = "auto")]
+    pub baseline_format: BaselineFormat,
+
     /// Fail if warnings exceed threshold
     #[arg(short, long)]
     pub threshold: Option<usize>,
 
+    /// How the warning count is compared with `--threshold`
+    #[arg(long = "threshold-op", value_enum, default_value = "le")]
+    pub threshold_op: ThresholdOp,
+
+    /// Strip trailing "; this is an error in the Swift 6 language mode" style
+    /// clauses from messages so ids stay stable across compiler versions
+    #[arg(long = "trim-message")]
+    pub trim_message: bool,
+
+    /// Fail if any warning becomes an error in the Swift 6 language mode
+    #[arg(long = "fail-on-swift6-errors")]
+    pub fail_on_swift6_errors: bool,
+
+    /// Report a parse error from the detected format's parser instead of
+    /// falling back to raw log parsing, to debug CI format mismatches
+    #[arg(long = "no-fallback")]
+    pub no_fallback: bool,
+
+    /// Stop parsing at the first warning that on its own fails `--threshold`
+    /// (e.g. `--threshold 0`) and exit 1 without writing a report
+    #[arg(
+        long = "fail-fast",
+        conflicts_with_all = ["changed_since", "seen_db", "warn_on_unknown"]
+    )]
+    pub fail_fast: bool,
+
+    /// Exit with code 3 if a non-empty input yields fewer than this many
+    /// warnings before filtering, as a canary for parser breakage
+    #[arg(long = "expect-min", value_name = "N")]
+    pub expect_min: Option<usize>,
+
+    /// Fail if warnings are spread across more than this many distinct files
+    #[arg(long = "max-affected-files")]
+    pub max_affected_files: Option<usize>,
+
+    /// How finely warning types are labeled and grouped in reports
+    #[arg(long = "taxonomy", value_enum, default_value_t = Taxonomy::Fine)]
+    pub taxonomy: Taxonomy,
+
+    /// Columns per tab stop when rendering code context and the caret under
+    /// the reported column
+    #[arg(long = "tab-width", value_name = "N", default_value_t = 4)]
+    pub tab_width: usize,
+
+    /// On warning lines longer than the window, show only this many
+    /// characters either side of the reported column, with `…` where cut
+    #[arg(long = "context-radius", value_name = "N")]
+    pub context_radius: Option<usize>,
+
+    /// Mark warnings whose file path or message matches this regex in the
+    /// markdown, slack and rtf reports (display only, nothing is filtered)
+    #[arg(long = "highlight-pattern", value_name = "REGEX")]
+    pub highlight_pattern: Option<String>,
+
+    /// Show warnings sharing an id once with an occurrence count in the
+    /// markdown, slack and rtf reports; totals still count every occurrence
+    #[arg(long = "collapse-duplicates")]
+    pub collapse_duplicates: bool,
+
+    /// Section Markdown output by this key instead of listing warnings flat
+    #[arg(long = "group-by", value_enum)]
+    pub group_by: Option<GroupBy>,
+
+    /// Split Markdown and Slack output into "Action Required" (Critical and
+    /// High) and "For Awareness" (Medium and Low) tiers, each with its count
+    #[arg(long = "tiered", conflicts_with = "group_by")]
+    pub tiered: bool,
+
+    /// Collapse duplicate warnings using this key (no deduplication by default)
+    #[arg(long = "dedup-by", value_enum)]
+    pub dedup_by: Option<DedupKey>,
+
+    /// Collapse warnings with the same file and message on lines within N of each other
+    #[arg(long = "dedup-window", value_name = "N")]
+    pub dedup_window: Option<usize>,
+
+    /// Raise the severity of warnings in any file with more than N warnings
+    /// of the same type
+    #[arg(long = "escalate-hotspots", value_name = "N")]
+    pub escalate_hotspots: Option<usize>,
+
+    /// Only keep warnings on lines changed since this git ref
+    #[arg(long = "changed-since", value_name = "REF")]
+    pub changed_since: Option<String>,
+
+    /// Globs for test sources; their warnings are lowered one severity level
+    /// and reported separately
+    #[arg(
+        long = "test-path",
+        value_name = "GLOB",
+        default_values_t = ["**/*Tests/**".to_string(), "**/Tests/**".to_string()]
+    )]
+    #[serde(rename = "test-path")]
+    pub test_paths: Vec<String>,
+
+    /// JSON file of previously reported warning ids, shared across invocations;
+    /// already-seen warnings are suppressed and new ids are appended
+    #[arg(long = "seen-db", value_name = "PATH")]
+    pub seen_db: Option<PathBuf>,
+
     /// Filter by warning type
     #[arg(short = 'F', long)]
     pub filter: Option<WarningTypeFilter>,
 
+    /// Also report uncategorized warnings that look possibly concurrency-related (at Low severity)
+    #[arg(long = "include-low-confidence")]
+    pub include_low_confidence: bool,
+
+    /// Trim trailing whitespace and normalize line endings in extracted code context
+    #[arg(long = "normalize-context")]
+    pub normalize_context: bool,
+
+    /// Keep the original input line of each warning in a `raw` field, for
+    /// auditing the normalized message. The line holds the unmapped path, so
+    /// it can't be combined with `--anonymize-paths`.
+    #[arg(long = "keep-raw", conflicts_with = "anonymize_paths")]
+    pub keep_raw: bool,
+
+    /// List warnings that matched no concurrency pattern on stderr, for pattern tuning
+    #[arg(long = "warn-on-unknown")]
+    pub warn_on_unknown: bool,
+
+    /// Also report `nonisolated(unsafe)` and `@unchecked Sendable` escape hatches
+    #[arg(long = "audit-unsafe")]
+    pub audit_unsafe: bool,
+
+    /// Also report usage of deprecated or inadvisable concurrency APIs
+    #[arg(long = "detect-deprecated")]
+    pub detect_deprecated: bool,
+
+    /// Drop warnings in dependency and generated code (DerivedData, .build, Pods, Carthage, *.generated.swift)
+    #[arg(long = "ignore-generated")]
+    pub ignore_generated: bool,
+
+    /// Add per-file warning density (warnings per 100 lines) to JSON and summary output
+    #[arg(long = "density")]
+    pub density: bool,
+
+    /// Replace each directory and file name with a salted hashed token in every
+    /// output, keeping extensions and depth, for reports shared outside the team
+    #[arg(long = "anonymize-paths")]
+    pub anonymize_paths: bool,
+
+    /// Secret salt for `--anonymize-paths` tokens. Tokens only match across runs
+    /// with the same salt; without one, every run picks a random salt.
+    #[arg(
+        long = "anonymize-salt",
+        env = "SWIFTCONCUR_ANONYMIZE_SALT",
+        value_name = "SALT",
+        hide_env_values = true
+    )]
+    #[serde(skip)]
+    pub anonymize_salt: Option<String>,
+
     /// Lines of context to show
     #[arg(short, long, default_value = "3")]
     pub context: usize,
 
+    /// Fail, listing the files, when a warning's source file can't be opened for context
+    #[arg(long = "require-context")]
+    pub require_context: bool,
+
+    /// TOML pattern pack whose `[[patterns]]` categorize messages before the
+    /// built-in English patterns, e.g. for localized compiler output
+    #[arg(long = "patterns", value_name = "FILE")]
+    pub patterns: Option<PathBuf>,
+
+    /// JSON object renaming warning types in JSON and CSV output, e.g.
+    /// `{"actor_isolation": "CONCURRENCY_ISOLATION"}`; unmapped types keep their name
+    #[arg(long = "category-map", value_name = "JSON")]
+    pub category_map: Option<PathBuf>,
+
+    /// JSON object mapping reported file paths (or directories) to the on-disk
+    /// files to read code context from, for sources generated with `#sourceLocation`
+    #[arg(long = "source-map", value_name = "JSON")]
+    pub source_map: Option<PathBuf>,
+
+    /// Strip indentation shared by all code context lines in rendered output
+    #[arg(long = "relativize-context")]
+    pub relativize_context: bool,
+
+    /// Show only the trimmed warning line, inline, instead of the surrounding
+    /// context block in markdown and rtf output, whatever `--context` is
+    #[arg(long = "collapse-context")]
+    pub collapse_context: bool,
+
     /// Enable verbose logging
     #[arg(short, long)]
     pub verbose: bool,
+
+    /// Repository URL used to build per-warning permalinks (e.g. https://github.com/org/repo)
+    #[arg(long = "repo-url")]
+    pub repo_url: Option<String>,
+
+    /// Commit SHA the warnings were produced from
+    #[arg(long = "commit", env = "GITHUB_SHA")]
+    pub commit: Option<String>,
+
+    /// Branch the warnings were produced from
+    #[arg(long = "branch", env = "GITHUB_REF_NAME")]
+    pub branch: Option<String>,
+
+    /// Test suite name for `--format junit`
+    #[arg(long = "junit-suite-name", default_value = crate::formatters::junit::DEFAULT_SUITE_NAME)]
+    pub junit_suite_name: String,
+
+    /// Repository root that absolute warning paths are made relative to (defaults to the working directory)
+    #[arg(long = "repo-root", env = "GITHUB_WORKSPACE")]
+    pub repo_root: Option<PathBuf>,
+
+    /// Character encoding of the input log
+    #[arg(long = "input-encoding", value_enum, default_value = "utf8")]
+    pub input_encoding: InputEncoding,
+
+    /// Fail instead of reporting zero warnings when the input matches no known format
+    #[arg(long = "strict-detection")]
+    pub strict_detection: bool,
+
+    /// Where each option got its value (`command line`, `env NAME` or
+    /// `default`), keyed by flag name; filled by [`Cli::parse_with_sources`]
+    #[arg(skip)]
+    #[serde(skip)]
+    pub value_sources: BTreeMap<String, String>,
+}
+
+impl Cli {
+    /// Parse the process arguments like [`Parser::parse`], also recording where
+    /// each option got its value for `--print-config`
+    pub fn parse_with_sources() -> Self {
+        let command = Self::command();
+        let matches = command.clone().get_matches();
+        let mut cli = Self::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+        cli.value_sources = command
+            .get_arguments()
+            .filter_map(|arg| {
+                let source = match matches.value_source(arg.get_id().as_str())? {
+                    ValueSource::CommandLine => "command line".to_string(),
+                    ValueSource::EnvVariable => {
+                        format!("env {}", arg.get_env()?.to_string_lossy())
+                    }
+                    _ => "default".to_string(),
+                };
+                Some((arg.get_long()?.to_string(), source))
+            })
+            .collect();
+        cli
+    }
+}
+
+fn parse_label(raw: &str) -> Result<(String, String), String> {
+    match raw.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => {
+            Ok((key.trim().to_string(), value.to_string()))
+        }
+        _ => Err(format!("expected KEY=VALUE, got '{raw}'")),
+    }
+}
+
+impl Default for Cli {
+    fn default() -> Self {
+        Self::parse_from(["swiftconcur"])
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Aggregate historical JSON reports into a warning count time series
+    Trend(TrendArgs),
+    /// Check a config file and pattern pack, optionally classifying a sample message
+    Validate(ValidateArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct TrendArgs {
+    /// Directory containing historical JSON reports
+    pub dir: PathBuf,
+
+    /// Output format for the series
+    #[arg(long = "format", value_enum, default_value = "json")]
+    pub format: TrendFormat,
+}
+
+#[derive(Args, Debug)]
+#[command(group(
+    ArgGroup::new("inputs")
+        .args(["config", "patterns", "test_message"])
+        .required(true)
+        .multiple(true)
+))]
+pub struct ValidateArgs {
+    /// TOML config file, as passed to `--config`
+    #[arg(long = "config")]
+    pub config: Option<PathBuf>,
+
+    /// TOML pattern pack, as passed to `--patterns`
+    #[arg(long = "patterns")]
+    pub patterns: Option<PathBuf>,
+
+    /// Print how this message would be classified
+    #[arg(long = "test-message", value_name = "MESSAGE")]
+    pub test_message: Option<String>,
 }
 
 #[derive(Debug, Clone, ValueEnum)]
+pub enum TrendFormat {
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Clone, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum OutputFormat {
     Json,
     Markdown,
     Slack,
+    Grep,
+    Csv,
+    Xml,
+    /// JUnit XML, one failed test case per warning
+    Junit,
+    /// Vim/Neovim quickfix list (`file:line:col: warning: message`) for `:cfile`
+    Quickfix,
+    /// Minimal RTF for pasting into documents
+    Rtf,
+    /// JSON concurrency-debt inventory: counts and worst severity per module
+    Debt,
+    /// Length-delimited protobuf (requires the `proto` feature)
+    Proto,
 }
 
-#[derive(Debug, Clone, ValueEnum)]
+#[derive(Debug, Clone, Copy, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BaselineFormat {
+    Auto,
+    V1,
+    V2,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum InputEncoding {
+    Utf8,
+    Latin1,
+}
+
+/// A warning field selectable with `--columns`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Column {
+    File,
+    Line,
+    Column,
+    Type,
+    Severity,
+    Message,
+    Id,
+    Fix,
+    Effort,
+}
+
+impl Column {
+    /// Every field, in the default CSV order
+    pub const ALL: [Column; 9] = [
+        Column::File,
+        Column::Line,
+        Column::Column,
+        Column::Type,
+        Column::Severity,
+        Column::Message,
+        Column::Id,
+        Column::Fix,
+        Column::Effort,
+    ];
+
+    /// Header name, matching the `--columns` value
+    pub fn name(self) -> &'static str {
+        match self {
+            Column::File => "file",
+            Column::Line => "line",
+            Column::Column => "column",
+            Column::Type => "type",
+            Column::Severity => "severity",
+            Column::Message => "message",
+            Column::Id => "id",
+            Column::Fix => "fix",
+            Column::Effort => "effort",
+        }
+    }
+}
+
+/// What makes two warnings the same for `--dedup-by`
+#[derive(Debug, Clone, Copy, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DedupKey {
+    /// The parser-assigned warning id
+    Id,
+    /// Normalized message text alone, ignoring file and position
+    Message,
+    /// File and normalized message, ignoring line and column
+    FileMessage,
+}
+
+/// Comparison between the warning count and `--threshold` that passes the gate
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThresholdOp {
+    /// Pass when count <= threshold (`--threshold 0` allows zero warnings)
+    #[default]
+    Le,
+    /// Pass when count < threshold
+    Lt,
+}
+
+impl ThresholdOp {
+    pub fn passes(self, count: usize, threshold: usize) -> bool {
+        match self {
+            ThresholdOp::Le => count <= threshold,
+            ThresholdOp::Lt => count < threshold,
+        }
+    }
+}
+
+/// How `--group-by` sections the warnings in Markdown output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GroupBy {
+    /// One section per severity, most severe first
+    Severity,
+}
+
+/// Warning type granularity for `--taxonomy`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Taxonomy {
+    /// Label warnings by their detected type
+    #[default]
+    Fine,
+    /// Label and count warnings in isolation, sendability and data race buckets
+    Coarse,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum WarningTypeFilter {
     ActorIsolation,
     Sendable,
     DataRace,
     Performance,
+    Deprecated,
 }
@@ -0,0 +1,215 @@
+use crate::error::{ParseError, Result};
+use crate::models::{Effort, Warning, WarningType};
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+/// Settings read from the `--config` TOML file
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    #[serde(default)]
+    pub fixes: FixTemplates,
+    #[serde(default)]
+    pub docs: DocLinks,
+    #[serde(default)]
+    pub effort: EffortMap,
+}
+
+/// Per-type `suggested_fix` templates from the `[fixes]` table. `{message}`
+/// expands to the warning's message; types without a template keep the
+/// suggestion from [`builtin_fix`](crate::parser::patterns::builtin_fix).
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct FixTemplates(#[serde(serialize_with = "sorted")] HashMap<WarningType, String>);
+
+impl FixTemplates {
+    pub fn set(&mut self, warning_type: WarningType, template: impl Into<String>) {
+        self.0.insert(warning_type, template.into());
+    }
+
+    /// The rendered template for `warning_type`, if one is configured
+    pub fn render(&self, warning_type: WarningType, message: &str) -> Option<String> {
+        self.0
+            .get(&warning_type)
+            .map(|template| template.replace("{message}", message))
+    }
+
+    pub fn apply(&self, mut warnings: Vec<Warning>) -> Vec<Warning> {
+        for warning in &mut warnings {
+            if let Some(fix) = self.render(warning.warning_type, &warning.message) {
+                warning.suggested_fix = Some(fix);
+            }
+        }
+        warnings
+    }
+}
+
+/// Per-type "Learn more" links from the `[docs]` table, overriding the built-in
+/// [`WarningType::docs_url`]
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct DocLinks(#[serde(serialize_with = "sorted")] HashMap<WarningType, String>);
+
+impl DocLinks {
+    pub fn set(&mut self, warning_type: WarningType, url: impl Into<String>) {
+        self.0.insert(warning_type, url.into());
+    }
+
+    /// The configured link for `warning_type`, falling back to the built-in one
+    pub fn url(&self, warning_type: WarningType) -> Option<&str> {
+        self.0
+            .get(&warning_type)
+            .map(String::as_str)
+            .or_else(|| warning_type.docs_url())
+    }
+}
+
+/// Per-type remediation effort from the `[effort]` table, overriding
+/// [`WarningType::default_effort`]
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct EffortMap(#[serde(serialize_with = "sorted")] HashMap<WarningType, Effort>);
+
+impl EffortMap {
+    pub fn set(&mut self, warning_type: WarningType, effort: Effort) {
+        self.0.insert(warning_type, effort);
+    }
+
+    /// The configured effort for `warning_type`, falling back to the default
+    pub fn effort(&self, warning_type: WarningType) -> Effort {
+        self.0
+            .get(&warning_type)
+            .copied()
+            .unwrap_or_else(|| warning_type.default_effort())
+    }
+
+    pub fn apply(&self, mut warnings: Vec<Warning>) -> Vec<Warning> {
+        for warning in &mut warnings {
+            warning.effort = Some(self.effort(warning.warning_type));
+        }
+        warnings
+    }
+}
+
+/// Serialize a per-type table in a stable order, for `--print-config`
+fn sorted<V: Serialize, S: Serializer>(
+    map: &HashMap<WarningType, V>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    let sorted: BTreeMap<&str, &V> = map.iter().map(|(k, v)| (k.as_str(), v)).collect();
+    sorted.serialize(serializer)
+}
+
+pub fn load_config(path: &Path) -> Result<Config> {
+    let content = std::fs::read_to_string(path)?;
+    toml::from_str(&content)
+        .map_err(|e| ParseError::ConfigError(format!("{}: {e}", path.display())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn warning(warning_type: WarningType, message: &str) -> Warning {
+        Warning {
+            id: "a".to_string(),
+            warning_type,
+            message: message.to_string(),
+            suggested_fix: Some("built-in".to_string()),
+            ..Warning::fixture("/project/File.swift", 1)
+        }
+    }
+
+    #[test]
+    fn test_fix_templates_from_toml() {
+        let config: Config = toml::from_str(
+            r#"
+[fixes]
+actor_isolation = "See https://wiki.example.com/actor-isolation"
+sendable_conformance = "Fix '{message}' per https://wiki.example.com/sendable"
+"#,
+        )
+        .unwrap();
+
+        let warnings = config.fixes.apply(vec![
+            warning(
+                WarningType::ActorIsolation,
+                "actor-isolated property 'state' can not be referenced",
+            ),
+            warning(
+                WarningType::SendableConformance,
+                "Type 'Cache' does not conform to the 'Sendable' protocol",
+            ),
+            warning(WarningType::DataRace, "data race detected"),
+        ]);
+
+        assert_eq!(
+            warnings[0].suggested_fix.as_deref(),
+            Some("See https://wiki.example.com/actor-isolation")
+        );
+        assert_eq!(
+            warnings[1].suggested_fix.as_deref(),
+            Some("Fix 'Type 'Cache' does not conform to the 'Sendable' protocol' per https://wiki.example.com/sendable")
+        );
+        assert_eq!(warnings[2].suggested_fix.as_deref(), Some("built-in"));
+    }
+
+    #[test]
+    fn test_unknown_fix_type_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("swiftconcur.toml");
+        std::fs::write(&path, "[fixes]\nactor = \"typo\"\n").unwrap();
+
+        assert!(matches!(
+            load_config(&path),
+            Err(ParseError::ConfigError(_))
+        ));
+    }
+
+    #[test]
+    fn test_doc_links_override_built_in() {
+        let config: Config = toml::from_str(
+            r#"
+[docs]
+data_race = "https://wiki.example.com/races"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.docs.url(WarningType::DataRace),
+            Some("https://wiki.example.com/races")
+        );
+        assert_eq!(
+            config.docs.url(WarningType::ActorIsolation),
+            WarningType::ActorIsolation.docs_url()
+        );
+        assert_eq!(config.docs.url(WarningType::Unknown), None);
+    }
+
+    #[test]
+    fn test_effort_overrides_default() {
+        let config: Config = toml::from_str(
+            r#"
+[effort]
+actor_isolation = "high"
+"#,
+        )
+        .unwrap();
+
+        let warnings = config.effort.apply(vec![
+            warning(WarningType::ActorIsolation, "actor-isolated property"),
+            warning(WarningType::SendableConformance, "does not conform"),
+        ]);
+        assert_eq!(warnings[0].effort, Some(Effort::High));
+        assert_eq!(warnings[1].effort, Some(Effort::Low));
+
+        let err = toml::from_str::<Config>(
+            "[effort]
+data_race = \"huge\"\n",
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("huge"), "{err}");
+    }
+}
@@ -0,0 +1,45 @@
+use crate::cli::InputEncoding;
+use crate::error::{ParseError, Result};
+
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Decode raw input bytes to a string, stripping any UTF-8 byte order mark
+pub fn decode_input(bytes: &[u8], encoding: InputEncoding) -> Result<String> {
+    let bytes = bytes.strip_prefix(UTF8_BOM).unwrap_or(bytes);
+
+    match encoding {
+        InputEncoding::Utf8 => String::from_utf8(bytes.to_vec()).map_err(|_| {
+            ParseError::InvalidFormat(
+                "input is not valid UTF-8 (try --input-encoding latin1)".to_string(),
+            )
+        }),
+        InputEncoding::Latin1 => {
+            // encoding_rs follows the WHATWG spec, which maps ISO-8859-1 to windows-1252
+            let (decoded, _) = encoding_rs::WINDOWS_1252.decode_without_bom_handling(bytes);
+            Ok(decoded.into_owned())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strips_utf8_bom() {
+        let decoded = decode_input(b"\xEF\xBB\xBFhello", InputEncoding::Utf8).unwrap();
+        assert_eq!(decoded, "hello");
+    }
+
+    #[test]
+    fn test_decodes_latin1() {
+        let decoded = decode_input(b"caf\xE9", InputEncoding::Latin1).unwrap();
+        assert_eq!(decoded, "café");
+    }
+
+    #[test]
+    fn test_rejects_invalid_utf8() {
+        let result = decode_input(b"caf\xE9", InputEncoding::Utf8);
+        assert!(matches!(result, Err(ParseError::InvalidFormat(_))));
+    }
+}
@@ -8,6 +8,9 @@ pub enum ParseError {
     #[error("Failed to parse JSON: {0}")]
     JsonError(#[from] serde_json::Error),
 
+    #[error("Failed to write XML: {0}")]
+    XmlError(#[from] quick_xml::SeError),
+
     #[error("No warnings found in input")]
     NoWarnings,
 
@@ -16,6 +19,15 @@ pub enum ParseError {
 
     #[error("Baseline comparison failed: {0}")]
     BaselineError(String),
+
+    #[error("Git error: {0}")]
+    GitError(String),
+
+    #[error("Invalid config: {0}")]
+    ConfigError(String),
+
+    #[error("Source files not readable for code context (check the working directory): {0}")]
+    MissingSources(String),
 }
 
 pub type Result<T> = std::result::Result<T, ParseError>;
@@ -0,0 +1,77 @@
+use crate::error::{ParseError, Result};
+use crate::models::WarningType;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Names to report warning types under, for dashboards with their own
+/// taxonomy. Only serialized output changes; filtering, gating and the other
+/// formatters keep the built-in types. Unmapped types keep their own name.
+#[derive(Debug, Clone, Default)]
+pub struct CategoryMap {
+    names: Arc<HashMap<WarningType, String>>,
+}
+
+impl CategoryMap {
+    pub fn new(names: HashMap<WarningType, String>) -> Self {
+        Self {
+            names: Arc::new(names),
+        }
+    }
+
+    /// Load a JSON object of `"actor_isolation": "CONCURRENCY_ISOLATION"` pairs
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let names: HashMap<WarningType, String> = serde_json::from_str(&content).map_err(|e| {
+            ParseError::InvalidFormat(format!("category map {}: {e}", path.display()))
+        })?;
+        Ok(Self::new(names))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// The reported name for `warning_type`
+    pub fn name(&self, warning_type: WarningType) -> &str {
+        self.names
+            .get(&warning_type)
+            .map_or_else(|| warning_type.as_str(), String::as_str)
+    }
+
+    /// Rewrite the `warning_type` of each warning in a serialized run
+    pub(crate) fn remap_run(&self, run: &mut Value) {
+        let Some(warnings) = run.get_mut("warnings").and_then(Value::as_array_mut) else {
+            return;
+        };
+        for warning in warnings {
+            let Some(field) = warning.get_mut("warning_type") else {
+                continue;
+            };
+            if let Ok(warning_type) = serde_json::from_value::<WarningType>(field.clone()) {
+                *field = Value::String(self.name(warning_type).to_string());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_rejects_unknown_type() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("categories.json");
+        std::fs::write(&path, r#"{"actor_isolaton": "ISOLATION"}"#).unwrap();
+
+        let err = CategoryMap::load(&path).unwrap_err();
+        assert!(err.to_string().contains("category map"), "{err}");
+
+        std::fs::write(&path, r#"{"actor_isolation": "ISOLATION"}"#).unwrap();
+        let map = CategoryMap::load(&path).unwrap();
+        assert_eq!(map.name(WarningType::ActorIsolation), "ISOLATION");
+        assert_eq!(map.name(WarningType::DataRace), "data_race");
+    }
+}
@@ -0,0 +1,110 @@
+use crate::cli::Column;
+use crate::error::Result;
+use crate::formatters::{column_value, CategoryMap, Formatter};
+use crate::models::WarningRun;
+
+// Quote a field when it contains a delimiter, quote or line break (RFC 4180)
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// A header row followed by one row per warning
+pub struct CsvFormatter {
+    columns: Vec<Column>,
+    categories: CategoryMap,
+}
+
+impl Default for CsvFormatter {
+    fn default() -> Self {
+        Self {
+            columns: Column::ALL.to_vec(),
+            categories: CategoryMap::default(),
+        }
+    }
+}
+
+impl CsvFormatter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Select and order the emitted fields; an empty list keeps all of them
+    pub fn with_columns(mut self, columns: &[Column]) -> Self {
+        if !columns.is_empty() {
+            self.columns = columns.to_vec();
+        }
+        self
+    }
+
+    /// Report warning types under the names in `categories`
+    pub fn with_category_map(mut self, categories: CategoryMap) -> Self {
+        self.categories = categories;
+        self
+    }
+}
+
+impl Formatter for CsvFormatter {
+    fn format(&self, run: &WarningRun) -> Result<String> {
+        let header: Vec<&str> = self.columns.iter().map(|c| c.name()).collect();
+        let mut rows = vec![header.join(",")];
+
+        for warning in run {
+            let row: Vec<String> = self
+                .columns
+                .iter()
+                .map(|column| match column {
+                    Column::Type => escape(self.categories.name(warning.warning_type)),
+                    _ => escape(&column_value(*column, warning)),
+                })
+                .collect();
+            rows.push(row.join(","));
+        }
+
+        Ok(rows.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Effort, Warning, WarningType};
+
+    fn run() -> WarningRun {
+        WarningRun::new(vec![Warning {
+            id: "/src/Box.swift:12:40".to_string(),
+            warning_type: WarningType::SendableConformance,
+            message: "Type 'Box<K, V>' does not conform to the \"Sendable\" protocol".to_string(),
+            effort: Some(Effort::Low),
+            ..Warning::fixture("/src/Box.swift", 12)
+        }])
+    }
+
+    #[test]
+    fn test_default_columns() {
+        let output = CsvFormatter::new().format(&run()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(
+            lines[0],
+            "file,line,column,type,severity,message,id,fix,effort"
+        );
+        assert_eq!(
+            lines[1],
+            "/src/Box.swift,12,,sendable_conformance,high,\"Type 'Box<K, V>' does not conform to the \"\"Sendable\"\" protocol\",/src/Box.swift:12:40,,low"
+        );
+    }
+
+    #[test]
+    fn test_custom_column_subset_and_order() {
+        let output = CsvFormatter::new()
+            .with_columns(&[Column::Severity, Column::File, Column::Line])
+            .format(&run())
+            .unwrap();
+
+        assert_eq!(output, "severity,file,line\nhigh,/src/Box.swift,12");
+    }
+}
@@ -0,0 +1,194 @@
+use crate::error::Result;
+use crate::formatters::Formatter;
+use crate::models::{Severity, Warning, WarningRun};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+
+/// Component for warnings whose path has no directory to derive one from
+pub const ROOT_COMPONENT: &str = "(root)";
+
+/// Concurrency debt rolled up per component, for technical-debt inventories
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DebtReport {
+    pub commit: Option<String>,
+    pub total_warnings: usize,
+    /// Sorted by name
+    pub components: Vec<ComponentDebt>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ComponentDebt {
+    pub name: String,
+    pub warnings: usize,
+    pub worst_severity: Severity,
+    pub by_severity: BTreeMap<String, usize>,
+    pub by_type: BTreeMap<String, usize>,
+}
+
+/// JSON debt inventory keyed by component. A component is the module
+/// directory under `Sources/` or `Tests/` (the SwiftPM layout), otherwise the
+/// top-level directory of the path relative to the repository root.
+pub struct DebtFormatter {
+    root: PathBuf,
+}
+
+impl DebtFormatter {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn component(&self, path: &Path) -> String {
+        let relative = path.strip_prefix(&self.root).unwrap_or(path);
+        let dirs: Vec<&str> = relative
+            .parent()
+            .into_iter()
+            .flat_map(Path::components)
+            .filter_map(|component| match component {
+                Component::Normal(name) => name.to_str(),
+                _ => None,
+            })
+            .collect();
+
+        let module = dirs
+            .iter()
+            .position(|dir| *dir == "Sources" || *dir == "Tests")
+            .and_then(|i| dirs.get(i + 1));
+        module
+            .or(dirs.first())
+            .map_or_else(|| ROOT_COMPONENT.to_string(), |dir| dir.to_string())
+    }
+
+    pub fn report(&self, run: &WarningRun) -> DebtReport {
+        let mut by_component: BTreeMap<String, Vec<&Warning>> = BTreeMap::new();
+        for warning in run.iter() {
+            by_component
+                .entry(self.component(&warning.file_path))
+                .or_default()
+                .push(warning);
+        }
+
+        let components = by_component
+            .into_iter()
+            .filter_map(|(name, warnings)| {
+                let worst_severity = Severity::ALL
+                    .into_iter()
+                    .find(|severity| warnings.iter().any(|w| w.severity == *severity))?;
+                let mut by_severity = BTreeMap::new();
+                let mut by_type = BTreeMap::new();
+                for warning in &warnings {
+                    *by_severity
+                        .entry(warning.severity.as_str().to_string())
+                        .or_insert(0) += 1;
+                    *by_type
+                        .entry(warning.warning_type.as_str().to_string())
+                        .or_insert(0) += 1;
+                }
+                Some(ComponentDebt {
+                    name,
+                    warnings: warnings.len(),
+                    worst_severity,
+                    by_severity,
+                    by_type,
+                })
+            })
+            .collect();
+
+        DebtReport {
+            commit: run.commit_sha.clone(),
+            total_warnings: run.total_warnings,
+            components,
+        }
+    }
+}
+
+impl Formatter for DebtFormatter {
+    fn format(&self, run: &WarningRun) -> Result<String> {
+        Ok(serde_json::to_string_pretty(&self.report(run))?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::WarningType;
+
+    fn warning(path: &str, warning_type: WarningType, severity: Severity) -> Warning {
+        Warning {
+            id: format!("{path}:{}", warning_type.as_str()),
+            warning_type,
+            severity,
+            ..Warning::fixture(path, 1)
+        }
+    }
+
+    #[test]
+    fn test_rollup_per_module() {
+        let run = WarningRun::new(vec![
+            warning(
+                "/repo/Sources/Networking/Client.swift",
+                WarningType::SendableConformance,
+                Severity::Medium,
+            ),
+            warning(
+                "/repo/Sources/Storage/Cache.swift",
+                WarningType::ActorIsolation,
+                Severity::High,
+            ),
+            warning(
+                "/repo/Sources/Networking/Session/Pool.swift",
+                WarningType::DataRace,
+                Severity::Critical,
+            ),
+            warning(
+                "/repo/Sources/Networking/Client.swift",
+                WarningType::SendableConformance,
+                Severity::Medium,
+            ),
+        ]);
+
+        let report = DebtFormatter::new("/repo").report(&run);
+        assert_eq!(report.total_warnings, 4);
+        let names: Vec<&str> = report.components.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["Networking", "Storage"]);
+
+        let networking = &report.components[0];
+        assert_eq!(networking.warnings, 3);
+        assert_eq!(networking.worst_severity, Severity::Critical);
+        assert_eq!(networking.by_severity["medium"], 2);
+        assert_eq!(networking.by_severity["critical"], 1);
+        assert_eq!(networking.by_type["sendable_conformance"], 2);
+        assert_eq!(networking.by_type["data_race"], 1);
+
+        let storage = &report.components[1];
+        assert_eq!(storage.warnings, 1);
+        assert_eq!(storage.worst_severity, Severity::High);
+        assert_eq!(storage.by_type["actor_isolation"], 1);
+
+        let json: serde_json::Value =
+            serde_json::from_str(&DebtFormatter::new("/repo").format(&run).unwrap()).unwrap();
+        assert_eq!(json["components"][0]["worst_severity"], "critical");
+    }
+
+    #[test]
+    fn test_component_outside_swiftpm_layout() {
+        let formatter = DebtFormatter::new("/repo");
+        assert_eq!(
+            formatter.component(Path::new("/repo/App/Views/Home.swift")),
+            "App"
+        );
+        assert_eq!(
+            formatter.component(Path::new("/repo/Tests/AppTests/HomeTests.swift")),
+            "AppTests"
+        );
+        assert_eq!(
+            formatter.component(Path::new("/repo/Main.swift")),
+            ROOT_COMPONENT
+        );
+        // Paths outside the root keep their own top-level directory
+        assert_eq!(
+            formatter.component(Path::new("/elsewhere/Kit/File.swift")),
+            "elsewhere"
+        );
+    }
+}
@@ -0,0 +1,112 @@
+use crate::models::WarningRun;
+
+// Quote `value` for a POSIX shell
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+// `value` on one line, so log text can't escape the `#` comment it's written into
+fn comment_text(value: &str) -> String {
+    value.replace(['\r', '\n'], " ")
+}
+
+/// A reviewable shell script with one commented TODO per warning. Parsed
+/// compiler fix-its are not available, so no edit is applied automatically;
+/// each entry opens the location in `$EDITOR` once uncommented.
+pub fn format_fix_script(run: &WarningRun) -> String {
+    let mut output = String::from("#!/bin/sh\n");
+    output.push_str(
+        "# Generated by swiftconcur. Review each TODO, then uncomment the edits to apply.\n",
+    );
+    output.push_str(&format!(
+        "# {} warning{}\n",
+        run.total_warnings,
+        if run.total_warnings == 1 { "" } else { "s" }
+    ));
+    output.push_str("set -e\n");
+
+    for warning in run {
+        let file = comment_text(&warning.file_path.display().to_string());
+        output.push_str(&format!(
+            "\n# {}:{} [{}/{}]\n",
+            file,
+            warning.line_number,
+            warning.warning_type.as_str(),
+            warning.severity.as_str()
+        ));
+        output.push_str(&format!("# {}\n", comment_text(&warning.message)));
+        match &warning.suggested_fix {
+            Some(fix) => output.push_str(&format!("# TODO: {}\n", comment_text(fix))),
+            None => output.push_str("# TODO: review manually; no suggested fix\n"),
+        }
+        output.push_str(&format!(
+            "# \"${{EDITOR:-vi}}\" +{} {}\n",
+            warning.line_number,
+            shell_quote(&file)
+        ));
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Warning;
+
+    fn warning(file: &str, line: usize, suggested_fix: Option<&str>) -> Warning {
+        Warning {
+            column_number: Some(5),
+            message: "actor-isolated property 'state' can not be mutated".to_string(),
+            suggested_fix: suggested_fix.map(str::to_string),
+            ..Warning::fixture(file, line)
+        }
+    }
+
+    #[test]
+    fn test_fix_script_references_each_location() {
+        let run = WarningRun::new(vec![
+            warning(
+                "/project/Sources/Counter.swift",
+                37,
+                Some("Consider using 'await' or @MainActor to safely mutate the actor-isolated property."),
+            ),
+            warning("/project/Sources/Owner's Model.swift", 12, None),
+        ]);
+
+        let script = format_fix_script(&run);
+
+        assert!(script.starts_with("#!/bin/sh\n"));
+        assert!(script.contains("# /project/Sources/Counter.swift:37 [actor_isolation/high]\n"));
+        assert!(script.contains("# TODO: Consider using 'await' or @MainActor"));
+        assert!(script.contains("+37 '/project/Sources/Counter.swift'"));
+        assert!(
+            script.contains("# /project/Sources/Owner's Model.swift:12 [actor_isolation/high]\n")
+        );
+        assert!(script.contains("# TODO: review manually; no suggested fix\n"));
+        assert!(script.contains(r"+12 '/project/Sources/Owner'\''s Model.swift'"));
+    }
+
+    #[test]
+    fn test_fix_script_keeps_log_text_commented() {
+        let mut injected = warning(
+            "/project/Sources/Store\n.swift",
+            3,
+            Some("Use an actor.\r\nrm -rf /tmp/y"),
+        );
+        injected.message = "data race detected in shared state\nrm -rf /tmp/x".to_string();
+        let run = WarningRun::new(vec![injected]);
+
+        let script = format_fix_script(&run);
+
+        for line in script.lines().skip(1) {
+            assert!(
+                line.is_empty() || line.starts_with('#') || line == "set -e",
+                "uncommented line: {line}"
+            );
+        }
+        assert!(script.contains("# data race detected in shared state rm -rf /tmp/x\n"));
+        assert!(script.contains("# TODO: Use an actor.  rm -rf /tmp/y\n"));
+        assert!(script.contains("+3 '/project/Sources/Store .swift'"));
+    }
+}
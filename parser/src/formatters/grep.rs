@@ -0,0 +1,151 @@
+use crate::cli::Column;
+use crate::error::Result;
+use crate::formatters::{column_value, Formatter};
+use crate::models::WarningRun;
+
+/// Printed instead of an empty report, so consumers can tell a clean run
+/// from a tool that printed nothing
+pub const NO_WARNINGS_SENTINEL: &str = "# swiftconcur: 0 warnings";
+
+/// One line per warning: `file:line:col: [type/severity] message`, or the
+/// selected columns joined with `:`
+#[derive(Default)]
+pub struct GrepFormatter {
+    columns: Vec<Column>,
+    quiet: bool,
+}
+
+impl GrepFormatter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace the default line shape with these fields; an empty list keeps it
+    pub fn with_columns(mut self, columns: &[Column]) -> Self {
+        self.columns = columns.to_vec();
+        self
+    }
+
+    /// Print nothing, rather than the sentinel line, for runs without warnings
+    pub fn quiet(mut self, quiet: bool) -> Self {
+        self.quiet = quiet;
+        self
+    }
+}
+
+impl Formatter for GrepFormatter {
+    fn format(&self, run: &WarningRun) -> Result<String> {
+        if run.warnings.is_empty() && !self.quiet {
+            return Ok(NO_WARNINGS_SENTINEL.to_string());
+        }
+
+        let mut warnings: Vec<_> = run.warnings.iter().collect();
+        warnings.sort_by(|a, b| {
+            a.file_path
+                .cmp(&b.file_path)
+                .then(a.line_number.cmp(&b.line_number))
+                .then(a.column_number.cmp(&b.column_number))
+        });
+
+        let lines: Vec<String> = warnings
+            .iter()
+            .map(|warning| {
+                if !self.columns.is_empty() {
+                    let fields: Vec<String> = self
+                        .columns
+                        .iter()
+                        .map(|column| column_value(*column, warning))
+                        .collect();
+                    return fields.join(":");
+                }
+
+                let location = match warning.column_number {
+                    Some(column) => format!(
+                        "{}:{}:{}",
+                        warning.file_path.display(),
+                        warning.line_number,
+                        column
+                    ),
+                    None => format!("{}:{}", warning.file_path.display(), warning.line_number),
+                };
+                format!(
+                    "{}: [{}/{}] {}",
+                    location,
+                    warning.warning_type.as_str(),
+                    warning.severity.as_str(),
+                    warning.message
+                )
+            })
+            .collect();
+
+        Ok(lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Warning;
+
+    fn warning(file: &str, line: usize, column: Option<usize>) -> Warning {
+        Warning {
+            column_number: column,
+            message: "actor-isolated property 'shared' can not be referenced".to_string(),
+            ..Warning::fixture(file, line)
+        }
+    }
+
+    #[test]
+    fn test_grep_line_shape_and_order() {
+        let run = WarningRun::new(vec![
+            warning("/src/B.swift", 3, Some(7)),
+            warning("/src/A.swift", 20, None),
+            warning("/src/A.swift", 4, Some(1)),
+        ]);
+
+        let output = GrepFormatter::new().format(&run).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(
+            lines,
+            vec![
+                "/src/A.swift:4:1: [actor_isolation/high] actor-isolated property 'shared' can not be referenced",
+                "/src/A.swift:20: [actor_isolation/high] actor-isolated property 'shared' can not be referenced",
+                "/src/B.swift:3:7: [actor_isolation/high] actor-isolated property 'shared' can not be referenced",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_grep_custom_columns() {
+        let run = WarningRun::new(vec![
+            warning("/src/B.swift", 3, Some(7)),
+            warning("/src/A.swift", 4, Some(1)),
+        ]);
+
+        let output = GrepFormatter::new()
+            .with_columns(&[Column::Line, Column::File, Column::Severity])
+            .format(&run)
+            .unwrap();
+
+        assert_eq!(output, "4:/src/A.swift:high\n3:/src/B.swift:high");
+    }
+
+    #[test]
+    fn test_grep_sentinel_for_empty_run() {
+        let run = WarningRun::new(Vec::new());
+
+        assert_eq!(
+            GrepFormatter::new().format(&run).unwrap(),
+            NO_WARNINGS_SENTINEL
+        );
+        assert_eq!(
+            GrepFormatter::new()
+                .with_columns(&[Column::File, Column::Line])
+                .format(&run)
+                .unwrap(),
+            NO_WARNINGS_SENTINEL
+        );
+        assert_eq!(GrepFormatter::new().quiet(true).format(&run).unwrap(), "");
+    }
+}
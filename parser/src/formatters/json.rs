@@ -1,18 +1,73 @@
-use crate::error::Result;
-use crate::formatters::Formatter;
+use crate::error::{ParseError, Result};
+use crate::formatters::{CategoryMap, Formatter};
 use crate::models::WarningRun;
+use std::io::Write;
 
 #[derive(Default)]
-pub struct JsonFormatter;
+pub struct JsonFormatter {
+    categories: CategoryMap,
+}
 
 impl JsonFormatter {
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    /// Report warning types under the names in `categories`
+    pub fn with_category_map(mut self, categories: CategoryMap) -> Self {
+        self.categories = categories;
+        self
+    }
+
+    fn remapped(&self, run: &WarningRun) -> Result<serde_json::Value> {
+        let mut value = serde_json::to_value(run)?;
+        self.categories.remap_run(&mut value);
+        Ok(value)
     }
 }
 
 impl Formatter for JsonFormatter {
     fn format(&self, run: &WarningRun) -> Result<String> {
-        Ok(serde_json::to_string_pretty(run)?)
+        if self.categories.is_empty() {
+            return Ok(serde_json::to_string_pretty(run)?);
+        }
+        Ok(serde_json::to_string_pretty(&self.remapped(run)?)?)
+    }
+
+    /// Serializes straight to `writer`, one warning at a time, unless types
+    /// are remapped
+    fn write_to(&self, run: &WarningRun, writer: &mut dyn Write) -> Result<()> {
+        let written = if self.categories.is_empty() {
+            serde_json::to_writer_pretty(writer, run)
+        } else {
+            serde_json::to_writer_pretty(writer, &self.remapped(run)?)
+        };
+        written.map_err(|e| {
+            if e.is_io() {
+                ParseError::IoError(e.into())
+            } else {
+                e.into()
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_streamed_output_matches_buffered() {
+        let warnings =
+            crate::parse_str(include_str!("../../tests/fixtures/mixed_warnings.json"), 0).unwrap();
+        let mut run = WarningRun::new(warnings);
+        run.commit_sha = Some("abc123".to_string());
+        assert!(run.total_warnings > 1);
+
+        let mut streamed = Vec::new();
+        JsonFormatter::new().write_to(&run, &mut streamed).unwrap();
+
+        let buffered = JsonFormatter::new().format(&run).unwrap();
+        assert_eq!(streamed, buffered.into_bytes());
     }
 }
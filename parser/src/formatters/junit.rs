@@ -0,0 +1,234 @@
+use crate::error::Result;
+use crate::formatters::Formatter;
+use crate::models::WarningRun;
+use serde::{Deserialize, Serialize};
+
+/// Suite name used when none is configured
+pub const DEFAULT_SUITE_NAME: &str = "SwiftConcur";
+
+/// Root `<testsuites>` element of the JUnit report
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename = "testsuites")]
+pub struct JunitReport {
+    #[serde(rename = "@tests")]
+    pub tests: usize,
+    #[serde(rename = "@failures")]
+    pub failures: usize,
+    #[serde(rename = "@time")]
+    pub time: String,
+    pub testsuite: JunitSuite,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct JunitSuite {
+    #[serde(rename = "@name")]
+    pub name: String,
+    #[serde(rename = "@tests")]
+    pub tests: usize,
+    #[serde(rename = "@failures")]
+    pub failures: usize,
+    #[serde(rename = "@errors")]
+    pub errors: usize,
+    #[serde(rename = "@skipped")]
+    pub skipped: usize,
+    #[serde(rename = "@time")]
+    pub time: String,
+    #[serde(rename = "@timestamp")]
+    pub timestamp: String,
+    pub properties: JunitProperties,
+    #[serde(rename = "testcase", default)]
+    pub testcases: Vec<JunitTestCase>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct JunitProperties {
+    #[serde(rename = "property", default)]
+    pub properties: Vec<JunitProperty>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct JunitProperty {
+    #[serde(rename = "@name")]
+    pub name: String,
+    #[serde(rename = "@value")]
+    pub value: String,
+}
+
+/// One warning, reported as a failed test case
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct JunitTestCase {
+    #[serde(rename = "@name")]
+    pub name: String,
+    #[serde(rename = "@classname")]
+    pub classname: String,
+    #[serde(rename = "@time")]
+    pub time: String,
+    pub failure: JunitFailure,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct JunitFailure {
+    #[serde(rename = "@message")]
+    pub message: String,
+    #[serde(rename = "@type")]
+    pub failure_type: String,
+    #[serde(rename = "$text")]
+    pub text: String,
+}
+
+pub struct JunitFormatter {
+    suite_name: String,
+}
+
+impl Default for JunitFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JunitFormatter {
+    pub fn new() -> Self {
+        Self {
+            suite_name: DEFAULT_SUITE_NAME.to_string(),
+        }
+    }
+
+    pub fn with_suite_name(mut self, name: impl Into<String>) -> Self {
+        self.suite_name = name.into();
+        self
+    }
+}
+
+impl Formatter for JunitFormatter {
+    fn format(&self, run: &WarningRun) -> Result<String> {
+        // Parsing is not timed per warning; strict consumers only need the attribute
+        let time = "0".to_string();
+        let properties = [("commit", &run.commit_sha), ("branch", &run.branch)]
+            .into_iter()
+            .filter_map(|(name, value)| {
+                value.as_ref().map(|value| JunitProperty {
+                    name: name.to_string(),
+                    value: value.clone(),
+                })
+            })
+            .collect();
+        let testcases: Vec<JunitTestCase> = run
+            .iter()
+            .map(|warning| {
+                let location = match warning.column_number {
+                    Some(column) => format!(
+                        "{}:{}:{column}",
+                        warning.file_path.display(),
+                        warning.line_number
+                    ),
+                    None => format!("{}:{}", warning.file_path.display(), warning.line_number),
+                };
+                JunitTestCase {
+                    name: format!("{} at {location}", warning.warning_type.as_str()),
+                    classname: warning.file_path.display().to_string(),
+                    time: time.clone(),
+                    failure: JunitFailure {
+                        message: warning.message.clone(),
+                        failure_type: warning.warning_type.as_str().to_string(),
+                        text: format!(
+                            "[{}] {location}: {}",
+                            warning.severity.as_str(),
+                            warning.message
+                        ),
+                    },
+                }
+            })
+            .collect();
+
+        let report = JunitReport {
+            tests: testcases.len(),
+            failures: testcases.len(),
+            time: time.clone(),
+            testsuite: JunitSuite {
+                name: self.suite_name.clone(),
+                tests: testcases.len(),
+                failures: testcases.len(),
+                errors: 0,
+                skipped: 0,
+                time,
+                timestamp: run.created_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
+                properties: JunitProperties { properties },
+                testcases,
+            },
+        };
+
+        let mut output = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let mut serializer = quick_xml::se::Serializer::new(&mut output);
+        serializer.indent(' ', 2);
+        report.serialize(serializer)?;
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Severity, Warning, WarningType};
+
+    #[test]
+    fn test_junit_suite_name_and_properties() {
+        let mut run = WarningRun::new(vec![Warning {
+            id: "a".to_string(),
+            warning_type: WarningType::DataRace,
+            severity: Severity::Critical,
+            column_number: Some(10),
+            message: "data race detected in <shared> state".to_string(),
+            ..Warning::fixture("/src/Queue.swift", 95)
+        }]);
+        run.commit_sha = Some("abc123".to_string());
+        run.branch = Some("main".to_string());
+
+        let xml = JunitFormatter::new()
+            .with_suite_name("Concurrency")
+            .format(&run)
+            .unwrap();
+        assert!(xml.contains("data race detected in &lt;shared&gt; state"));
+
+        let body = xml.split_once("?>").unwrap().1;
+        let report: JunitReport = quick_xml::de::from_str(body).unwrap();
+        let suite = &report.testsuite;
+
+        assert_eq!(report.tests, 1);
+        assert_eq!(suite.name, "Concurrency");
+        assert_eq!(suite.failures, 1);
+        assert_eq!(suite.time, "0");
+        assert_eq!(
+            suite.timestamp,
+            run.created_at.format("%Y-%m-%dT%H:%M:%S").to_string()
+        );
+        assert_eq!(
+            suite.properties.properties,
+            vec![
+                JunitProperty {
+                    name: "commit".to_string(),
+                    value: "abc123".to_string(),
+                },
+                JunitProperty {
+                    name: "branch".to_string(),
+                    value: "main".to_string(),
+                },
+            ]
+        );
+        assert_eq!(suite.testcases[0].classname, "/src/Queue.swift");
+        assert_eq!(
+            suite.testcases[0].name,
+            "data_race at /src/Queue.swift:95:10"
+        );
+        assert_eq!(suite.testcases[0].failure.failure_type, "data_race");
+    }
+
+    #[test]
+    fn test_junit_default_suite_name_without_metadata() {
+        let xml = JunitFormatter::new()
+            .format(&WarningRun::new(Vec::new()))
+            .unwrap();
+        assert!(xml.contains("name=\"SwiftConcur\""));
+        assert!(xml.contains("tests=\"0\""));
+        assert!(xml.contains("timestamp=\""));
+    }
+}
@@ -1,13 +1,36 @@
+use crate::cli::GroupBy;
 use crate::error::Result;
-use crate::formatters::Formatter;
-use crate::models::{Severity, WarningRun, WarningType};
+use crate::formatters::{fence_language, occurrence_suffix, FormatOptions, Formatter, Tier};
+use crate::models::{Severity, Warning, WarningRun, WarningType};
 
 #[derive(Default)]
-pub struct MarkdownFormatter;
+pub struct MarkdownFormatter {
+    options: FormatOptions,
+    group_by: Option<GroupBy>,
+}
 
 impl MarkdownFormatter {
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    pub fn with_options(mut self, options: FormatOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn group_by(mut self, group_by: Option<GroupBy>) -> Self {
+        self.group_by = group_by;
+        self
+    }
+
+    fn severity_label(&self, severity: &Severity) -> &str {
+        match severity {
+            Severity::Critical => "Critical",
+            Severity::High => "High",
+            Severity::Medium => "Medium",
+            Severity::Low => "Low",
+        }
     }
 
     fn severity_emoji(&self, severity: &Severity) -> &str {
@@ -20,14 +43,85 @@ impl MarkdownFormatter {
     }
 
     fn warning_type_label(&self, warning_type: &WarningType) -> &str {
+        if let Some(label) = self.options.bucket_label(*warning_type) {
+            return label;
+        }
         match warning_type {
             WarningType::ActorIsolation => "Actor Isolation",
             WarningType::SendableConformance => "Sendable Conformance",
             WarningType::DataRace => "Data Race",
             WarningType::PerformanceRegression => "Performance Regression",
+            WarningType::DeprecatedConcurrencyApi => "Deprecated Concurrency API",
+            WarningType::UnsafeEscapeHatch => "Unsafe Escape Hatch",
             WarningType::Unknown => "Unknown",
         }
     }
+
+    fn push_warning(&self, output: &mut String, warning: &Warning, count: usize) {
+        let location = match self
+            .options
+            .permalinks
+            .as_ref()
+            .and_then(|p| p.link(warning))
+        {
+            Some(link) => format!("[{}]({})", warning.file_path.display(), link),
+            None => warning.file_path.display().to_string(),
+        };
+        output.push_str(&format!(
+            "### {}{} {} - {}{}\n\n",
+            self.options.highlight_marker(warning),
+            self.severity_emoji(&warning.severity),
+            self.warning_type_label(&warning.warning_type),
+            location,
+            occurrence_suffix(count)
+        ));
+
+        output.push_str(&format!(
+            "**Line:** {}{}\n",
+            warning.line_number,
+            self.options.relative_location(warning)
+        ));
+        output.push_str(&format!(
+            "**Message:** {}\n\n",
+            self.options.display_message(&warning.message)
+        ));
+        let collapsed = self.options.collapse_context && !warning.code_context.line.is_empty();
+        if collapsed {
+            output.push_str(&format!(
+                "**Code:** {}\n\n",
+                code_span(warning.code_context.line.trim())
+            ));
+        }
+        if let Some(decl) = self.options.type_declaration(warning) {
+            output.push_str(&format!(
+                "**Declaration:** {} (line {})\n\n",
+                code_span(&decl.text),
+                decl.line_number
+            ));
+        }
+        if let Some(url) = self.options.docs.url(warning.warning_type) {
+            output.push_str(&format!("[Learn more]({url})\n\n"));
+        }
+
+        if !collapsed && !warning.code_context.line.is_empty() {
+            let (context, caret) = self.options.display_context(warning);
+
+            output.push_str(&format!("```{}\n", fence_language(&warning.file_path)));
+            for line in &context.before {
+                output.push_str(&format!("  {line}\n"));
+            }
+            output.push_str(&format!("> {}\n", context.line));
+            if let Some(offset) = caret {
+                output.push_str(&format!("  {}^\n", " ".repeat(offset)));
+            }
+            for line in &context.after {
+                output.push_str(&format!("  {line}\n"));
+            }
+            output.push_str("```\n\n");
+        }
+
+        output.push_str("---\n\n");
+    }
 }
 
 impl Formatter for MarkdownFormatter {
@@ -36,6 +130,10 @@ impl Formatter for MarkdownFormatter {
 
         output.push_str("# Swift Concurrency Warnings Report\n\n");
         output.push_str(&format!("**Total Warnings:** {}\n", run.total_warnings));
+        output.push_str(&format!(
+            "**Errors in Swift 6:** {}\n",
+            run.swift6_error_count
+        ));
         output.push_str(&format!(
             "**Generated:** {}\n\n",
             run.created_at.format("%Y-%m-%d %H:%M:%S UTC")
@@ -49,34 +147,341 @@ impl Formatter for MarkdownFormatter {
             output.push_str(&format!("**Branch:** `{branch}`\n"));
         }
 
-        output.push_str("\n## Warnings\n\n");
+        match self.group_by {
+            None if self.options.tiered => {
+                output.push('\n');
+                for tier in Tier::ALL {
+                    let group: Vec<&Warning> = run
+                        .iter()
+                        .filter(|w| !w.test_code && Tier::of(w.severity) == tier)
+                        .collect();
+                    output.push_str(&format!("## {} ({})\n\n", tier.label(), group.len()));
+                    for (warning, count) in self.options.occurrences(group) {
+                        self.push_warning(&mut output, warning, count);
+                    }
+                }
+            }
+            None => {
+                output.push_str("\n## Warnings\n\n");
+                for (warning, count) in self
+                    .options
+                    .occurrences(run.iter().filter(|w| !w.test_code))
+                {
+                    self.push_warning(&mut output, warning, count);
+                }
+            }
+            Some(GroupBy::Severity) => {
+                output.push('\n');
+                for severity in Severity::ALL {
+                    let group: Vec<&Warning> = run
+                        .iter()
+                        .filter(|w| !w.test_code && w.severity == severity)
+                        .collect();
+                    if group.is_empty() {
+                        continue;
+                    }
+                    output.push_str(&format!(
+                        "## {} ({})\n\n",
+                        self.severity_label(&severity),
+                        group.len()
+                    ));
+                    for (warning, count) in self.options.occurrences(group) {
+                        self.push_warning(&mut output, warning, count);
+                    }
+                }
+            }
+        }
 
-        for warning in &run.warnings {
+        // Test code is lower priority, so it goes after production warnings
+        let test_warnings: Vec<&Warning> = run.iter().filter(|w| w.test_code).collect();
+        if !test_warnings.is_empty() {
             output.push_str(&format!(
-                "### {} {} - {}\n\n",
-                self.severity_emoji(&warning.severity),
-                self.warning_type_label(&warning.warning_type),
-                warning.file_path.display()
+                "## Test Code Warnings ({})\n\n",
+                test_warnings.len()
             ));
+            for (warning, count) in self.options.occurrences(test_warnings) {
+                self.push_warning(&mut output, warning, count);
+            }
+        }
+
+        Ok(output)
+    }
+}
 
-            output.push_str(&format!("**Line:** {}\n", warning.line_number));
-            output.push_str(&format!("**Message:** {}\n\n", warning.message));
+/// `text` as an inline code span, fenced with more backticks than any run of
+/// backticks inside it (e.g. Swift's escaped identifiers)
+fn code_span(text: &str) -> String {
+    let longest = text
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or_default();
+    let fence = "`".repeat(longest + 1);
+    // A space keeps a leading or trailing backtick from joining the fence
+    let pad = if text.starts_with('`') || text.ends_with('`') {
+        " "
+    } else {
+        ""
+    };
+    format!("{fence}{pad}{text}{pad}{fence}")
+}
 
-            if !warning.code_context.line.is_empty() {
-                output.push_str("```swift\n");
-                for line in &warning.code_context.before {
-                    output.push_str(&format!("  {line}\n"));
-                }
-                output.push_str(&format!("> {}\n", warning.code_context.line));
-                for line in &warning.code_context.after {
-                    output.push_str(&format!("  {line}\n"));
-                }
-                output.push_str("```\n\n");
-            }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::CodeContext;
 
-            output.push_str("---\n\n");
+    fn warning(path: &str) -> Warning {
+        Warning {
+            id: path.to_string(),
+            message: "main actor-isolated property 'view' can not be referenced".to_string(),
+            code_context: CodeContext {
+                before: vec!["- (void)load {".to_string()],
+                line: "    [self.view setNeedsLayout];".to_string(),
+                after: vec!["}".to_string()],
+            },
+            ..Warning::fixture(path, 2)
         }
+    }
 
-        Ok(output)
+    #[test]
+    fn test_fence_language_follows_extension() {
+        let run = WarningRun::new(vec![
+            warning("/project/Legacy/Loader.m"),
+            warning("/project/Sources/Loader.swift"),
+        ]);
+
+        let markdown = MarkdownFormatter::new().format(&run).unwrap();
+        let objc = markdown.find("```objc\n  - (void)load {").unwrap();
+        let swift = markdown.find("```swift\n").unwrap();
+        assert!(objc < swift);
+        assert_eq!(markdown.matches("```swift").count(), 1);
+    }
+
+    #[test]
+    fn test_collapsed_duplicates_show_count() {
+        let repeated = warning("/project/Sources/Loader.swift");
+        let run = WarningRun::new(vec![
+            repeated.clone(),
+            warning("/project/Legacy/Loader.m"),
+            repeated.clone(),
+            repeated,
+        ]);
+        let options = FormatOptions {
+            collapse_duplicates: true,
+            ..FormatOptions::default()
+        };
+
+        let markdown = MarkdownFormatter::new()
+            .with_options(options)
+            .format(&run)
+            .unwrap();
+        assert!(markdown.contains("**Total Warnings:** 4"));
+        assert_eq!(markdown.matches("/project/Sources/Loader.swift").count(), 1);
+        assert!(markdown.contains("/project/Sources/Loader.swift (×3)"));
+        assert!(!markdown.contains("Loader.m (×"));
+
+        let expanded = MarkdownFormatter::new().format(&run).unwrap();
+        assert_eq!(expanded.matches("/project/Sources/Loader.swift").count(), 3);
+        assert!(!expanded.contains('×'));
+    }
+
+    #[test]
+    fn test_highlight_marks_matching_warnings() {
+        let run = WarningRun::new(vec![
+            warning("/project/Legacy/Loader.m"),
+            warning("/project/Sources/Loader.swift"),
+        ]);
+        let options = FormatOptions {
+            highlight: Some(regex::Regex::new(r"Legacy/").unwrap()),
+            ..FormatOptions::default()
+        };
+
+        let markdown = MarkdownFormatter::new()
+            .with_options(options)
+            .format(&run)
+            .unwrap();
+        let headings: Vec<&str> = markdown.lines().filter(|l| l.starts_with("### ")).collect();
+        assert_eq!(headings.len(), 2);
+        assert!(headings[0].starts_with("### 🔎 "));
+        assert!(headings[0].ends_with("/project/Legacy/Loader.m"));
+        assert!(!headings[1].contains('🔎'));
+    }
+
+    #[test]
+    fn test_collapse_context_renders_only_the_line() {
+        let run = WarningRun::new(vec![warning("/project/Legacy/Loader.m")]);
+        let options = FormatOptions {
+            collapse_context: true,
+            ..FormatOptions::default()
+        };
+
+        let markdown = MarkdownFormatter::new()
+            .with_options(options)
+            .format(&run)
+            .unwrap();
+        assert!(markdown.contains("**Code:** `[self.view setNeedsLayout];`\n"));
+        assert!(!markdown.contains("- (void)load {"));
+        assert!(!markdown.contains("\n  }\n"));
+        assert!(!markdown.contains("```"));
+    }
+
+    #[test]
+    fn test_caret_under_tab_indented_line() {
+        let mut tabbed = warning("/project/Sources/Counter.swift");
+        tabbed.column_number = Some(3);
+        tabbed.code_context = CodeContext::empty("\t\tcount += 1".to_string());
+        let run = WarningRun::new(vec![tabbed]);
+
+        let markdown = MarkdownFormatter::new().format(&run).unwrap();
+        assert!(markdown.contains("\n>         count += 1\n          ^\n```"));
+
+        let options = FormatOptions {
+            tab_width: 2,
+            relativize_context: true,
+            ..FormatOptions::default()
+        };
+        let markdown = MarkdownFormatter::new()
+            .with_options(options)
+            .format(&run)
+            .unwrap();
+        assert!(markdown.contains("\n> count += 1\n  ^\n```"));
+    }
+
+    #[test]
+    fn test_context_radius_trims_long_line() {
+        let mut minified = warning("/project/Sources/Generated.swift");
+        let line = format!("{}self.count += 1{}", "a".repeat(2500), "b".repeat(2485));
+        assert_eq!(line.chars().count(), 5000);
+        minified.column_number = Some(2501);
+        minified.code_context = CodeContext::empty(line);
+        let run = WarningRun::new(vec![minified]);
+
+        let options = FormatOptions {
+            context_radius: Some(10),
+            ..FormatOptions::default()
+        };
+        let markdown = MarkdownFormatter::new()
+            .with_options(options)
+            .format(&run)
+            .unwrap();
+        let window = format!("…{}self.count …", "a".repeat(10));
+        assert!(
+            markdown.contains(&format!("\n> {window}\n  {}^\n", " ".repeat(11))),
+            "{markdown}"
+        );
+
+        // Without the option the whole line is shown
+        let markdown = MarkdownFormatter::new().format(&run).unwrap();
+        assert!(markdown.contains(&"b".repeat(2485)));
+    }
+
+    #[test]
+    fn test_group_by_severity_sections() {
+        let warnings =
+            crate::parse_str(include_str!("../../tests/fixtures/mixed_warnings.json"), 0).unwrap();
+        let run = WarningRun::new(warnings);
+
+        let markdown = MarkdownFormatter::new()
+            .group_by(Some(GroupBy::Severity))
+            .format(&run)
+            .unwrap();
+        let sections: Vec<&str> = markdown.lines().filter(|l| l.starts_with("## ")).collect();
+        assert_eq!(
+            sections,
+            ["## Critical (1)", "## High (1)", "## Medium (1)"]
+        );
+        assert!(!markdown.contains("## Warnings"));
+
+        let critical = markdown.find("## Critical").unwrap();
+        let high = markdown.find("## High").unwrap();
+        let race = markdown.find("Data Race - ").unwrap();
+        assert!(critical < race && race < high);
+        assert_eq!(markdown.matches("### ").count(), 3);
+    }
+
+    #[test]
+    fn test_include_decl_renders_type_declaration() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Config.swift");
+        std::fs::write(&path, "struct Config {\n    var name: String\n}\n").unwrap();
+        let mut sendable = warning(path.to_str().unwrap());
+        sendable.warning_type = WarningType::SendableConformance;
+        sendable.message = "type 'Config' does not conform to the 'Sendable' protocol".to_string();
+        let run = WarningRun::new(vec![sendable]);
+        let options = FormatOptions {
+            include_decl: true,
+            ..FormatOptions::default()
+        };
+
+        let markdown = MarkdownFormatter::new()
+            .with_options(options)
+            .format(&run)
+            .unwrap();
+        assert!(markdown.contains("**Declaration:** `struct Config {` (line 1)\n"));
+
+        let plain = MarkdownFormatter::new().format(&run).unwrap();
+        assert!(!plain.contains("**Declaration:**"));
+    }
+
+    #[test]
+    fn test_code_span_escapes_backticks() {
+        assert_eq!(code_span("struct Config {"), "`struct Config {`");
+        assert_eq!(code_span("enum `Type` {"), "``enum `Type` {``");
+        assert_eq!(code_span("`default`"), "`` `default` ``");
+    }
+
+    #[test]
+    fn test_learn_more_link_per_type() {
+        for warning_type in WarningType::ALL {
+            let mut typed = warning("/project/Sources/Loader.swift");
+            typed.warning_type = warning_type;
+            let markdown = MarkdownFormatter::new()
+                .format(&WarningRun::new(vec![typed]))
+                .unwrap();
+
+            match warning_type.docs_url() {
+                Some(url) => assert!(
+                    markdown.contains(&format!("[Learn more]({url})\n")),
+                    "{warning_type:?}"
+                ),
+                None => assert!(!markdown.contains("Learn more"), "{warning_type:?}"),
+            }
+        }
+
+        let mut options = FormatOptions::default();
+        options.docs.set(
+            WarningType::ActorIsolation,
+            "https://wiki.example.com/isolation",
+        );
+        let markdown = MarkdownFormatter::new()
+            .with_options(options)
+            .format(&WarningRun::new(vec![warning("/project/A.swift")]))
+            .unwrap();
+        assert!(markdown.contains("[Learn more](https://wiki.example.com/isolation)"));
+        assert!(!markdown.contains("swift.org"));
+    }
+
+    #[test]
+    fn test_tiered_sections() {
+        let mut critical = warning("/project/Sources/Store.swift");
+        critical.severity = Severity::Critical;
+        let mut low = warning("/project/Sources/Cache.swift");
+        low.severity = Severity::Low;
+        let run = WarningRun::new(vec![low, critical]);
+        let options = FormatOptions {
+            tiered: true,
+            ..FormatOptions::default()
+        };
+
+        let markdown = MarkdownFormatter::new()
+            .with_options(options)
+            .format(&run)
+            .unwrap();
+        let action = markdown.find("## Action Required (1)").unwrap();
+        let awareness = markdown.find("## For Awareness (1)").unwrap();
+        let store = markdown.find("Store.swift").unwrap();
+        let cache = markdown.find("Cache.swift").unwrap();
+        assert!(action < store && store < awareness && awareness < cache);
     }
 }
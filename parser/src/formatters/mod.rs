@@ -1,14 +1,445 @@
+pub mod category_map;
+pub mod csv;
+pub mod debt;
+pub mod fix_script;
+pub mod grep;
 pub mod json;
+pub mod junit;
 pub mod markdown;
+pub mod permalink;
+#[cfg(feature = "proto")]
+pub mod proto;
+pub mod quickfix;
+pub mod rtf;
+pub mod scope;
 pub mod slack;
+pub mod summary;
+pub mod xml;
 
+use crate::cli::{Column, Taxonomy};
+use crate::config::DocLinks;
 use crate::error::Result;
-use crate::models::WarningRun;
+use crate::models::{CodeContext, Severity, Warning, WarningRun, WarningType};
+use crate::parser::warnings::taxonomy_type;
+use regex::Regex;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
 pub trait Formatter {
     fn format(&self, run: &WarningRun) -> Result<String>;
+
+    /// Write the report to `writer`. Formatters that can serialize directly to
+    /// the writer override this to avoid holding the whole report in memory.
+    fn write_to(&self, run: &WarningRun, writer: &mut dyn Write) -> Result<()> {
+        writer.write_all(self.format(run)?.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// Columns per tab stop when none is configured
+pub const DEFAULT_TAB_WIDTH: usize = 4;
+
+/// Presentation options shared by the human-readable formatters
+#[derive(Debug, Clone)]
+pub struct FormatOptions {
+    pub permalinks: Option<Permalinks>,
+    /// Strip indentation shared by all code context lines before rendering
+    pub relativize_context: bool,
+    /// Render only the trimmed warning line, inline, instead of the context block
+    pub collapse_context: bool,
+    /// Truncate displayed messages to this many characters
+    pub max_message_len: Option<usize>,
+    /// Annotate each warning with its enclosing declaration and offset in it
+    pub function_relative: bool,
+    /// Label types by their `--taxonomy` bucket
+    pub taxonomy: Taxonomy,
+    /// Render warnings sharing an id once, with an occurrence count
+    pub collapse_duplicates: bool,
+    /// Mark warnings whose file path or message matches
+    pub highlight: Option<Regex>,
+    /// Columns per tab stop when rendering code context and carets
+    pub tab_width: usize,
+    /// Show the declaration of the type a warning names, when found in its file
+    pub include_decl: bool,
+    /// "Learn more" documentation link per warning type
+    pub docs: DocLinks,
+    /// Split warnings into [`Tier`]s instead of listing them flat
+    pub tiered: bool,
+    /// Show only this many characters either side of the caret on longer
+    /// warning lines
+    pub context_radius: Option<usize>,
+    /// On-disk file to read for each reported path, when that differs (e.g.
+    /// through `--source-map` or `--anonymize-paths`)
+    pub sources: SourcePaths,
+}
+
+/// Reported file paths mapped to the files to read for them
+pub type SourcePaths = HashMap<PathBuf, PathBuf>;
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            permalinks: None,
+            relativize_context: false,
+            collapse_context: false,
+            max_message_len: None,
+            function_relative: false,
+            taxonomy: Taxonomy::default(),
+            collapse_duplicates: false,
+            highlight: None,
+            tab_width: DEFAULT_TAB_WIDTH,
+            include_decl: false,
+            docs: DocLinks::default(),
+            tiered: false,
+            context_radius: None,
+            sources: SourcePaths::new(),
+        }
+    }
+}
+
+/// Triage tier for `--tiered` output, derived from severity
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tier {
+    /// Critical and High
+    ActionRequired,
+    /// Medium and Low
+    ForAwareness,
+}
+
+impl Tier {
+    /// Every tier, in display order
+    pub const ALL: [Tier; 2] = [Tier::ActionRequired, Tier::ForAwareness];
+
+    pub fn of(severity: Severity) -> Self {
+        match severity {
+            Severity::Critical | Severity::High => Tier::ActionRequired,
+            Severity::Medium | Severity::Low => Tier::ForAwareness,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Tier::ActionRequired => "Action Required",
+            Tier::ForAwareness => "For Awareness",
+        }
+    }
+}
+
+/// ` (×N)` for a warning that occurs more than once, otherwise empty
+pub(crate) fn occurrence_suffix(count: usize) -> String {
+    if count > 1 {
+        format!(" (×{count})")
+    } else {
+        String::new()
+    }
+}
+
+/// Code fence language for context from `path`, by file extension
+pub(crate) fn fence_language(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("m" | "mm") => "objc",
+        Some("h") => "cpp",
+        _ => "swift",
+    }
+}
+
+/// The value of one `--columns` field for `warning`; absent values are empty
+pub(crate) fn column_value(column: Column, warning: &Warning) -> String {
+    match column {
+        Column::File => warning.file_path.display().to_string(),
+        Column::Line => warning.line_number.to_string(),
+        Column::Column => warning
+            .column_number
+            .map(|c| c.to_string())
+            .unwrap_or_default(),
+        Column::Type => warning.warning_type.as_str().to_string(),
+        Column::Severity => warning.severity.as_str().to_string(),
+        Column::Message => warning.message.clone(),
+        Column::Id => warning.id.clone(),
+        Column::Fix => warning.suggested_fix.clone().unwrap_or_default(),
+        Column::Effort => warning
+            .effort
+            .map(|effort| effort.as_str().to_string())
+            .unwrap_or_default(),
+    }
+}
+
+impl FormatOptions {
+    /// Display label of the coarse `--taxonomy` bucket `warning_type` falls
+    /// in, or `None` to use the type's own label
+    pub fn bucket_label(&self, warning_type: WarningType) -> Option<&'static str> {
+        match self.taxonomy {
+            Taxonomy::Fine => None,
+            Taxonomy::Coarse => match taxonomy_type(warning_type, self.taxonomy) {
+                WarningType::SendableConformance => Some("Sendability"),
+                WarningType::DataRace => Some("Data Race"),
+                _ => Some("Isolation"),
+            },
+        }
+    }
+
+    /// `warnings` paired with how often each occurs. With `collapse_duplicates`
+    /// warnings sharing an id appear once, at their first position; otherwise
+    /// every warning is listed with a count of 1.
+    pub fn occurrences<'a>(
+        &self,
+        warnings: impl IntoIterator<Item = &'a Warning>,
+    ) -> Vec<(&'a Warning, usize)> {
+        let mut entries: Vec<(&Warning, usize)> = Vec::new();
+        let mut index: HashMap<&str, usize> = HashMap::new();
+        for warning in warnings {
+            if !self.collapse_duplicates {
+                entries.push((warning, 1));
+                continue;
+            }
+            match index.get(warning.id.as_str()) {
+                Some(&i) => entries[i].1 += 1,
+                None => {
+                    index.insert(&warning.id, entries.len());
+                    entries.push((warning, 1));
+                }
+            }
+        }
+        entries
+    }
+
+    /// Code context of `warning` as displayed: tabs expanded and, with
+    /// `relativize_context`, common indentation removed. Also returns the
+    /// 0-based offset of the caret on the warning line, when its column is known.
+    /// With `context_radius`, a warning line too long to fit the window around
+    /// the caret is cut down to it, and the lines around it to the same
+    /// columns, with `…` marking each trimmed side.
+    pub fn display_context(&self, warning: &Warning) -> (CodeContext, Option<usize>) {
+        let expanded = warning.code_context.expand_tabs(self.tab_width);
+        let (mut context, removed) = if self.relativize_context {
+            expanded.dedent()
+        } else {
+            (expanded, 0)
+        };
+        let mut caret = warning.column_number.and_then(|column| {
+            let display = warning.code_context.display_column(column, self.tab_width);
+            // Columns past the end of the line or inside removed indentation can't be pointed at
+            (display - 1)
+                .checked_sub(removed)
+                .filter(|offset| *offset <= context.line.chars().count())
+        });
+        if let (Some(radius), Some(offset)) = (self.context_radius, caret) {
+            let (start, end) = window(context.line.chars().count(), offset, radius);
+            for line in context.before.iter_mut().chain(&mut context.after) {
+                *line = trim_to(line, start, end);
+            }
+            let (line, trimmed_offset) = trim_around(&context.line, offset, radius);
+            context.line = line;
+            caret = Some(trimmed_offset);
+        }
+        (context, caret)
+    }
+
+    /// `🔎 ` for warnings matching the highlight pattern, otherwise empty
+    pub fn highlight_marker(&self, warning: &Warning) -> &'static str {
+        let matched = self.highlight.as_ref().is_some_and(|pattern| {
+            pattern.is_match(&warning.file_path.to_string_lossy())
+                || pattern.is_match(&warning.message)
+        });
+        if matched {
+            "🔎 "
+        } else {
+            ""
+        }
+    }
+
+    /// ` (line N of func foo())` when `function_relative` is set and an
+    /// enclosing declaration is found, otherwise empty
+    pub fn relative_location(&self, warning: &Warning) -> String {
+        if !self.function_relative {
+            return String::new();
+        }
+        scope::enclosing_declaration(warning, &self.source_path(warning))
+            .map(|decl| format!(" (line {} of {})", decl.offset, decl.name))
+            .unwrap_or_default()
+    }
+
+    /// The declaration of the type named in the warning when `include_decl` is set
+    pub fn type_declaration(&self, warning: &Warning) -> Option<scope::TypeDeclaration> {
+        if !self.include_decl {
+            return None;
+        }
+        scope::type_declaration(warning, &self.source_path(warning))
+    }
+
+    /// The file to read for `warning`'s source
+    fn source_path(&self, warning: &Warning) -> PathBuf {
+        self.sources
+            .get(&warning.file_path)
+            .cloned()
+            .unwrap_or_else(|| warning.file_path.clone())
+    }
+
+    /// `message` cut to `max_message_len` characters plus an ellipsis, when longer
+    pub fn display_message<'a>(&self, message: &'a str) -> Cow<'a, str> {
+        let Some(max) = self.max_message_len else {
+            return Cow::Borrowed(message);
+        };
+        match message.char_indices().nth(max) {
+            // Slicing at a char boundary keeps multi-byte characters intact
+            Some((end, _)) => Cow::Owned(format!("{}…", &message[..end])),
+            None => Cow::Borrowed(message),
+        }
+    }
+}
+
+/// The character columns `start..end` shown for a `radius` window around the
+/// 0-based `offset` on a line `len` characters long. Lines that fit the window
+/// start at column 0.
+fn window(len: usize, offset: usize, radius: usize) -> (usize, usize) {
+    if len <= 2 * radius + 1 {
+        (0, 2 * radius + 1)
+    } else {
+        (offset.saturating_sub(radius), offset + radius + 1)
+    }
+}
+
+/// The characters of `line` in columns `start..end`, with `…` on each side
+/// that was cut
+fn trim_to(line: &str, start: usize, end: usize) -> String {
+    let chars: Vec<char> = line.chars().collect();
+    let (start, end) = (start.min(chars.len()), end.min(chars.len()));
+    let mut trimmed = String::new();
+    if start > 0 {
+        trimmed.push('…');
+    }
+    trimmed.extend(&chars[start..end]);
+    if end < chars.len() {
+        trimmed.push('…');
+    }
+    trimmed
+}
+
+/// The characters of `line` within `radius` of the 0-based `offset`, with `…`
+/// on each side that was cut, and the offset's position in the result. Lines
+/// that already fit the window are returned whole.
+fn trim_around(line: &str, offset: usize, radius: usize) -> (String, usize) {
+    let (start, end) = window(line.chars().count(), offset, radius);
+    (
+        trim_to(line, start, end),
+        offset - start + usize::from(start > 0),
+    )
 }
 
+pub use category_map::CategoryMap;
+pub use csv::CsvFormatter;
+pub use debt::DebtFormatter;
+pub use fix_script::format_fix_script;
+pub use grep::GrepFormatter;
 pub use json::JsonFormatter;
+pub use junit::JunitFormatter;
 pub use markdown::MarkdownFormatter;
+pub use permalink::Permalinks;
+#[cfg(feature = "proto")]
+pub use proto::ProtoFormatter;
+pub use quickfix::QuickfixFormatter;
+pub use rtf::RtfFormatter;
 pub use slack::SlackFormatter;
+pub use summary::{format_step_summary, format_summary};
+pub use xml::XmlFormatter;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_message_truncation() {
+        let options = FormatOptions {
+            max_message_len: Some(10),
+            ..FormatOptions::default()
+        };
+
+        assert_eq!(options.display_message("short"), "short");
+        assert_eq!(options.display_message("exactly 10"), "exactly 10");
+        assert_eq!(
+            options.display_message("type 'Box<T>' does not conform"),
+            "type 'Box<…"
+        );
+        // Multi-byte characters are counted, not bytes, and never split
+        assert_eq!(
+            options.display_message("ünïcödé ‘quoted’ text"),
+            "ünïcödé ‘q…"
+        );
+        assert_eq!(
+            FormatOptions::default().display_message("type 'Box<T>' does not conform"),
+            "type 'Box<T>' does not conform"
+        );
+    }
+
+    #[test]
+    fn test_trim_around_edges() {
+        assert_eq!(trim_around("let x = 1", 4, 4), ("let x = 1".to_string(), 4));
+        assert_eq!(trim_around("0123456789", 1, 2), ("0123…".to_string(), 1));
+        assert_eq!(trim_around("0123456789", 9, 2), ("…789".to_string(), 3));
+        assert_eq!(trim_around("0123456789", 5, 2), ("…34567…".to_string(), 3));
+    }
+
+    #[test]
+    fn test_context_radius_trims_surrounding_lines() {
+        let mut warning = Warning::fixture("/project/Generated.swift", 2);
+        warning.column_number = Some(8);
+        warning.code_context = CodeContext {
+            before: vec!["0123456789abcdef".to_string(), "}".to_string()],
+            line: "abcdefghijklmnop".to_string(),
+            after: vec![String::new(), "short".to_string()],
+        };
+        let options = FormatOptions {
+            context_radius: Some(2),
+            ..FormatOptions::default()
+        };
+
+        let (context, caret) = options.display_context(&warning);
+        assert_eq!(context.line, "…fghij…");
+        assert_eq!(caret, Some(3));
+        // Surrounding lines keep the warning line's columns
+        assert_eq!(context.before, ["…56789…", "…"]);
+        assert_eq!(context.after, ["", "…"]);
+
+        // A warning line that fits still bounds longer neighbors
+        warning.code_context.line = "let x".to_string();
+        warning.column_number = Some(5);
+        let (context, _) = options.display_context(&warning);
+        assert_eq!(context.line, "let x");
+        assert_eq!(context.before[0], "01234…");
+    }
+
+    #[test]
+    fn test_bucket_labels() {
+        let coarse = FormatOptions {
+            taxonomy: Taxonomy::Coarse,
+            ..FormatOptions::default()
+        };
+
+        assert_eq!(
+            coarse.bucket_label(WarningType::ActorIsolation),
+            Some("Isolation")
+        );
+        assert_eq!(
+            coarse.bucket_label(WarningType::SendableConformance),
+            Some("Sendability")
+        );
+        assert_eq!(
+            coarse.bucket_label(WarningType::UnsafeEscapeHatch),
+            Some("Sendability")
+        );
+        assert_eq!(
+            coarse.bucket_label(WarningType::PerformanceRegression),
+            Some("Isolation")
+        );
+        assert_eq!(
+            coarse.bucket_label(WarningType::DataRace),
+            Some("Data Race")
+        );
+        assert_eq!(
+            FormatOptions::default().bucket_label(WarningType::ActorIsolation),
+            None
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use crate::models::Warning;
+use std::path::{Component, Path, PathBuf};
+
+// Percent-encode a path segment, leaving RFC 3986 unreserved characters as is
+fn encode_segment(segment: &str) -> String {
+    let mut encoded = String::with_capacity(segment.len());
+    for byte in segment.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+/// Builds `{repo}/blob/{sha}/{relative_path}#L{line}` links for hosted repositories
+#[derive(Debug, Clone)]
+pub struct Permalinks {
+    repo_url: String,
+    commit: String,
+    root: PathBuf,
+}
+
+impl Permalinks {
+    pub fn new(repo_url: &str, commit: &str, root: impl Into<PathBuf>) -> Self {
+        Self {
+            repo_url: repo_url.trim_end_matches('/').to_string(),
+            commit: commit.to_string(),
+            root: root.into(),
+        }
+    }
+
+    /// Path of `path` relative to the repository root, using `/` separators
+    pub fn relative_path(&self, path: &Path) -> Option<String> {
+        let relative = if path.is_absolute() {
+            path.strip_prefix(&self.root).ok()?
+        } else {
+            path
+        };
+
+        let mut segments = Vec::new();
+        for component in relative.components() {
+            match component {
+                Component::Normal(segment) => segments.push(segment.to_str()?.to_string()),
+                Component::CurDir => {}
+                // Paths escaping the root can't be linked
+                _ => return None,
+            }
+        }
+
+        if segments.is_empty() {
+            None
+        } else {
+            Some(segments.join("/"))
+        }
+    }
+
+    pub fn link(&self, warning: &Warning) -> Option<String> {
+        let relative = self.relative_path(&warning.file_path)?;
+        let encoded: Vec<String> = relative.split('/').map(encode_segment).collect();
+        Some(format!(
+            "{}/blob/{}/{}#L{}",
+            self.repo_url,
+            self.commit,
+            encoded.join("/"),
+            warning.line_number
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn warning(path: &str, line: usize) -> Warning {
+        Warning::fixture(path, line)
+    }
+
+    #[test]
+    fn test_permalink_shape() {
+        let permalinks = Permalinks::new(
+            "https://github.com/acme/app/",
+            "abc123",
+            "/Users/runner/work/app/app",
+        );
+
+        assert_eq!(
+            permalinks
+                .link(&warning(
+                    "/Users/runner/work/app/app/Sources/App/Item.swift",
+                    37
+                ))
+                .as_deref(),
+            Some("https://github.com/acme/app/blob/abc123/Sources/App/Item.swift#L37")
+        );
+        assert_eq!(
+            permalinks
+                .link(&warning("./Sources/App/Item.swift", 5))
+                .as_deref(),
+            Some("https://github.com/acme/app/blob/abc123/Sources/App/Item.swift#L5")
+        );
+    }
+
+    #[test]
+    fn test_permalink_encodes_spaces() {
+        let permalinks = Permalinks::new(
+            "https://github.com/acme/app",
+            "abc123",
+            "/Users/me/My Project",
+        );
+
+        assert_eq!(
+            permalinks
+                .link(&warning(
+                    "/Users/me/My Project/Sources/Shared State.swift",
+                    10
+                ))
+                .as_deref(),
+            Some("https://github.com/acme/app/blob/abc123/Sources/Shared%20State.swift#L10")
+        );
+    }
+
+    #[test]
+    fn test_no_permalink_outside_root() {
+        let permalinks = Permalinks::new("https://github.com/acme/app", "abc123", "/work/app");
+
+        assert!(permalinks
+            .link(&warning("/elsewhere/Item.swift", 1))
+            .is_none());
+        assert!(permalinks.link(&warning("../Item.swift", 1)).is_none());
+    }
+}
@@ -0,0 +1,115 @@
+use crate::models::{Severity, WarningRun, WarningType};
+use prost::Message;
+
+/// Types generated from `proto/swiftconcur.proto`
+pub mod pb {
+    include!(concat!(env!("OUT_DIR"), "/swiftconcur.rs"));
+}
+
+/// Encodes a run as a length-delimited `swiftconcur.WarningRun` message
+#[derive(Default)]
+pub struct ProtoFormatter;
+
+impl ProtoFormatter {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn encode(&self, run: &WarningRun) -> Vec<u8> {
+        to_message(run).encode_length_delimited_to_vec()
+    }
+}
+
+fn to_message(run: &WarningRun) -> pb::WarningRun {
+    pb::WarningRun {
+        schema_version: run.schema_version,
+        id: run.id.clone(),
+        commit_sha: run.commit_sha.clone(),
+        branch: run.branch.clone(),
+        pull_request: run.pull_request,
+        total_warnings: run.total_warnings as u64,
+        swift6_error_count: run.swift6_error_count as u64,
+        warnings: run
+            .iter()
+            .map(|warning| pb::Warning {
+                id: warning.id.clone(),
+                warning_type: warning_type(warning.warning_type) as i32,
+                severity: severity(warning.severity) as i32,
+                file_path: warning.file_path.display().to_string(),
+                line_number: warning.line_number as u64,
+                column_number: warning.column_number.map(|c| c as u64),
+                message: warning.message.clone(),
+                code_context: Some(pb::CodeContext {
+                    before: warning.code_context.before.clone(),
+                    line: warning.code_context.line.clone(),
+                    after: warning.code_context.after.clone(),
+                }),
+                suggested_fix: warning.suggested_fix.clone(),
+                labels: warning
+                    .labels
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect(),
+            })
+            .collect(),
+        created_at: run.created_at.to_rfc3339(),
+        detected_format: run.detected_format.clone(),
+    }
+}
+
+fn warning_type(warning_type: WarningType) -> pb::WarningType {
+    match warning_type {
+        WarningType::ActorIsolation => pb::WarningType::ActorIsolation,
+        WarningType::SendableConformance => pb::WarningType::SendableConformance,
+        WarningType::DataRace => pb::WarningType::DataRace,
+        WarningType::PerformanceRegression => pb::WarningType::PerformanceRegression,
+        WarningType::DeprecatedConcurrencyApi => pb::WarningType::DeprecatedConcurrencyApi,
+        WarningType::UnsafeEscapeHatch => pb::WarningType::UnsafeEscapeHatch,
+        WarningType::Unknown => pb::WarningType::Unknown,
+    }
+}
+
+fn severity(severity: Severity) -> pb::Severity {
+    match severity {
+        Severity::Critical => pb::Severity::Critical,
+        Severity::High => pb::Severity::High,
+        Severity::Medium => pb::Severity::Medium,
+        Severity::Low => pb::Severity::Low,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{CodeContext, Warning};
+
+    #[test]
+    fn test_proto_round_trip() {
+        let mut run = WarningRun::new(vec![Warning {
+            id: "abc".to_string(),
+            warning_type: WarningType::DataRace,
+            severity: Severity::Critical,
+            column_number: Some(5),
+            message: "data race detected".to_string(),
+            code_context: CodeContext::empty("cache[key] = value".to_string()),
+            suggested_fix: Some("Use an actor".to_string()),
+            ..Warning::fixture("/src/Cache.swift", 12)
+        }]);
+        run.commit_sha = Some("deadbeef".to_string());
+
+        let bytes = ProtoFormatter::new().encode(&run);
+        let decoded = pb::WarningRun::decode_length_delimited(bytes.as_slice()).unwrap();
+
+        assert_eq!(decoded, to_message(&run));
+        assert_eq!(decoded.commit_sha.as_deref(), Some("deadbeef"));
+        assert_eq!(decoded.total_warnings, 1);
+        let warning = &decoded.warnings[0];
+        assert_eq!(warning.warning_type(), pb::WarningType::DataRace);
+        assert_eq!(warning.severity(), pb::Severity::Critical);
+        assert_eq!(warning.column_number, Some(5));
+        assert_eq!(
+            warning.code_context.as_ref().unwrap().line,
+            "cache[key] = value"
+        );
+    }
+}
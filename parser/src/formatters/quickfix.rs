@@ -0,0 +1,128 @@
+use crate::error::Result;
+use crate::formatters::grep::NO_WARNINGS_SENTINEL;
+use crate::formatters::Formatter;
+use crate::models::WarningRun;
+
+/// Editor quickfix list for `:cfile`: one `file:line:col: warning: [type] message`
+/// line per warning, sorted by file and line. Matches vim's default
+/// `%f:%l:%c: %trror: %m` / `%f:%l:%c: %tarning: %m` errorformat entries.
+/// Warnings that become errors under Swift 6 use the `error` keyword.
+/// An empty run prints [`NO_WARNINGS_SENTINEL`], which matches no errorformat
+/// entry and so jumps nowhere.
+#[derive(Default)]
+pub struct QuickfixFormatter {
+    quiet: bool,
+}
+
+impl QuickfixFormatter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Print nothing, rather than the sentinel line, for runs without warnings
+    pub fn quiet(mut self, quiet: bool) -> Self {
+        self.quiet = quiet;
+        self
+    }
+}
+
+impl Formatter for QuickfixFormatter {
+    fn format(&self, run: &WarningRun) -> Result<String> {
+        if run.warnings.is_empty() && !self.quiet {
+            return Ok(NO_WARNINGS_SENTINEL.to_string());
+        }
+
+        let mut warnings: Vec<_> = run.warnings.iter().collect();
+        warnings.sort_by(|a, b| {
+            a.file_path
+                .cmp(&b.file_path)
+                .then(a.line_number.cmp(&b.line_number))
+                .then(a.column_number.cmp(&b.column_number))
+        });
+
+        let lines: Vec<String> = warnings
+            .iter()
+            .map(|warning| {
+                let keyword = if warning.is_error_in_swift6() {
+                    "error"
+                } else {
+                    "warning"
+                };
+                // %m stops at the end of the line
+                let message = warning.message.replace(['\r', '\n'], " ");
+                format!(
+                    "{}:{}:{}: {}: [{}] {}",
+                    warning.file_path.display(),
+                    warning.line_number,
+                    // Column 1 when unknown, so every line fits the same errorformat
+                    warning.column_number.unwrap_or(1),
+                    keyword,
+                    warning.warning_type.as_str(),
+                    message
+                )
+            })
+            .collect();
+
+        Ok(lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Warning;
+    use regex::Regex;
+
+    fn warning(file: &str, line: usize, column: Option<usize>, message: &str) -> Warning {
+        Warning {
+            column_number: column,
+            message: message.to_string(),
+            ..Warning::fixture(file, line)
+        }
+    }
+
+    #[test]
+    fn test_quickfix_lines_match_errorformat() {
+        let run = WarningRun::new(vec![
+            warning(
+                "/src/B.swift",
+                3,
+                Some(7),
+                "main actor-isolated property 'count' can not be mutated; this is an error in the Swift 6 language mode",
+            ),
+            warning("/src/A.swift", 20, None, "data race\ndetected"),
+            warning(
+                "/src/A.swift",
+                4,
+                Some(12),
+                "actor-isolated property 'shared' can not be referenced",
+            ),
+        ]);
+
+        let output = QuickfixFormatter::new().format(&run).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "/src/A.swift:4:12: warning: [actor_isolation] actor-isolated property 'shared' can not be referenced",
+                "/src/A.swift:20:1: warning: [actor_isolation] data race detected",
+                "/src/B.swift:3:7: error: [actor_isolation] main actor-isolated property 'count' can not be mutated; this is an error in the Swift 6 language mode",
+            ]
+        );
+
+        // %f:%l:%c: %t%*[a-z]: %m
+        let errorformat = Regex::new(r"^[^:]+:\d+:\d+: (warning|error): \S.*$").unwrap();
+        assert!(lines.iter().all(|line| errorformat.is_match(line)));
+    }
+
+    #[test]
+    fn test_quickfix_empty_run() {
+        let run = WarningRun::new(Vec::new());
+
+        let output = QuickfixFormatter::new().format(&run).unwrap();
+        assert_eq!(output, NO_WARNINGS_SENTINEL);
+
+        let output = QuickfixFormatter::new().quiet(true).format(&run).unwrap();
+        assert_eq!(output, "");
+    }
+}
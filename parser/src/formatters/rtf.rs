@@ -0,0 +1,189 @@
+use crate::error::Result;
+use crate::formatters::{occurrence_suffix, FormatOptions, Formatter};
+use crate::models::{Severity, Warning, WarningRun, WarningType};
+
+/// Minimal RTF that keeps its structure when pasted into word processors and wikis
+#[derive(Default)]
+pub struct RtfFormatter {
+    options: FormatOptions,
+}
+
+/// Escape RTF control characters and encode non-ASCII text as `\uN?` escapes
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\t' => escaped.push_str("\\tab "),
+            '\n' => escaped.push_str("\\line "),
+            c if c.is_ascii() => escaped.push(c),
+            c => {
+                // RTF takes signed 16-bit UTF-16 code units, with `?` as the
+                // fallback for readers without Unicode support
+                let mut units = [0u16; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    escaped.push_str(&format!("\\u{}?", *unit as i16));
+                }
+            }
+        }
+    }
+    escaped
+}
+
+impl RtfFormatter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_options(mut self, options: FormatOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    fn severity_tag(&self, severity: &Severity) -> &str {
+        match severity {
+            Severity::Critical => "CRITICAL",
+            Severity::High => "HIGH",
+            Severity::Medium => "MEDIUM",
+            Severity::Low => "LOW",
+        }
+    }
+
+    fn warning_type_label(&self, warning_type: &WarningType) -> &str {
+        if let Some(label) = self.options.bucket_label(*warning_type) {
+            return label;
+        }
+        match warning_type {
+            WarningType::ActorIsolation => "Actor Isolation",
+            WarningType::SendableConformance => "Sendable Conformance",
+            WarningType::DataRace => "Data Race",
+            WarningType::PerformanceRegression => "Performance Regression",
+            WarningType::DeprecatedConcurrencyApi => "Deprecated Concurrency API",
+            WarningType::UnsafeEscapeHatch => "Unsafe Escape Hatch",
+            WarningType::Unknown => "Unknown",
+        }
+    }
+
+    fn push_warning(&self, output: &mut String, warning: &Warning, count: usize) {
+        output.push_str(&format!(
+            "{}{{\\b [{}]}} {} - {}:{}{}{}\\par\n",
+            escape(self.options.highlight_marker(warning)),
+            self.severity_tag(&warning.severity),
+            escape(self.warning_type_label(&warning.warning_type)),
+            escape(&warning.file_path.display().to_string()),
+            warning.line_number,
+            escape(&self.options.relative_location(warning)),
+            escape(&occurrence_suffix(count))
+        ));
+        output.push_str(&format!(
+            "{}\\par\n",
+            escape(&self.options.display_message(&warning.message))
+        ));
+        let collapsed = self.options.collapse_context && !warning.code_context.line.is_empty();
+        if collapsed {
+            output.push_str(&format!(
+                "{{\\f1 {}}}\\par\n",
+                escape(warning.code_context.line.trim())
+            ));
+        }
+        if let Some(decl) = self.options.type_declaration(warning) {
+            output.push_str(&format!(
+                "{{\\i Declared at line {}:}} {{\\f1 {}}}\\par\n",
+                decl.line_number,
+                escape(&decl.text)
+            ));
+        }
+
+        if !collapsed && !warning.code_context.line.is_empty() {
+            let (context, caret) = self.options.display_context(warning);
+
+            output.push_str("{\\f1\\fs20 ");
+            for line in &context.before {
+                output.push_str(&format!("  {}\\line\n", escape(line)));
+            }
+            output.push_str(&format!("> {}\\line\n", escape(&context.line)));
+            if let Some(offset) = caret {
+                output.push_str(&format!("  {}^\\line\n", " ".repeat(offset)));
+            }
+            for line in &context.after {
+                output.push_str(&format!("  {}\\line\n", escape(line)));
+            }
+            output.push_str("}\\par\n");
+        }
+
+        if let Some(fix) = &warning.suggested_fix {
+            output.push_str(&format!("{{\\i Fix:}} {}\\par\n", escape(fix)));
+        }
+        output.push_str("\\par\n");
+    }
+}
+
+impl Formatter for RtfFormatter {
+    fn format(&self, run: &WarningRun) -> Result<String> {
+        let mut output = String::new();
+
+        output.push_str("{\\rtf1\\ansi\\deff0\n");
+        output.push_str("{\\fonttbl{\\f0\\fswiss Helvetica;}{\\f1\\fmodern Courier New;}}\n");
+        output.push_str("\\f0\\fs22\n");
+        output.push_str("{\\b\\fs32 Swift Concurrency Warnings Report}\\par\n");
+        output.push_str(&format!(
+            "{{\\b Total Warnings:}} {}\\par\n",
+            run.total_warnings
+        ));
+        output.push_str(&format!(
+            "{{\\b Errors in Swift 6:}} {}\\par\n",
+            run.swift6_error_count
+        ));
+        output.push_str(&format!(
+            "{{\\b Generated:}} {}\\par\n",
+            run.created_at.format("%Y-%m-%d %H:%M:%S UTC")
+        ));
+        if let Some(commit) = &run.commit_sha {
+            output.push_str(&format!("{{\\b Commit:}} {}\\par\n", escape(commit)));
+        }
+        if let Some(branch) = &run.branch {
+            output.push_str(&format!("{{\\b Branch:}} {}\\par\n", escape(branch)));
+        }
+        output.push_str("\\par\n");
+
+        for (warning, count) in self.options.occurrences(run.iter()) {
+            self.push_warning(&mut output, warning, count);
+        }
+
+        output.push('}');
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::CodeContext;
+
+    #[test]
+    fn test_rtf_structure_and_escaping() {
+        let run = WarningRun::new(vec![Warning {
+            id: "a".to_string(),
+            column_number: Some(5),
+            message: "actor-isolated property 'cache' can not be referenced — see {docs}"
+                .to_string(),
+            code_context: CodeContext {
+                before: vec!["func load() {".to_string()],
+                line: "    print(\"\\(cache)\")".to_string(),
+                after: vec!["}".to_string()],
+            },
+            ..Warning::fixture("/src/Store.swift", 3)
+        }]);
+
+        let rtf = RtfFormatter::new().format(&run).unwrap();
+        assert!(rtf.starts_with("{\\rtf1\\ansi"));
+        assert!(rtf.ends_with('}'));
+        assert!(rtf.contains("{\\b [HIGH]} Actor Isolation - /src/Store.swift:3\\par"));
+        assert!(rtf.contains("\\u8212? see \\{docs\\}"));
+        assert!(rtf.contains("{\\f1\\fs20   func load() \\{\\line"));
+        assert!(rtf.contains(">     print(\"\\\\(cache)\")\\line"));
+    }
+}
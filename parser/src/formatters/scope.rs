@@ -0,0 +1,240 @@
+use crate::models::Warning;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+lazy_static! {
+    // A `func`, `init`, `deinit` or computed `var` declaration line, allowing
+    // leading attributes and modifiers
+    static ref DECLARATION: Regex = Regex::new(
+        r"^\s*(?:@\w+(?:\([^)]*\))?\s+)*(?:(?:public|private|fileprivate|internal|open|package|static|class|override|final|mutating|nonmutating|nonisolated|convenience|required|async)\s+)*(?:func\s+(?P<func>[\w$]+|`[^`]+`)|(?P<init>init)\s*[?!]?\s*[(<]|(?P<deinit>deinit)\b|var\s+(?P<var>\w+)\s*:[^=]*\{)"
+    ).unwrap();
+
+    // A quoted type-like identifier in a message, e.g. `Box` in 'Box<T>'
+    static ref QUOTED_TYPE: Regex = Regex::new(r"'([A-Za-z_]\w*)(?:<[^']*>)?'").unwrap();
+}
+
+// Attributes and modifiers that may precede a type declaration
+const TYPE_PREFIX: &str = r"^\s*(?:@\w+(?:\([^)]*\))?\s+)*(?:(?:public|private|fileprivate|internal|open|package|final|indirect|distributed|nonisolated)\s+)*";
+
+/// The declaration enclosing a warning and the warning's offset within it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnclosingDeclaration {
+    /// e.g. `func load()`, `init`, `deinit` or `var title`
+    pub name: String,
+    /// Lines from the declaration line to the warning line
+    pub offset: usize,
+}
+
+fn declaration_name(line: &str) -> Option<String> {
+    let captures = DECLARATION.captures(line)?;
+    if let Some(name) = captures.name("func") {
+        Some(format!("func {}()", name.as_str()))
+    } else if captures.name("init").is_some() {
+        Some("init".to_string())
+    } else if captures.name("deinit").is_some() {
+        Some("deinit".to_string())
+    } else {
+        captures
+            .name("var")
+            .map(|name| format!("var {}", name.as_str()))
+    }
+}
+
+/// Find the nearest declaration at or above the warning line. The code context
+/// is searched first; when it has none, the warning's `source` file is read if
+/// available. This is a textual scan, not a parse, so nested scopes are not
+/// tracked.
+pub fn enclosing_declaration(warning: &Warning, source: &Path) -> Option<EnclosingDeclaration> {
+    let context = &warning.code_context;
+    let first_line = warning.line_number.checked_sub(context.before.len())?;
+
+    let in_context = std::iter::once(&context.line)
+        .chain(context.before.iter().rev())
+        .enumerate()
+        .find_map(|(offset, line)| {
+            declaration_name(line).map(|name| EnclosingDeclaration { name, offset })
+        });
+    if in_context.is_some() {
+        return in_context;
+    }
+
+    // Extend the search above the context window using the source file
+    let file = File::open(source).ok()?;
+    let lines: Vec<String> = BufReader::new(file)
+        .lines()
+        .take(first_line.saturating_sub(1))
+        .collect::<std::io::Result<_>>()
+        .ok()?;
+    lines.iter().enumerate().rev().find_map(|(index, line)| {
+        declaration_name(line).map(|name| EnclosingDeclaration {
+            name,
+            offset: warning.line_number - (index + 1),
+        })
+    })
+}
+
+/// The declaration line of a type named in a warning message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeDeclaration {
+    pub line_number: usize,
+    /// The declaration line, trimmed
+    pub text: String,
+}
+
+/// Find the `struct`/`class`/`enum`/`actor` declaration of the first quoted
+/// identifier in the message that is declared in the warning's `source` file.
+/// `None` when the file can't be read or declares none of them.
+pub fn type_declaration(warning: &Warning, source: &Path) -> Option<TypeDeclaration> {
+    let names: Vec<&str> = QUOTED_TYPE
+        .captures_iter(&warning.message)
+        .filter_map(|captures| captures.get(1).map(|m| m.as_str()))
+        .collect();
+    if names.is_empty() {
+        return None;
+    }
+
+    let file = File::open(source).ok()?;
+    let lines: Vec<String> = BufReader::new(file)
+        .lines()
+        .collect::<std::io::Result<_>>()
+        .ok()?;
+
+    names.iter().find_map(|name| {
+        let pattern = Regex::new(&format!(
+            r"{TYPE_PREFIX}(?:struct|class|enum|actor)\s+{}\b",
+            regex::escape(name)
+        ))
+        .ok()?;
+        lines
+            .iter()
+            .position(|line| pattern.is_match(line))
+            .map(|index| TypeDeclaration {
+                line_number: index + 1,
+                text: lines[index].trim().to_string(),
+            })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::CodeContext;
+    use std::io::Write;
+    use std::path::PathBuf;
+
+    fn warning(file_path: PathBuf, line_number: usize, code_context: CodeContext) -> Warning {
+        Warning {
+            id: "id".to_string(),
+            code_context,
+            ..Warning::fixture(file_path, line_number)
+        }
+    }
+
+    #[test]
+    fn test_type_declaration_for_sendable_warning() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(
+            file,
+            "import Foundation\n\n@MainActor\npublic final class Box<T> {{\n    var value: T\n}}\n\nlet shared = Box(value: 1)"
+        )
+        .unwrap();
+        // Read from `source`, not the reported (e.g. remapped) path
+        let mut sendable = warning(
+            PathBuf::from("/ci/Sources/Box.swift"),
+            8,
+            CodeContext::default(),
+        );
+        sendable.message =
+            "type 'Box<Int>' does not conform to the 'Sendable' protocol".to_string();
+
+        assert_eq!(
+            type_declaration(&sendable, file.path()),
+            Some(TypeDeclaration {
+                line_number: 4,
+                text: "public final class Box<T> {".to_string(),
+            })
+        );
+
+        sendable.message = "type 'Missing' does not conform to the 'Sendable' protocol".to_string();
+        assert_eq!(type_declaration(&sendable, file.path()), None);
+        sendable.message =
+            "type 'Box<Int>' does not conform to the 'Sendable' protocol".to_string();
+        assert_eq!(
+            type_declaration(&sendable, Path::new("/missing.swift")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_declaration_names() {
+        assert_eq!(
+            declaration_name("    @MainActor private func refresh(items: [Item]) async {"),
+            Some("func refresh()".to_string())
+        );
+        assert_eq!(
+            declaration_name("    convenience init?(coder: NSCoder) {"),
+            Some("init".to_string())
+        );
+        assert_eq!(declaration_name("    deinit {"), Some("deinit".to_string()));
+        assert_eq!(
+            declaration_name("    var title: String {"),
+            Some("var title".to_string())
+        );
+        assert_eq!(declaration_name("    var count = 0"), None);
+        assert_eq!(declaration_name("        model.refresh()"), None);
+    }
+
+    #[test]
+    fn test_enclosing_declaration_in_context() {
+        let context = CodeContext::new(
+            vec![
+                "    func increment() {".to_string(),
+                "        Task {".to_string(),
+            ],
+            "            self.model.count += 1".to_string(),
+            vec!["        }".to_string()],
+        );
+        let found = enclosing_declaration(
+            &warning(PathBuf::from("/missing.swift"), 37, context),
+            Path::new("/missing.swift"),
+        );
+
+        assert_eq!(
+            found,
+            Some(EnclosingDeclaration {
+                name: "func increment()".to_string(),
+                offset: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn test_enclosing_declaration_from_file() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(
+            file,
+            "final class Loader {{\n    func load() async {{\n        let a = 1\n        let b = 2\n        let c = 3\n        cache.store(a + b + c)\n    }}\n}}"
+        )
+        .unwrap();
+        let context = CodeContext::new(
+            vec!["        let c = 3".to_string()],
+            "        cache.store(a + b + c)".to_string(),
+            vec![],
+        );
+        let found = enclosing_declaration(
+            &warning(PathBuf::from("/ci/Sources/Loader.swift"), 6, context),
+            file.path(),
+        );
+
+        assert_eq!(
+            found,
+            Some(EnclosingDeclaration {
+                name: "func load()".to_string(),
+                offset: 4,
+            })
+        );
+    }
+}
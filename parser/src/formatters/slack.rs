@@ -1,30 +1,140 @@
 use crate::error::Result;
-use crate::formatters::Formatter;
-use crate::models::{WarningRun, WarningType};
-use serde_json::json;
+use crate::formatters::{occurrence_suffix, FormatOptions, Formatter, Tier};
+use crate::models::{Severity, Warning, WarningRun, WarningType};
+use serde_json::{json, Value};
+
+/// Warnings listed per section before the rest are summarized
+const MAX_WARNINGS: usize = 10;
 
 #[derive(Default)]
-pub struct SlackFormatter;
+pub struct SlackFormatter {
+    options: FormatOptions,
+    summary_only: bool,
+}
 
 impl SlackFormatter {
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    pub fn with_options(mut self, options: FormatOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Omit per-warning attachments, keeping the header and summary plus a
+    /// color bar for the worst severity
+    pub fn summary_only(mut self, summary_only: bool) -> Self {
+        self.summary_only = summary_only;
+        self
+    }
+
+    fn severity_color(&self, severity: &Severity) -> &str {
+        match severity {
+            Severity::Critical => "danger",
+            Severity::High => "warning",
+            Severity::Medium => "#439FE0",
+            Severity::Low => "#A0A0A0",
+        }
     }
 
     fn warning_type_label(&self, warning_type: &WarningType) -> &str {
+        if let Some(label) = self.options.bucket_label(*warning_type) {
+            return label;
+        }
         match warning_type {
             WarningType::ActorIsolation => "Actor Isolation",
             WarningType::SendableConformance => "Sendable Conformance",
             WarningType::DataRace => "Data Race",
             WarningType::PerformanceRegression => "Performance Regression",
+            WarningType::DeprecatedConcurrencyApi => "Deprecated Concurrency API",
+            WarningType::UnsafeEscapeHatch => "Unsafe Escape Hatch",
             WarningType::Unknown => "Unknown",
         }
     }
+
+    /// One attachment per warning, at most [`MAX_WARNINGS`] and then a note
+    /// of how many more there are
+    fn push_warnings<'a>(
+        &self,
+        attachments: &mut Vec<Value>,
+        warnings: impl IntoIterator<Item = &'a Warning>,
+    ) {
+        let entries = self.options.occurrences(warnings);
+        for (warning, count) in entries.iter().take(MAX_WARNINGS) {
+            attachments.push(self.warning_attachment(warning, *count));
+        }
+        if entries.len() > MAX_WARNINGS {
+            attachments.push(json!({
+                "blocks": [{
+                    "type": "section",
+                    "text": {
+                        "type": "mrkdwn",
+                        "text": format!("_... and {} more warnings_", entries.len() - MAX_WARNINGS)
+                    }
+                }]
+            }));
+        }
+    }
+
+    fn warning_attachment(&self, warning: &Warning, count: usize) -> Value {
+        let location = match self
+            .options
+            .permalinks
+            .as_ref()
+            .and_then(|p| p.link(warning))
+        {
+            Some(link) => format!("<{}|{}>", link, warning.file_path.display()),
+            None => format!("`{}`", warning.file_path.display()),
+        };
+
+        // Attachments carry a color bar keyed on severity
+        json!({
+            "color": self.severity_color(&warning.severity),
+            "blocks": [{
+                "type": "section",
+                "text": {
+                    "type": "mrkdwn",
+                    "text": format!(
+                        "{}*{}* in {}{}\nLine {}{}: {}{}{}",
+                        self.options.highlight_marker(warning),
+                        self.warning_type_label(&warning.warning_type),
+                        location,
+                        occurrence_suffix(count),
+                        warning.line_number,
+                        self.options.relative_location(warning),
+                        self.options.display_message(&warning.message),
+                        self.options
+                            .type_declaration(warning)
+                            .map(|decl| format!(
+                                "\nDeclared at line {}: `{}`",
+                                decl.line_number, decl.text
+                            ))
+                            .unwrap_or_default(),
+                        self.options
+                            .docs
+                            .url(warning.warning_type)
+                            .map(|url| format!("\n<{url}|Learn more>"))
+                            .unwrap_or_default()
+                    )
+                },
+                "accessory": {
+                    "type": "button",
+                    "text": {
+                        "type": "plain_text",
+                        "text": "View"
+                    },
+                    "value": warning.id.clone()
+                }
+            }]
+        })
+    }
 }
 
 impl Formatter for SlackFormatter {
     fn format(&self, run: &WarningRun) -> Result<String> {
         let mut blocks = Vec::new();
+        let mut attachments = Vec::new();
 
         // Header block
         blocks.push(json!({
@@ -54,53 +164,155 @@ impl Formatter for SlackFormatter {
             }
         }));
 
-        // Add warning details if any exist
-        if !run.warnings.is_empty() {
+        if self.summary_only {
+            let worst = [
+                Severity::Critical,
+                Severity::High,
+                Severity::Medium,
+                Severity::Low,
+            ]
+            .into_iter()
+            .find(|severity| run.iter().any(|w| w.severity == *severity));
+
+            if let Some(worst) = worst {
+                attachments.push(json!({
+                    "color": self.severity_color(&worst),
+                    "blocks": [{
+                        "type": "context",
+                        "elements": [{
+                            "type": "mrkdwn",
+                            "text": format!("Worst severity: *{}*", worst.as_str())
+                        }]
+                    }]
+                }));
+            }
+        } else if !run.warnings.is_empty() {
+            // Add warning details
             blocks.push(json!({
                 "type": "divider"
             }));
 
-            for (i, warning) in run.warnings.iter().enumerate() {
-                if i >= 10 {
-                    // Limit to first 10 warnings for Slack
-                    blocks.push(json!({
-                        "type": "section",
-                        "text": {
-                            "type": "mrkdwn",
-                            "text": format!("_... and {} more warnings_", run.warnings.len() - 10)
-                        }
+            if self.options.tiered {
+                for tier in Tier::ALL {
+                    let warnings: Vec<&Warning> = run
+                        .iter()
+                        .filter(|w| Tier::of(w.severity) == tier)
+                        .collect();
+                    attachments.push(json!({
+                        "blocks": [{
+                            "type": "section",
+                            "text": {
+                                "type": "mrkdwn",
+                                "text": format!("*{}* ({})", tier.label(), warnings.len())
+                            }
+                        }]
                     }));
-                    break;
+                    self.push_warnings(&mut attachments, warnings);
                 }
-
-                blocks.push(json!({
-                    "type": "section",
-                    "text": {
-                        "type": "mrkdwn",
-                        "text": format!(
-                            "*{}* in `{}`\nLine {}: {}",
-                            self.warning_type_label(&warning.warning_type),
-                            warning.file_path.display(),
-                            warning.line_number,
-                            warning.message
-                        )
-                    },
-                    "accessory": {
-                        "type": "button",
-                        "text": {
-                            "type": "plain_text",
-                            "text": "View"
-                        },
-                        "value": warning.id.clone()
-                    }
-                }));
+            } else {
+                self.push_warnings(&mut attachments, &run.warnings);
             }
         }
 
         let slack_message = json!({
-            "blocks": blocks
+            "blocks": blocks,
+            "attachments": attachments
         });
 
         Ok(serde_json::to_string_pretty(&slack_message)?)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn warning(warning_type: WarningType, severity: Severity) -> Warning {
+        Warning {
+            id: "id".to_string(),
+            warning_type,
+            severity,
+            message: "data race detected".to_string(),
+            ..Warning::fixture("/src/Queue.swift", 95)
+        }
+    }
+
+    #[test]
+    fn test_attachment_color_reflects_severity() {
+        let run = WarningRun::new(vec![
+            warning(WarningType::DataRace, Severity::Critical),
+            warning(WarningType::ActorIsolation, Severity::High),
+        ]);
+
+        let output = SlackFormatter::new().format(&run).unwrap();
+        let message: Value = serde_json::from_str(&output).unwrap();
+        let attachments = message["attachments"].as_array().unwrap();
+
+        assert_eq!(attachments.len(), 2);
+        assert_eq!(attachments[0]["color"], "danger");
+        assert_eq!(attachments[1]["color"], "warning");
+        assert!(attachments[0]["blocks"][0]["text"]["text"]
+            .as_str()
+            .unwrap()
+            .starts_with("*Data Race*"));
+    }
+
+    #[test]
+    fn test_summary_only_omits_warning_sections() {
+        let run = WarningRun::new(vec![
+            warning(WarningType::ActorIsolation, Severity::Medium),
+            warning(WarningType::DataRace, Severity::High),
+            warning(WarningType::SendableConformance, Severity::Low),
+        ]);
+
+        let output = SlackFormatter::new()
+            .summary_only(true)
+            .format(&run)
+            .unwrap();
+        let message: Value = serde_json::from_str(&output).unwrap();
+
+        let blocks = message["blocks"].as_array().unwrap();
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0]["type"], "header");
+        assert_eq!(
+            blocks[1]["text"]["text"],
+            "⚠️ Found 3 Swift concurrency warnings"
+        );
+
+        let attachments = message["attachments"].as_array().unwrap();
+        assert_eq!(attachments.len(), 1);
+        assert_eq!(attachments[0]["color"], "warning");
+        assert!(!output.contains("data race detected"));
+        assert!(!output.contains("Queue.swift"));
+    }
+
+    #[test]
+    fn test_tiered_sections() {
+        let run = WarningRun::new(vec![
+            warning(WarningType::SendableConformance, Severity::Low),
+            warning(WarningType::DataRace, Severity::Critical),
+        ]);
+        let options = FormatOptions {
+            tiered: true,
+            ..FormatOptions::default()
+        };
+
+        let output = SlackFormatter::new()
+            .with_options(options)
+            .format(&run)
+            .unwrap();
+        let message: Value = serde_json::from_str(&output).unwrap();
+        let texts: Vec<&str> = message["attachments"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|a| a["blocks"][0]["text"]["text"].as_str().unwrap())
+            .collect();
+
+        assert_eq!(texts.len(), 4);
+        assert_eq!(texts[0], "*Action Required* (1)");
+        assert!(texts[1].starts_with("*Data Race*"));
+        assert_eq!(texts[2], "*For Awareness* (1)");
+        assert!(texts[3].starts_with("*Sendable Conformance*"));
+    }
+}
@@ -0,0 +1,136 @@
+use crate::cli::Taxonomy;
+use crate::models::{Severity, WarningDiff, WarningRun, WarningType};
+use crate::parser::warnings::taxonomy_type;
+
+/// Files listed in the summary's density line
+const MAX_DENSITY_FILES: usize = 5;
+
+/// Warnings listed in the GitHub job summary
+const MAX_STEP_SUMMARY_WARNINGS: usize = 5;
+
+/// Name of the `--taxonomy` bucket `warning_type` stands for in the summary
+fn bucket_name(warning_type: WarningType, taxonomy: Taxonomy) -> &'static str {
+    match (taxonomy, warning_type) {
+        (Taxonomy::Coarse, WarningType::ActorIsolation) => "isolation",
+        (Taxonomy::Coarse, WarningType::SendableConformance) => "sendability",
+        _ => warning_type.as_str(),
+    }
+}
+
+/// A short human-readable summary: totals by type (or `--taxonomy` bucket) and
+/// severity plus the gate result
+pub fn format_summary(run: &WarningRun, passed: bool, taxonomy: Taxonomy) -> String {
+    let mut output = format!(
+        "SwiftConcur: {} warning{} - {}\n",
+        run.total_warnings,
+        if run.total_warnings == 1 { "" } else { "s" },
+        if passed { "PASS" } else { "FAIL" }
+    );
+
+    let by_type: Vec<String> = WarningType::ALL
+        .iter()
+        .filter_map(|t| {
+            let count = run
+                .iter()
+                .filter(|w| taxonomy_type(w.warning_type, taxonomy) == *t)
+                .count();
+            (count > 0).then(|| format!("{}={}", bucket_name(*t, taxonomy), count))
+        })
+        .collect();
+
+    let by_severity: Vec<String> = [
+        Severity::Critical,
+        Severity::High,
+        Severity::Medium,
+        Severity::Low,
+    ]
+    .iter()
+    .filter_map(|s| {
+        let count = run.iter().filter(|w| w.severity == *s).count();
+        (count > 0).then(|| format!("{}={}", s.as_str(), count))
+    })
+    .collect();
+
+    if !by_type.is_empty() {
+        output.push_str(&format!("  By type: {}\n", by_type.join(", ")));
+        output.push_str(&format!("  By severity: {}\n", by_severity.join(", ")));
+        output.push_str(&format!("  Swift 6 errors: {}\n", run.swift6_error_count));
+        output.push_str(&format!("  Effort points: {}\n", run.effort_points));
+    }
+
+    // Densest first; files that couldn't be read have no density to show
+    let densest: Vec<String> = run
+        .files
+        .iter()
+        .filter_map(|file| {
+            file.density
+                .map(|density| format!("{} {density:.1}", file.path.display()))
+        })
+        .take(MAX_DENSITY_FILES)
+        .collect();
+    if !densest.is_empty() {
+        output.push_str(&format!(
+            "  Warnings per 100 lines: {}\n",
+            densest.join(", ")
+        ));
+    }
+
+    output
+}
+
+/// Concise Markdown for a GitHub Actions job summary (`$GITHUB_STEP_SUMMARY`):
+/// counts by severity, the worst severity, the baseline delta and the most
+/// severe warnings
+pub fn format_step_summary(run: &WarningRun, diff: Option<&WarningDiff>, passed: bool) -> String {
+    let mut output = format!(
+        "### SwiftConcur: {} warning{} - {}\n\n",
+        run.total_warnings,
+        if run.total_warnings == 1 { "" } else { "s" },
+        if passed { "✅ PASS" } else { "❌ FAIL" }
+    );
+
+    let counts: Vec<(Severity, usize)> = Severity::ALL
+        .into_iter()
+        .map(|s| (s, run.iter().filter(|w| w.severity == s).count()))
+        .filter(|(_, count)| *count > 0)
+        .collect();
+    if let Some((worst, _)) = counts.first() {
+        output.push_str("| Severity | Count |\n|---|---|\n");
+        for (severity, count) in &counts {
+            output.push_str(&format!("| {} | {count} |\n", severity.as_str()));
+        }
+        output.push_str(&format!("\n**Worst severity:** {}\n", worst.as_str()));
+    }
+    if let Some(diff) = diff {
+        output.push_str(&format!(
+            "\n**Baseline:** +{} new, -{} fixed\n",
+            diff.new.len(),
+            diff.fixed.len()
+        ));
+    }
+
+    let mut top: Vec<_> = run.iter().collect();
+    // Stable, so warnings of equal severity keep their report order
+    top.sort_by_key(|w| Severity::ALL.iter().position(|s| *s == w.severity));
+    if !top.is_empty() {
+        output.push_str("\n**Top warnings**\n\n");
+        for warning in top.iter().take(MAX_STEP_SUMMARY_WARNINGS) {
+            output.push_str(&format!(
+                "- **{}** `{}:{}` {} ({})\n",
+                warning.severity.as_str(),
+                warning.file_path.display(),
+                warning.line_number,
+                warning.message,
+                warning.warning_type.as_str()
+            ));
+        }
+        if top.len() > MAX_STEP_SUMMARY_WARNINGS {
+            output.push_str(&format!(
+                "- …and {} more\n",
+                top.len() - MAX_STEP_SUMMARY_WARNINGS
+            ));
+        }
+    }
+
+    output
+}
@@ -0,0 +1,121 @@
+use crate::error::Result;
+use crate::formatters::Formatter;
+use crate::models::WarningRun;
+use serde::{Deserialize, Serialize};
+
+/// Root `<warnings>` element of the XML report
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename = "warnings")]
+pub struct XmlReport {
+    #[serde(rename = "@total")]
+    pub total: usize,
+    #[serde(rename = "@generated")]
+    pub generated: String,
+    #[serde(rename = "warning", default)]
+    pub warnings: Vec<XmlWarning>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct XmlWarning {
+    #[serde(rename = "@type")]
+    pub warning_type: String,
+    #[serde(rename = "@severity")]
+    pub severity: String,
+    #[serde(rename = "@file")]
+    pub file: String,
+    #[serde(rename = "@line")]
+    pub line: usize,
+    #[serde(rename = "@column", skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
+    pub message: String,
+    #[serde(rename = "suggestedFix", skip_serializing_if = "Option::is_none")]
+    pub suggested_fix: Option<String>,
+}
+
+#[derive(Default)]
+pub struct XmlFormatter;
+
+impl XmlFormatter {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Formatter for XmlFormatter {
+    fn format(&self, run: &WarningRun) -> Result<String> {
+        let report = XmlReport {
+            total: run.total_warnings,
+            generated: run.created_at.to_rfc3339(),
+            warnings: run
+                .iter()
+                .map(|warning| XmlWarning {
+                    warning_type: warning.warning_type.as_str().to_string(),
+                    severity: warning.severity.as_str().to_string(),
+                    file: warning.file_path.display().to_string(),
+                    line: warning.line_number,
+                    column: warning.column_number,
+                    message: warning.message.clone(),
+                    suggested_fix: warning.suggested_fix.clone(),
+                })
+                .collect(),
+        };
+
+        let mut output = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let mut serializer = quick_xml::se::Serializer::new(&mut output);
+        serializer.indent(' ', 2);
+        report.serialize(serializer)?;
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Severity, Warning, WarningType};
+
+    #[test]
+    fn test_xml_round_trip() {
+        let run = WarningRun::new(vec![
+            Warning {
+                id: "a".to_string(),
+                warning_type: WarningType::SendableConformance,
+                column_number: Some(4),
+                message: "Type 'Box<T>' does not conform to the 'Sendable' protocol".to_string(),
+                suggested_fix: Some("Add \"Sendable\" conformance".to_string()),
+                ..Warning::fixture("/src/Foo & Bar.swift", 12)
+            },
+            Warning {
+                id: "b".to_string(),
+                warning_type: WarningType::DataRace,
+                severity: Severity::Critical,
+                message: "data race detected".to_string(),
+                ..Warning::fixture("/src/Queue.swift", 95)
+            },
+        ]);
+
+        let xml = XmlFormatter::new().format(&run).unwrap();
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>"));
+        assert!(xml.contains("Box&lt;T&gt;"));
+        assert!(xml.contains("Foo &amp; Bar.swift"));
+
+        let body = xml.split_once("?>").unwrap().1;
+        let report: XmlReport = quick_xml::de::from_str(body).unwrap();
+
+        assert_eq!(report.total, 2);
+        assert_eq!(report.generated, run.created_at.to_rfc3339());
+        assert_eq!(
+            report.warnings[0],
+            XmlWarning {
+                warning_type: "sendable_conformance".to_string(),
+                severity: "high".to_string(),
+                file: "/src/Foo & Bar.swift".to_string(),
+                line: 12,
+                column: Some(4),
+                message: "Type 'Box<T>' does not conform to the 'Sendable' protocol".to_string(),
+                suggested_fix: Some("Add \"Sendable\" conformance".to_string()),
+            }
+        );
+        assert_eq!(report.warnings[1].column, None);
+        assert_eq!(report.warnings[1].suggested_fix, None);
+    }
+}
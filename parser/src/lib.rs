@@ -1,102 +1,1031 @@
+pub mod baseline;
+pub mod changes;
 pub mod cli;
+pub mod config;
+pub mod encoding;
 pub mod error;
 pub mod formatters;
 pub mod models;
 pub mod parser;
+pub mod profile;
+pub mod scan;
+pub mod seen;
+pub mod trend;
 
-use cli::{Cli, OutputFormat};
-use error::Result;
-use formatters::{Formatter, JsonFormatter, MarkdownFormatter, SlackFormatter};
-use models::WarningRun;
-use parser::{check_threshold, filter_warnings, RawLogParser, XcodeBuildParser, XcresultParser};
+use cli::{Cli, Command, InputEncoding, OutputFormat, TrendArgs, TrendFormat, ValidateArgs};
+use config::Config;
+use error::{ParseError, Result};
+use formatters::{
+    format_fix_script, format_step_summary, format_summary, CategoryMap, CsvFormatter,
+    DebtFormatter, FormatOptions, Formatter, GrepFormatter, JsonFormatter, JunitFormatter,
+    MarkdownFormatter, Permalinks, QuickfixFormatter, RtfFormatter, SlackFormatter, SourcePaths,
+    XmlFormatter,
+};
+use models::{Warning, WarningRun};
+use parser::{
+    affected_files, anonymize_path, anonymize_paths, check_affected_files, check_threshold,
+    count_lines, dedup_warnings, dedup_window, detect_format, downrank_tests, drop_generated,
+    escalate_hotspots, filter_warnings, is_generated_path, random_salt, split_uncategorized,
+    xcresult_document, DetectionOptions, InputFormat, PatternPack, RawLogParser, SourceMap,
+    TestPaths, XcodeBuildParser, XcresultParser,
+};
+use profile::Profile;
+use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs::File;
-use std::io::{self, BufReader};
+use std::io::{self, BufRead, BufReader, BufWriter, Cursor, Read, Write};
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 
 pub fn run(cli: Cli) -> Result<i32> {
-    // Parse input - detect format and use appropriate parser with fallbacks
-    let warnings = if cli.input == "-" {
-        let stdin = io::stdin();
-        let reader = BufReader::new(stdin.lock());
-
-        // Try XcodeBuildParser first (JSON), fall back to RawLogParser
-        let xcodebuild_parser = XcodeBuildParser::new(cli.context);
-        match xcodebuild_parser.parse_stream(reader) {
-            Ok(warnings) if !warnings.is_empty() => warnings,
-            _ => {
-                // Fallback: re-read stdin as raw log format
-                let stdin = io::stdin();
-                let reader = BufReader::new(stdin.lock());
-                let rawlog_parser = RawLogParser::new(cli.context);
-                rawlog_parser.parse_stream(reader)?
-            }
+    run_with_transform(cli, Some)
+}
+
+/// Like [`run`], but passes every warning through `transform` after parsing and
+/// filtering, before formatting and gating; `None` drops the warning. Counts
+/// are recomputed, so embedders can redact paths or rewrite messages. With
+/// `--fail-fast`, `transform` is also asked about each warning parsing could
+/// stop at, so a warning it drops never ends parsing.
+pub fn run_with_transform<F>(cli: Cli, mut transform: F) -> Result<i32>
+where
+    F: FnMut(Warning) -> Option<Warning>,
+{
+    match &cli.command {
+        Some(Command::Trend(args)) => return run_trend(args),
+        Some(Command::Validate(args)) => return run_validate(args),
+        None => {}
+    }
+    if cli.print_config {
+        return print_config(&cli);
+    }
+
+    let profile = Profile::new();
+    let config = cli_config(&cli)?;
+    let (run, parsed, baseline, sources) = collect_run(&cli, &config, &profile, &mut transform)?;
+    let run = run.filter_map(transform);
+    if let Some(code) = fail_fast_exit(&cli, &run) {
+        return Ok(code);
+    }
+    let options = format_options(&cli, &config, sources)?;
+
+    // Format output
+    let formatter: Box<dyn Formatter> = match cli.format {
+        OutputFormat::Json => Box::new(JsonFormatter::new().with_category_map(category_map(&cli)?)),
+        OutputFormat::Markdown => Box::new(
+            MarkdownFormatter::new()
+                .with_options(options)
+                .group_by(cli.group_by),
+        ),
+        OutputFormat::Slack => Box::new(
+            SlackFormatter::new()
+                .with_options(options)
+                .summary_only(cli.slack_summary_only),
+        ),
+        OutputFormat::Grep => Box::new(
+            GrepFormatter::new()
+                .with_columns(&cli.columns)
+                .quiet(cli.quiet),
+        ),
+        OutputFormat::Csv => Box::new(
+            CsvFormatter::new()
+                .with_columns(&cli.columns)
+                .with_category_map(category_map(&cli)?),
+        ),
+        OutputFormat::Xml => Box::new(XmlFormatter::new()),
+        OutputFormat::Junit => {
+            Box::new(JunitFormatter::new().with_suite_name(cli.junit_suite_name.clone()))
+        }
+        OutputFormat::Quickfix => Box::new(QuickfixFormatter::new().quiet(cli.quiet)),
+        OutputFormat::Rtf => Box::new(RtfFormatter::new().with_options(options)),
+        OutputFormat::Debt => Box::new(DebtFormatter::new(repo_root(&cli)?)),
+        OutputFormat::Proto => {
+            profile.time("write", || write_proto(&run, cli.output.as_deref()))?;
+            return finish(&cli, &run, parsed, baseline.as_ref(), &profile);
+        }
+    };
+
+    write_report(&cli, formatter.as_ref(), &run, &profile)?;
+    finish(&cli, &run, parsed, baseline.as_ref(), &profile)
+}
+
+/// Like [`run`], but renders the report with `formatter` instead of the one
+/// selected by `--format`. Parsing, filtering, output and gating are unchanged.
+pub fn run_with_formatter(cli: Cli, formatter: Box<dyn Formatter>) -> Result<i32> {
+    let profile = Profile::new();
+    let config = cli_config(&cli)?;
+    let (run, parsed, baseline, _) = collect_run(&cli, &config, &profile, &mut Some)?;
+    if let Some(code) = fail_fast_exit(&cli, &run) {
+        return Ok(code);
+    }
+    write_report(&cli, formatter.as_ref(), &run, &profile)?;
+    finish(&cli, &run, parsed, baseline.as_ref(), &profile)
+}
+
+/// With `--fail-fast`, report the warning parsing stopped at and return exit
+/// code 1 when the run fails the threshold, skipping the report
+fn fail_fast_exit(cli: &Cli, run: &WarningRun) -> Option<i32> {
+    if !cli.fail_fast || check_threshold(&run.warnings, cli.threshold, cli.threshold_op) {
+        return None;
+    }
+    if let Some(w) = run.warnings.first() {
+        eprintln!(
+            "Fail-fast: {}:{}: {}",
+            w.file_path.display(),
+            w.line_number,
+            w.message
+        );
+    }
+    Some(1)
+}
+
+/// Whether `warning` survives the type and generated-code filters, so
+/// `--fail-fast` can stop parsing at it
+fn fails_fast(cli: &Cli, warning: &Warning) -> bool {
+    let matches_filter = !filter_warnings(vec![warning.clone()], cli.filter).is_empty();
+    matches_filter && !(cli.ignore_generated && is_generated_path(&warning.file_path))
+}
+
+/// Fail with the list of source files that can't be opened for code context
+fn require_sources(warnings: &[Warning], source_map: &SourceMap) -> Result<()> {
+    let unreadable: BTreeSet<&Path> = warnings
+        .iter()
+        .map(|w| w.file_path.as_path())
+        .filter(|path| std::fs::File::open(source_map.resolve(path)).is_err())
+        .collect();
+    if unreadable.is_empty() {
+        return Ok(());
+    }
+    let list: Vec<String> = unreadable
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect();
+    Err(ParseError::MissingSources(list.join(", ")))
+}
+
+/// `--repo-root`, or the working directory when none is given
+fn repo_root(cli: &Cli) -> Result<PathBuf> {
+    match &cli.repo_root {
+        Some(root) => Ok(root.clone()),
+        None => Ok(std::env::current_dir()?),
+    }
+}
+
+/// The `--category-map` file, or no renames when none is given
+fn category_map(cli: &Cli) -> Result<CategoryMap> {
+    match &cli.category_map {
+        Some(path) => CategoryMap::load(path),
+        None => Ok(CategoryMap::default()),
+    }
+}
+
+/// The `--config` file, or the defaults when none is given
+fn cli_config(cli: &Cli) -> Result<Config> {
+    match &cli.config {
+        Some(path) => config::load_config(path),
+        None => Ok(Config::default()),
+    }
+}
+
+/// Print the configuration in effect as one JSON document: every option after
+/// clap applied defaults and environment variables, the `--config` tables, and
+/// under `sources` where each of them came from
+fn print_config(cli: &Cli) -> Result<i32> {
+    let serde_json::Value::Object(mut effective) = serde_json::to_value(cli)? else {
+        unreachable!("options serialize to an object");
+    };
+    let mut sources: BTreeMap<String, String> = cli
+        .value_sources
+        .iter()
+        .filter(|(key, _)| effective.contains_key(*key))
+        .map(|(key, source)| (key.clone(), source.clone()))
+        .collect();
+    let table_source = match &cli.config {
+        Some(path) => format!("config {}", path.display()),
+        None => "default".to_string(),
+    };
+    if let serde_json::Value::Object(tables) = serde_json::to_value(cli_config(cli)?)? {
+        for (table, value) in tables {
+            sources.insert(table.clone(), table_source.clone());
+            effective.insert(table, value);
+        }
+    }
+    effective.insert("sources".to_string(), serde_json::to_value(sources)?);
+    println!("{}", serde_json::to_string_pretty(&effective)?);
+    Ok(0)
+}
+
+/// Read, parse and filter the input selected by `cli` into a run. Also returns
+/// how many warnings were parsed before filtering, or `None` for blank input,
+/// the `--baseline` run if one was given, and the on-disk source file of each
+/// reported path for formatters that read sources.
+fn collect_run(
+    cli: &Cli,
+    config: &Config,
+    profile: &Profile,
+    transform: &mut dyn FnMut(Warning) -> Option<Warning>,
+) -> Result<(WarningRun, Option<usize>, Option<WarningRun>, SourcePaths)> {
+    let baseline = match &cli.baseline {
+        Some(path) => Some(baseline::load_baseline(path, cli.baseline_format)?),
+        None => None,
+    };
+
+    let detection = DetectionOptions {
+        include_low_confidence: cli.include_low_confidence,
+        detect_deprecated: cli.detect_deprecated,
+        audit_unsafe: cli.audit_unsafe,
+        trim_message: cli.trim_message,
+        keep_unknown: cli.warn_on_unknown,
+        normalize_context: cli.normalize_context,
+        keep_raw: cli.keep_raw,
+    };
+    let source_map = match &cli.source_map {
+        Some(path) => SourceMap::load(path)?,
+        None => SourceMap::default(),
+    };
+    let patterns = match &cli.patterns {
+        Some(path) => PatternPack::load(path)?,
+        None => PatternPack::default(),
+    };
+
+    if cli.fail_fast
+        && cli
+            .threshold
+            .is_none_or(|threshold| cli.threshold_op.passes(1, threshold))
+    {
+        return Err(ParseError::InvalidFormat(
+            "--fail-fast needs a threshold a single warning exceeds (e.g. --threshold 0)"
+                .to_string(),
+        ));
+    }
+    let mut fail_fast =
+        |warning: &Warning| fails_fast(cli, warning) && transform(warning.clone()).is_some();
+    let settings = ParseSettings {
+        context: cli.context,
+        detection,
+        source_map: &source_map,
+        patterns: &patterns,
+        xcresult_issue_types: &cli.xcresult_issue_types,
+        fallback: !cli.no_fallback,
+    };
+
+    let context_before = profile::context_time();
+    let paths = if let Some(pattern) = &cli.input_glob {
+        let paths = profile.time("read", || scan::expand_glob(pattern))?;
+        if paths.is_empty() && !cli.allow_empty {
+            return Err(ParseError::InvalidFormat(format!(
+                "--input-glob '{pattern}' matched no files (pass --allow-empty to allow this)"
+            )));
         }
+        Some(paths)
+    } else if cli.input != "-" && Path::new(&cli.input).is_dir() {
+        Some(profile.time("read", || scan::discover_inputs(Path::new(&cli.input)))?)
     } else {
-        // Read file to detect format
-        let content = std::fs::read_to_string(&cli.input)?;
-
-        // Try to detect if it's xcresult JSON format
-        if content.trim_start().starts_with('{') && content.contains("_values") {
-            // Parse as xcresult JSON
-            let parser = XcresultParser::new(cli.context);
-            match parser.parse_json(&content) {
-                Ok(warnings) if !warnings.is_empty() => warnings,
-                _ => {
-                    // Fallback to raw log parsing
-                    use std::io::Cursor;
-                    let cursor = Cursor::new(&content);
-                    let rawlog_parser = RawLogParser::new(cli.context);
-                    rawlog_parser.parse_stream(cursor)?
+        None
+    };
+
+    let inputs = if let Some(paths) = paths {
+        let progress = scan::progress_bar(paths.len(), !cli.quiet);
+        let per_file = if cli.fail_fast {
+            // One file at a time, so nothing is parsed after the file that stopped
+            let mut per_file = Vec::new();
+            for path in &paths {
+                let bytes = profile.time("read", || std::fs::read(path))?;
+                let parsed = parse_input(&bytes, cli, settings, Some(&mut fail_fast), profile)?;
+                progress.inc(1);
+                let stopped = parsed.stopped;
+                per_file.push(parsed);
+                if stopped {
+                    break;
                 }
             }
+            per_file
         } else {
-            // Try XcodeBuildParser first (structured JSON lines), then RawLogParser
-            let file = File::open(&cli.input)?;
-            let reader = BufReader::new(file);
-            let xcodebuild_parser = XcodeBuildParser::new(cli.context);
-
-            match xcodebuild_parser.parse_stream(reader) {
-                Ok(warnings) if !warnings.is_empty() => warnings,
-                _ => {
-                    // Fallback to raw log parsing for plain text xcodebuild output
-                    use std::io::Cursor;
-                    let cursor = Cursor::new(&content);
-                    let rawlog_parser = RawLogParser::new(cli.context);
-                    rawlog_parser.parse_stream(cursor)?
-                }
+            scan::parse_files(&paths, cli.jobs, |path| {
+                let bytes = profile.time("read", || std::fs::read(path))?;
+                let parsed = parse_input(&bytes, cli, settings, None, profile)?;
+                progress.inc(1);
+                Ok(parsed)
+            })?
+        };
+        progress.finish_and_clear();
+        per_file
+    } else if cli.fail_fast {
+        let reader: Box<dyn BufRead> = if cli.input == "-" {
+            Box::new(io::stdin().lock())
+        } else {
+            Box::new(BufReader::new(File::open(&cli.input)?))
+        };
+        vec![parse_until_stop(
+            reader,
+            cli,
+            settings,
+            &mut fail_fast,
+            profile,
+        )?]
+    } else {
+        // Read the whole input up front so format detection can inspect it
+        let bytes = profile.time("read", || {
+            if cli.input == "-" {
+                let mut buf = Vec::new();
+                io::stdin().read_to_end(&mut buf).map(|_| buf)
+            } else {
+                std::fs::read(&cli.input)
             }
+        })?;
+        vec![parse_input(&bytes, cli, settings, None, profile)?]
+    };
+
+    let blank = inputs.iter().all(|input| input.blank);
+    let formats: BTreeSet<&str> = inputs.iter().map(|i| i.format.parser_name()).collect();
+    let detected_format = match formats.len() {
+        0 => None,
+        1 => formats.first().map(|f| f.to_string()),
+        _ => Some("mixed".to_string()),
+    };
+    let localized = inputs
+        .iter()
+        .filter_map(|input| input.localized)
+        .reduce(|(a, b), (c, d)| (a + c, b + d));
+    if let (Some((unmatched, total)), false) = (localized, cli.quiet) {
+        eprintln!(
+            "Hint: {unmatched} of {total} compiler warnings matched no pattern and look localized; \
+             build with an English locale (e.g. LANG=en_US.UTF-8) or load a pattern pack with --patterns"
+        );
+    }
+    let warnings: Vec<Warning> = inputs.into_iter().flat_map(|i| i.warnings).collect();
+    let warnings = if cli.warn_on_unknown {
+        let (kept, uncategorized) = split_uncategorized(warnings, &detection);
+        for w in &uncategorized {
+            eprintln!(
+                "Uncategorized warning: {}:{}: {}",
+                w.file_path.display(),
+                w.line_number,
+                w.message
+            );
         }
+        kept
+    } else {
+        warnings
     };
+    let parsed = (!blank).then_some(warnings.len());
+
+    profile.add(
+        "context extraction",
+        profile::context_time().saturating_sub(context_before),
+    );
 
+    let filter_started = Instant::now();
     // Filter warnings if requested
-    let filtered_warnings = filter_warnings(warnings, cli.filter);
+    let mut warnings = config.fixes.apply(warnings);
+    if !cli.labels.is_empty() {
+        for warning in &mut warnings {
+            warning.labels.extend(cli.labels.iter().cloned());
+        }
+    }
+    let mut filtered_warnings = config.effort.apply(filter_warnings(warnings, cli.filter));
+    if cli.ignore_generated {
+        filtered_warnings = drop_generated(filtered_warnings);
+    }
+    let test_paths = TestPaths::new(&cli.test_paths)?;
+    filtered_warnings = downrank_tests(filtered_warnings, &test_paths);
+    if let Some(key) = cli.dedup_by {
+        filtered_warnings = dedup_warnings(filtered_warnings, key);
+    }
+    if let Some(window) = cli.dedup_window {
+        filtered_warnings = dedup_window(filtered_warnings, window);
+    }
+    if let Some(limit) = cli.escalate_hotspots {
+        filtered_warnings = escalate_hotspots(filtered_warnings, limit);
+    }
+    if let Some(base) = &cli.changed_since {
+        let dir = repo_root(cli)?;
+        match changes::changed_lines(&dir, base)? {
+            Some(changed) => filtered_warnings = changed.retain_changed(filtered_warnings),
+            None => tracing::warn!(
+                "{} is not inside a git repository; ignoring --changed-since",
+                dir.display()
+            ),
+        }
+    }
+
+    // Checked before the seen-db records anything from a misconfigured run
+    if cli.require_context {
+        require_sources(&filtered_warnings, &source_map)?;
+    }
+
+    if let Some(db) = &cli.seen_db {
+        filtered_warnings = seen::retain_unseen(db, filtered_warnings)?;
+    }
+    let salt = cli
+        .anonymize_paths
+        .then(|| cli.anonymize_salt.clone().unwrap_or_else(random_salt));
+    // Files are read under their real paths, then keyed by the reported ones
+    let line_counts: HashMap<PathBuf, Option<usize>> = if cli.density {
+        affected_files(&filtered_warnings)
+            .into_iter()
+            .map(|path| {
+                let lines = count_lines(&source_map.resolve(path));
+                let key = match &salt {
+                    Some(salt) => anonymize_path(path, salt),
+                    None => path.to_path_buf(),
+                };
+                (key, lines)
+            })
+            .collect()
+    } else {
+        HashMap::new()
+    };
+    // Formatters that read sources get them by the reported paths
+    let sources: SourcePaths = if cli.include_decl || cli.function_relative {
+        affected_files(&filtered_warnings)
+            .into_iter()
+            .map(|path| {
+                let key = match &salt {
+                    Some(salt) => anonymize_path(path, salt),
+                    None => path.to_path_buf(),
+                };
+                (key, source_map.resolve(path))
+            })
+            .collect()
+    } else {
+        SourcePaths::new()
+    };
+    // Last, so every filter above still sees the real paths
+    if let Some(salt) = &salt {
+        filtered_warnings = anonymize_paths(filtered_warnings, salt);
+    }
+
+    profile.add("filter", filter_started.elapsed());
 
     // Create warning run
-    let run = WarningRun::new(filtered_warnings);
+    let mut run = WarningRun::new(filtered_warnings);
+    run.commit_sha = cli.commit.clone();
+    run.branch = cli.branch.clone();
+    run.detected_format = detected_format;
+    if cli.density {
+        run.set_file_lines(|path| line_counts.get(path).copied().flatten());
+    }
 
-    // Format output
-    let formatter: Box<dyn Formatter> = match cli.format {
-        OutputFormat::Json => Box::new(JsonFormatter::new()),
-        OutputFormat::Markdown => Box::new(MarkdownFormatter::new()),
-        OutputFormat::Slack => Box::new(SlackFormatter::new()),
+    Ok((run, parsed, baseline, sources))
+}
+
+fn is_blank(bytes: &[u8]) -> bool {
+    bytes.iter().all(u8::is_ascii_whitespace)
+}
+
+/// Whether the first non-blank line of `content` starts a JSON value
+fn opens_with_json(content: &str) -> bool {
+    content
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .is_some_and(|line| line.starts_with(['{', '[']))
+}
+
+fn format_options(cli: &Cli, config: &Config, sources: SourcePaths) -> Result<FormatOptions> {
+    let mut options = FormatOptions {
+        relativize_context: cli.relativize_context,
+        collapse_context: cli.collapse_context,
+        max_message_len: cli.max_message_len,
+        function_relative: cli.function_relative,
+        include_decl: cli.include_decl,
+        taxonomy: cli.taxonomy,
+        collapse_duplicates: cli.collapse_duplicates,
+        tab_width: cli.tab_width,
+        docs: config.docs.clone(),
+        tiered: cli.tiered,
+        context_radius: cli.context_radius,
+        sources,
+        ..FormatOptions::default()
     };
+    if let Some(pattern) = &cli.highlight_pattern {
+        options.highlight =
+            Some(Regex::new(pattern).map_err(|e| {
+                ParseError::InvalidFormat(format!("invalid --highlight-pattern: {e}"))
+            })?);
+    }
+    if let (Some(repo_url), Some(commit)) = (&cli.repo_url, &cli.commit) {
+        options.permalinks = Some(Permalinks::new(repo_url, commit, repo_root(cli)?));
+    }
+    Ok(options)
+}
 
-    let output = formatter.format(&run)?;
-    println!("{output}");
+/// Write the report, followed by a newline, to `--output` or stdout
+fn write_report(
+    cli: &Cli,
+    formatter: &dyn Formatter,
+    run: &WarningRun,
+    profile: &Profile,
+) -> Result<()> {
+    let write = |writer: &mut dyn Write| -> Result<()> {
+        let mut writer = BufWriter::new(writer);
+        // Streaming formatters write as they serialize, so their output time
+        // is part of "format"
+        profile.time("format", || formatter.write_to(run, &mut writer))?;
+        profile.time("write", || {
+            writer.write_all(b"\n")?;
+            writer.flush()
+        })?;
+        Ok(())
+    };
+    match &cli.output {
+        Some(path) => write_atomically(path, write),
+        None => write(&mut io::stdout().lock()),
+    }
+}
+
+/// Write `path` through a `.tmp` sibling that is renamed into place on
+/// success, so a failed run never leaves a truncated report behind
+fn write_atomically(path: &Path, write: impl FnOnce(&mut dyn Write) -> Result<()>) -> Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let written = File::create(&tmp)
+        .map_err(ParseError::from)
+        .and_then(|mut file| write(&mut file));
+    match written {
+        Ok(()) => Ok(std::fs::rename(&tmp, path)?),
+        Err(e) => {
+            let _ = std::fs::remove_file(&tmp);
+            Err(e)
+        }
+    }
+}
+
+/// Write the run as binary protobuf; unlike the text formats no newline is appended
+#[cfg(feature = "proto")]
+fn write_proto(run: &WarningRun, output: Option<&Path>) -> Result<()> {
+    use std::io::Write;
+
+    let bytes = formatters::ProtoFormatter::new().encode(run);
+    match output {
+        Some(path) => write_atomically(path, |writer| Ok(writer.write_all(&bytes)?))?,
+        None => io::stdout().write_all(&bytes)?,
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "proto"))]
+fn write_proto(_run: &WarningRun, _output: Option<&Path>) -> Result<()> {
+    Err(ParseError::InvalidFormat(
+        "protobuf output requires building with the `proto` feature".to_string(),
+    ))
+}
+
+/// Write side outputs that do not depend on the chosen format, then gate
+fn finish(
+    cli: &Cli,
+    run: &WarningRun,
+    parsed: Option<usize>,
+    baseline: Option<&WarningRun>,
+    profile: &Profile,
+) -> Result<i32> {
+    let diff = baseline.map(|baseline| run.diff(baseline));
+    if let (Some(diff), false) = (&diff, cli.quiet) {
+        eprintln!("{}", diff.summary_line());
+    }
+    if let Some(path) = &cli.emit_fix_script {
+        std::fs::write(path, format_fix_script(run))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))?;
+        }
+    }
+    if cli.profile {
+        eprint!("{}", profile.report());
+    }
+
+    let exit_code = gate(cli, run, parsed);
+    if cli.github_summary {
+        if let Some(path) = std::env::var_os("GITHUB_STEP_SUMMARY") {
+            let mut file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)?;
+            writeln!(
+                file,
+                "{}",
+                format_step_summary(run, diff.as_ref(), exit_code == 0)
+            )?;
+        }
+    }
+    // Only a passing run may become the baseline later runs are compared to
+    if let (0, Some(path)) = (exit_code, &cli.emit_baseline_on_pass) {
+        baseline::save_baseline(path, run)?;
+    }
+    Ok(exit_code)
+}
+
+/// Apply the CI gates, print the optional summary and return the exit code
+fn gate(cli: &Cli, run: &WarningRun, parsed: Option<usize>) -> i32 {
+    // A non-empty input yielding too few warnings points at a parser that
+    // silently stopped matching, so it outranks the threshold result
+    if let (Some(min), Some(parsed)) = (cli.expect_min, parsed) {
+        if parsed < min {
+            eprintln!(
+                "Parsed {parsed} warning(s) from non-empty input, expected at least {min}; \
+                 the log format may have changed"
+            );
+            return 3;
+        }
+    }
 
     // Check threshold and return appropriate exit code
-    let threshold_passed = check_threshold(&run.warnings, cli.threshold);
+    let mut threshold_passed = check_threshold(&run.warnings, cli.threshold, cli.threshold_op);
+
+    if !check_affected_files(&run.warnings, cli.max_affected_files) {
+        let files = affected_files(&run.warnings);
+        eprintln!(
+            "Warnings affect {} files (max {}):",
+            files.len(),
+            cli.max_affected_files.unwrap_or_default()
+        );
+        for file in files {
+            eprintln!("  {}", file.display());
+        }
+        threshold_passed = false;
+    }
+
+    if cli.fail_on_swift6_errors && run.swift6_error_count > 0 {
+        eprintln!(
+            "{} warning(s) become errors in the Swift 6 language mode",
+            run.swift6_error_count
+        );
+        threshold_passed = false;
+    }
+
+    if cli.summary {
+        print!("{}", format_summary(run, threshold_passed, cli.taxonomy));
+    }
 
     if threshold_passed {
-        Ok(0) // Success
+        0 // Success
     } else {
-        Ok(1) // Warnings exceed threshold
+        1 // Warnings exceed threshold
+    }
+}
+
+/// Warnings parsed from one input, with what was learned about the input itself
+struct ParsedInput {
+    warnings: Vec<Warning>,
+    /// The format whose parser produced the warnings
+    format: InputFormat,
+    /// Only whitespace
+    blank: bool,
+    /// `(unmatched, total)` warning lines when they look like a localized compiler's
+    localized: Option<(usize, usize)>,
+    /// Parsing stopped at a `--fail-fast` warning, leaving the rest unparsed
+    stopped: bool,
+}
+
+/// Predicate for the warning to stop parsing at
+type StopAt<'a> = dyn FnMut(&Warning) -> bool + 'a;
+
+/// How [`parse_content`] configures and chains the parsers
+#[derive(Clone, Copy)]
+struct ParseSettings<'a> {
+    context: usize,
+    detection: DetectionOptions,
+    source_map: &'a SourceMap,
+    patterns: &'a PatternPack,
+    xcresult_issue_types: &'a [String],
+    /// Fall back to raw log parsing when xcresult JSON fails to parse or yields nothing
+    fallback: bool,
+}
+
+/// Decode one input and parse it, honoring the CLI's encoding and detection
+/// flags. Line-based input stops being parsed after the first warning `stop`
+/// matches.
+fn parse_input(
+    bytes: &[u8],
+    cli: &Cli,
+    settings: ParseSettings<'_>,
+    stop: Option<&mut StopAt<'_>>,
+    profile: &Profile,
+) -> Result<ParsedInput> {
+    let content = profile.time("decode", || {
+        encoding::decode_input(bytes, cli.input_encoding)
+    })?;
+
+    let detected = profile.time("detect", || {
+        !cli.strict_detection || content.trim().is_empty() || detect_format(&content).is_some()
+    });
+    if !detected {
+        return Err(ParseError::InvalidFormat(
+            "input does not match any known xcodebuild or xcresult format".to_string(),
+        ));
+    }
+
+    // Includes context extraction, which is also reported on its own
+    let (warnings, format, stopped) =
+        profile.time("parse", || parse_content(&content, settings, stop))?;
+    Ok(ParsedInput {
+        warnings,
+        format,
+        blank: is_blank(bytes),
+        localized: settings.patterns.localized_warnings(&content),
+        stopped,
+    })
+}
+
+/// With `--fail-fast`, parse a UTF-8 raw log straight from `reader`, so input
+/// after the warning parsing stops at is never read or decoded. Other input is
+/// read whole and parsed by [`parse_input`].
+fn parse_until_stop(
+    mut reader: impl BufRead,
+    cli: &Cli,
+    settings: ParseSettings<'_>,
+    stop: &mut StopAt<'_>,
+    profile: &Profile,
+) -> Result<ParsedInput> {
+    let head = reader.fill_buf()?;
+    let streamable = matches!(cli.input_encoding, InputEncoding::Utf8)
+        && !cli.strict_detection
+        && head
+            .iter()
+            .find(|b| !b.is_ascii_whitespace())
+            .is_some_and(|b| !matches!(b, b'{' | b'[' | 0xEF));
+    let mut reader = Tee {
+        inner: reader,
+        read: Vec::new(),
+    };
+    if streamable {
+        let rawlog_parser = RawLogParser::new(settings.context)
+            .with_detection(settings.detection)
+            .with_source_map(settings.source_map.clone())
+            .with_patterns(settings.patterns.clone());
+        let mut warnings = Vec::new();
+        let mut stopped = false;
+        let parsed = profile.time("parse", || {
+            rawlog_parser.for_each_warning(&mut reader, |w| {
+                push_until(&mut warnings, w, Some(&mut *stop), &mut stopped)
+            })
+        });
+        if stopped {
+            return Ok(ParsedInput {
+                warnings,
+                format: InputFormat::RawLog,
+                blank: false,
+                localized: None,
+                stopped,
+            });
+        }
+        if parsed.is_ok() {
+            // Everything was read as UTF-8 text; only the JSON lines are left
+            let content = String::from_utf8(std::mem::take(&mut reader.read))
+                .map_err(|e| ParseError::InvalidFormat(e.to_string()))?;
+            let (warnings, format, stopped) = profile.time("parse", || {
+                parse_lines(&content, settings, Some(stop), Some(warnings))
+            })?;
+            return Ok(ParsedInput {
+                warnings,
+                format,
+                blank: is_blank(content.as_bytes()),
+                localized: settings.patterns.localized_warnings(&content),
+                stopped,
+            });
+        }
+        // Undecodable input: reparse it whole for the usual error
     }
+
+    let mut bytes = reader.read;
+    profile.time("read", || reader.inner.read_to_end(&mut bytes))?;
+    parse_input(&bytes, cli, settings, Some(stop), profile)
+}
+
+/// Reads through `inner`, keeping a copy of every byte consumed
+struct Tee<R> {
+    inner: R,
+    read: Vec<u8>,
+}
+
+impl<R: BufRead> Read for Tee<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.read.extend_from_slice(&buf[..n]);
+        Ok(n)
+    }
+}
+
+impl<R: BufRead> BufRead for Tee<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        // The bytes being consumed are still buffered, so this doesn't read
+        if amt > 0 {
+            if let Ok(buf) = self.inner.fill_buf() {
+                self.read.extend_from_slice(&buf[..amt]);
+            }
+        }
+        self.inner.consume(amt);
+    }
+}
+
+/// Parse `input` in any supported format (xcresult JSON, xcodebuild JSON, or raw
+/// xcodebuild log text) using the same auto-detection as `run()`
+pub fn parse_str(input: &str, context: usize) -> Result<Vec<Warning>> {
+    let (warnings, _, _) = parse_content(
+        input,
+        ParseSettings {
+            context,
+            detection: DetectionOptions::default(),
+            source_map: &SourceMap::default(),
+            patterns: &PatternPack::default(),
+            xcresult_issue_types: &[],
+            fallback: true,
+        },
+        None,
+    )?;
+    Ok(warnings)
+}
+
+/// Line-based input stops being parsed after the first warning `stop` matches;
+/// xcresult JSON is always parsed whole. Also returns whether parsing stopped.
+fn parse_content(
+    content: &str,
+    settings: ParseSettings<'_>,
+    stop: Option<&mut StopAt<'_>>,
+) -> Result<(Vec<Warning>, InputFormat, bool)> {
+    let ParseSettings {
+        context,
+        detection,
+        source_map,
+        patterns,
+        xcresult_issue_types,
+        fallback,
+    } = settings;
+
+    // Parse input - detect format and use appropriate parser with fallbacks
+    if xcresult_document(content).is_none() {
+        return parse_lines(content, settings, stop, None);
+    }
+
+    // Parse as xcresult JSON
+    let parser = XcresultParser::new(context)
+        .with_detection(detection)
+        .with_source_map(source_map.clone())
+        .with_patterns(patterns.clone())
+        .with_issue_types(xcresult_issue_types);
+    let parsed = parser.parse_json(content);
+    let parsed = match parsed {
+        Ok(warnings) if !warnings.is_empty() || !fallback => (warnings, InputFormat::Xcresult),
+        Err(e) if !fallback => return Err(e),
+        _ => {
+            // Fallback to raw log parsing
+            let rawlog_parser = RawLogParser::new(context)
+                .with_detection(detection)
+                .with_source_map(source_map.clone())
+                .with_patterns(patterns.clone());
+            let warnings = rawlog_parser.parse_stream(Cursor::new(content))?;
+            // A clean export is still an xcresult, not a raw log
+            let format = if warnings.is_empty() && parsed.is_ok() {
+                InputFormat::Xcresult
+            } else {
+                InputFormat::RawLog
+            };
+            (warnings, format)
+        }
+    };
+
+    Ok((parsed.0, parsed.1, false))
+}
+
+/// Parse line-based input with both line parsers, reusing `text_warnings`
+/// when the raw log pass already ran. Also returns whether parsing stopped.
+fn parse_lines(
+    content: &str,
+    settings: ParseSettings<'_>,
+    mut stop: Option<&mut StopAt<'_>>,
+    text_warnings: Option<Vec<Warning>>,
+) -> Result<(Vec<Warning>, InputFormat, bool)> {
+    // Logs can interleave plain text diagnostics with JSON diagnostic lines,
+    // so run both line parsers and union their results
+    let xcodebuild_parser = XcodeBuildParser::new(settings.context)
+        .with_detection(settings.detection)
+        .with_source_map(settings.source_map.clone())
+        .with_patterns(settings.patterns.clone());
+    let rawlog_parser = RawLogParser::new(settings.context)
+        .with_detection(settings.detection)
+        .with_source_map(settings.source_map.clone())
+        .with_patterns(settings.patterns.clone());
+    let text_pass = |stop: Option<&mut StopAt<'_>>, stopped: &mut bool| {
+        let mut warnings = Vec::new();
+        let mut stop = stop;
+        rawlog_parser.for_each_warning(Cursor::new(content), |w| {
+            push_until(&mut warnings, w, stop.as_deref_mut(), stopped)
+        })?;
+        Ok::<_, ParseError>(warnings)
+    };
+
+    let mut stopped = false;
+    // When stopping early, a log that doesn't open with JSON is scanned as text
+    // first, so the stop isn't only reached after a full JSON pass
+    let text_first = stop.is_some() && !opens_with_json(content);
+    let mut text_warnings = match text_warnings {
+        Some(warnings) => Some(warnings),
+        None if text_first => Some(text_pass(stop.as_deref_mut(), &mut stopped)?),
+        None => None,
+    };
+    let mut json_warnings = Vec::new();
+    if !stopped
+        && xcodebuild_parser
+            .for_each_warning(Cursor::new(content), |w| {
+                push_until(&mut json_warnings, w, stop.as_deref_mut(), &mut stopped)
+            })
+            .is_err()
+    {
+        json_warnings.clear();
+    }
+    if text_warnings.is_none() && !stopped {
+        text_warnings = Some(text_pass(stop, &mut stopped)?);
+    }
+
+    // Clean JSON builds carry no warnings, so fall back to the input's shape
+    let format = if !json_warnings.is_empty()
+        || detect_format(content) == Some(InputFormat::XcodebuildJson)
+    {
+        InputFormat::XcodebuildJson
+    } else {
+        InputFormat::RawLog
+    };
+    // A diagnostic can reach both parsers; keep the JSON one, but never
+    // collapse repeats within one parser's output
+    let json_ids: HashSet<String> = json_warnings.iter().map(|w| w.id.clone()).collect();
+    let mut warnings = json_warnings;
+    warnings.extend(
+        text_warnings
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|w| !json_ids.contains(&w.id)),
+    );
+    Ok((warnings, format, stopped))
+}
+
+/// Push `warning`, breaking (and setting `stopped`) when `stop` matches it
+fn push_until(
+    warnings: &mut Vec<Warning>,
+    warning: Warning,
+    stop: Option<&mut StopAt<'_>>,
+    stopped: &mut bool,
+) -> ControlFlow<()> {
+    *stopped = stop.is_some_and(|stop| stop(&warning));
+    warnings.push(warning);
+    if *stopped {
+        ControlFlow::Break(())
+    } else {
+        ControlFlow::Continue(())
+    }
+}
+
+/// Load the given config and pattern pack, failing on the first invalid one,
+/// and classify the sample message with the pack
+fn run_validate(args: &ValidateArgs) -> Result<i32> {
+    if let Some(path) = &args.config {
+        config::load_config(path)?;
+        println!("Config OK: {}", path.display());
+    }
+    let patterns = match &args.patterns {
+        Some(path) => {
+            let patterns = PatternPack::load(path)?;
+            println!(
+                "Patterns OK: {} pattern(s) in {}",
+                patterns.len(),
+                path.display()
+            );
+            patterns
+        }
+        None => PatternPack::default(),
+    };
+    if let Some(message) = &args.test_message {
+        let (warning_type, severity, confidence) = patterns.categorize(message);
+        println!(
+            "Classification: type={} severity={} confidence={confidence:.1}",
+            warning_type.as_str(),
+            severity.as_str()
+        );
+    }
+
+    Ok(0)
+}
+
+fn run_trend(args: &TrendArgs) -> Result<i32> {
+    let runs = trend::load_runs(&args.dir)?;
+    let points = trend::build_trend(&runs);
+
+    let output = match args.format {
+        TrendFormat::Json => trend::format_trend_json(&points)?,
+        TrendFormat::Csv => trend::format_trend_csv(&points),
+    };
+    println!("{}", output.trim_end());
+
+    Ok(0)
 }
 
 // Legacy compatibility function for existing CLI
 pub fn find_concurrency_warnings(input: &str) -> Vec<String> {
-    use std::io::Cursor;
-
     // Try XcodeBuildParser first
     let xcodebuild_parser = XcodeBuildParser::new(3);
     let cursor = Cursor::new(input);
@@ -1,9 +1,8 @@
-use clap::Parser;
 use std::process;
 use swiftconcur_parser::{cli::Cli, run};
 
 fn main() {
-    let cli = Cli::parse();
+    let cli = Cli::parse_with_sources();
 
     // Initialize tracing
     if cli.verbose {
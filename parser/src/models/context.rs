@@ -1,6 +1,25 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// `line` with each tab replaced by spaces up to the next multiple of
+/// `tab_width` columns, as editors display it
+pub fn expand_tabs(line: &str, tab_width: usize) -> String {
+    let tab_width = tab_width.max(1);
+    let mut expanded = String::with_capacity(line.len());
+    let mut column = 0;
+    for c in line.chars() {
+        if c == '\t' {
+            let spaces = tab_width - column % tab_width;
+            expanded.extend(std::iter::repeat_n(' ', spaces));
+            column += spaces;
+        } else {
+            expanded.push(c);
+            column += 1;
+        }
+    }
+    expanded
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CodeContext {
     pub before: Vec<String>,
     pub line: String,
@@ -23,4 +42,151 @@ impl CodeContext {
             after: Vec::new(),
         }
     }
+
+    /// The context with tabs expanded (see [`expand_tabs`])
+    pub fn expand_tabs(&self, tab_width: usize) -> Self {
+        Self {
+            before: self
+                .before
+                .iter()
+                .map(|l| expand_tabs(l, tab_width))
+                .collect(),
+            line: expand_tabs(&self.line, tab_width),
+            after: self
+                .after
+                .iter()
+                .map(|l| expand_tabs(l, tab_width))
+                .collect(),
+        }
+    }
+
+    /// The context with trailing whitespace and carriage returns removed from
+    /// every line, so CRLF and LF checkouts (or editor settings) compare equal
+    pub fn normalize_whitespace(&self) -> Self {
+        let normalize = |line: &String| line.replace('\r', "").trim_end().to_string();
+        Self {
+            before: self.before.iter().map(normalize).collect(),
+            line: normalize(&self.line),
+            after: self.after.iter().map(normalize).collect(),
+        }
+    }
+
+    /// 1-based display column of the compiler's 1-based `column` on the
+    /// warning line. The compiler counts a tab as one column; editors expand it.
+    pub fn display_column(&self, column: usize, tab_width: usize) -> usize {
+        let prefix: String = self.line.chars().take(column.saturating_sub(1)).collect();
+        expand_tabs(&prefix, tab_width).chars().count() + 1
+    }
+
+    /// Strip the leading whitespace shared by every non-blank line. Returns the
+    /// dedented context and the number of characters removed from each line, so
+    /// callers can shift column positions by the same amount.
+    pub fn dedent(&self) -> (Self, usize) {
+        let lines = self
+            .before
+            .iter()
+            .chain(std::iter::once(&self.line))
+            .chain(self.after.iter());
+
+        let mut common: Option<&str> = None;
+        for line in lines.filter(|l| !l.trim().is_empty()) {
+            let indent = &line[..line.len() - line.trim_start().len()];
+            common = Some(match common {
+                None => indent,
+                Some(current) => {
+                    let shared = current
+                        .char_indices()
+                        .zip(indent.chars())
+                        .find(|((_, a), b)| a != b)
+                        .map_or(current.len().min(indent.len()), |((i, _), _)| i);
+                    &current[..shared]
+                }
+            });
+        }
+
+        let prefix = common.unwrap_or("");
+        if prefix.is_empty() {
+            return (self.clone(), 0);
+        }
+
+        let strip = |line: &String| -> String {
+            match line.strip_prefix(prefix) {
+                Some(rest) => rest.to_string(),
+                // Blank lines may be shorter than the common indentation
+                None => line.trim_start().to_string(),
+            }
+        };
+
+        let dedented = Self {
+            before: self.before.iter().map(strip).collect(),
+            line: strip(&self.line),
+            after: self.after.iter().map(strip).collect(),
+        };
+        (dedented, prefix.chars().count())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(raw: &[&str]) -> Vec<String> {
+        raw.iter().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn test_dedent_uniform_block() {
+        let context = CodeContext::new(
+            lines(&["        func update() {", "            let x = 1"]),
+            "            self.count += x".to_string(),
+            lines(&["        }"]),
+        );
+
+        let (dedented, removed) = context.dedent();
+        assert_eq!(removed, 8);
+        assert_eq!(
+            dedented.before,
+            lines(&["func update() {", "    let x = 1"])
+        );
+        assert_eq!(dedented.line, "    self.count += x");
+        assert_eq!(dedented.after, lines(&["}"]));
+    }
+
+    #[test]
+    fn test_dedent_ignores_blank_lines() {
+        let context = CodeContext::new(
+            lines(&["    let a = 1", ""]),
+            "    let b = a".to_string(),
+            lines(&["  ", "      a += b"]),
+        );
+
+        let (dedented, removed) = context.dedent();
+        assert_eq!(removed, 4);
+        assert_eq!(dedented.before, lines(&["let a = 1", ""]));
+        assert_eq!(dedented.line, "let b = a");
+        assert_eq!(dedented.after, lines(&["", "  a += b"]));
+    }
+
+    #[test]
+    fn test_dedent_without_common_indent() {
+        let context = CodeContext::new(lines(&["let a = 1"]), "    a += 1".to_string(), vec![]);
+
+        let (dedented, removed) = context.dedent();
+        assert_eq!(removed, 0);
+        assert_eq!(dedented.line, "    a += 1");
+    }
+
+    #[test]
+    fn test_display_column_expands_tabs() {
+        let context = CodeContext::empty("\t\tcount += 1".to_string());
+
+        // `count` is at compiler column 3: each tab counts once
+        assert_eq!(context.display_column(3, 4), 9);
+        assert_eq!(context.display_column(3, 2), 5);
+        assert_eq!(context.display_column(1, 4), 1);
+        assert_eq!(context.expand_tabs(4).line, "        count += 1");
+
+        // A tab after text only advances to the next tab stop
+        assert_eq!(expand_tabs("ab\tc", 4), "ab  c");
+    }
 }
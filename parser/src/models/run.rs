@@ -1,29 +1,647 @@
 use super::Warning;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 
+/// Schema version written by this build
+pub const SCHEMA_VERSION: u32 = 2;
+
+// Documents written before `schema_version` existed are version 1
+fn legacy_schema_version() -> u32 {
+    1
+}
+
+fn is_zero(count: &usize) -> bool {
+    *count == 0
+}
+
+/// How [`WarningRun::merge`] resolves warnings that share an id
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergePolicy {
+    /// Keep the copy from the receiving run
+    #[default]
+    KeepFirst,
+    /// Keep whichever copy carries more detail (context, fix, column)
+    KeepRichest,
+    /// Keep every copy; no deduplication
+    KeepAll,
+}
+
+// Detail carried by a warning, used by `MergePolicy::KeepRichest`
+fn richness(warning: &Warning) -> usize {
+    let context = &warning.code_context;
+    context.before.len()
+        + usize::from(!context.line.is_empty())
+        + context.after.len()
+        + usize::from(warning.suggested_fix.is_some())
+        + usize::from(warning.column_number.is_some())
+}
+
+/// A run compared with a baseline by [`WarningRun::diff`]; warnings are
+/// matched by id
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WarningDiff {
+    /// In the run but not the baseline
+    pub new: Vec<Warning>,
+    /// In the baseline but no longer in the run
+    pub fixed: Vec<Warning>,
+    /// In both, as they appear in the run
+    pub persisting: Vec<Warning>,
+}
+
+impl WarningDiff {
+    /// One line for CI logs, e.g. `Baseline: +2 new, -5 fixed, 17 total`.
+    /// The total counts the run's warnings, new and persisting.
+    pub fn summary_line(&self) -> String {
+        format!(
+            "Baseline: +{} new, -{} fixed, {} total",
+            self.new.len(),
+            self.fixed.len(),
+            self.new.len() + self.persisting.len()
+        )
+    }
+}
+
+/// Warnings in one source file relative to its size, written with `--density`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FileMetrics {
+    pub path: PathBuf,
+    pub warnings: usize,
+    /// Line count; absent when the file couldn't be read
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lines: Option<usize>,
+    /// Warnings per 100 lines; absent when the file couldn't be read
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub density: Option<f64>,
+}
+
+impl FileMetrics {
+    pub fn new(path: PathBuf, warnings: usize, lines: Option<usize>) -> Self {
+        let density = lines
+            .filter(|lines| *lines > 0)
+            .map(|lines| warnings as f64 * 100.0 / lines as f64);
+        Self {
+            path,
+            warnings,
+            lines,
+            density,
+        }
+    }
+}
+
+/// A parsed build's warnings. Fields added after the first schema default when
+/// missing and are omitted when empty, and unknown fields are ignored, so
+/// documents round-trip between older and newer versions.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WarningRun {
+    /// [`SCHEMA_VERSION`] of the writer
+    #[serde(default = "legacy_schema_version")]
+    pub schema_version: u32,
+    #[serde(default)]
     pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub commit_sha: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub branch: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub pull_request: Option<u32>,
+    #[serde(default)]
     pub total_warnings: usize,
+    /// Warnings that become errors under the Swift 6 language mode
+    #[serde(default)]
+    pub swift6_error_count: usize,
+    /// Sum of the warnings' effort points
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub effort_points: usize,
     pub warnings: Vec<Warning>,
+    /// Parser that handled the input ("xcresult", "xcodebuild", "rawlog", or
+    /// "mixed" for directories of differing logs); informational only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detected_format: Option<String>,
+    #[serde(default)]
     pub created_at: DateTime<Utc>,
+    /// Per-file warning density, densest first; only filled in with `--density`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<FileMetrics>,
 }
 
 impl WarningRun {
     pub fn new(warnings: Vec<Warning>) -> Self {
         let total_warnings = warnings.len();
+        let swift6_error_count = warnings.iter().filter(|w| w.is_error_in_swift6()).count();
+        let effort_points = warnings
+            .iter()
+            .filter_map(|w| w.effort)
+            .map(|e| e.points())
+            .sum();
         Self {
+            schema_version: SCHEMA_VERSION,
             id: uuid::Uuid::new_v4().to_string(),
             commit_sha: None,
             branch: None,
             pull_request: None,
             total_warnings,
+            swift6_error_count,
+            effort_points,
             warnings,
+            detected_format: None,
             created_at: Utc::now(),
+            files: Vec::new(),
+        }
+    }
+
+    /// Upgrade a run deserialized from an older schema, filling in defaults for
+    /// fields that older versions did not write
+    pub fn migrate(mut self) -> Self {
+        if self.schema_version < 2 {
+            if self.id.is_empty() {
+                self.id = uuid::Uuid::new_v4().to_string();
+            }
+            self.total_warnings = self.warnings.len();
+        }
+        // Derived, and absent from documents written before it was added
+        self.swift6_error_count = self.swift6_error_count();
+        self.effort_points = self.effort_points();
+        self.schema_version = SCHEMA_VERSION;
+        self
+    }
+
+    /// Keep only the warnings matching `predicate`, updating `total_warnings`
+    pub fn retain<F>(&mut self, predicate: F)
+    where
+        F: FnMut(&Warning) -> bool,
+    {
+        self.warnings.retain(predicate);
+        self.total_warnings = self.warnings.len();
+        self.swift6_error_count = self.swift6_error_count();
+        self.effort_points = self.effort_points();
+        self.recount_files();
+    }
+
+    /// Replace each warning with `transform`'s result, dropping those it maps
+    /// to `None`, and recompute the counts
+    pub fn filter_map<F>(mut self, transform: F) -> Self
+    where
+        F: FnMut(Warning) -> Option<Warning>,
+    {
+        self.warnings = std::mem::take(&mut self.warnings)
+            .into_iter()
+            .filter_map(transform)
+            .collect();
+        self.total_warnings = self.warnings.len();
+        self.swift6_error_count = self.swift6_error_count();
+        self.effort_points = self.effort_points();
+        self.recount_files();
+        self
+    }
+
+    /// Combine `other` into this run, resolving duplicate ids with `policy`.
+    /// Warnings keep their order of first appearance. Metadata (commit, branch,
+    /// pull request) comes from `self`, falling back to `other` where unset;
+    /// differing detected formats become "mixed".
+    pub fn merge(mut self, other: WarningRun, policy: MergePolicy) -> Self {
+        match policy {
+            MergePolicy::KeepAll => self.warnings.extend(other.warnings),
+            MergePolicy::KeepFirst | MergePolicy::KeepRichest => {
+                let mut index: HashMap<String, usize> = HashMap::new();
+                let mut merged: Vec<Warning> = Vec::new();
+                for warning in self.warnings.drain(..).chain(other.warnings) {
+                    match index.get(&warning.id) {
+                        Some(&i) => {
+                            if policy == MergePolicy::KeepRichest
+                                && richness(&warning) > richness(&merged[i])
+                            {
+                                merged[i] = warning;
+                            }
+                        }
+                        None => {
+                            index.insert(warning.id.clone(), merged.len());
+                            merged.push(warning);
+                        }
+                    }
+                }
+                self.warnings = merged;
+            }
+        }
+
+        self.commit_sha = self.commit_sha.or(other.commit_sha);
+        self.branch = self.branch.or(other.branch);
+        self.pull_request = self.pull_request.or(other.pull_request);
+        self.detected_format = match (self.detected_format, other.detected_format) {
+            (Some(a), Some(b)) if a != b => Some("mixed".to_string()),
+            (a, b) => a.or(b),
+        };
+        for file in other.files {
+            if !self.files.iter().any(|f| f.path == file.path) {
+                self.files.push(file);
+            }
+        }
+
+        self.total_warnings = self.warnings.len();
+        self.swift6_error_count = self.swift6_error_count();
+        self.effort_points = self.effort_points();
+        self.recount_files();
+        self
+    }
+
+    /// Compare this run with `baseline`. Each list keeps the order of the run
+    /// it comes from.
+    pub fn diff(&self, baseline: &WarningRun) -> WarningDiff {
+        let current: HashSet<&str> = self.warnings.iter().map(|w| w.id.as_str()).collect();
+        let previous: HashSet<&str> = baseline.warnings.iter().map(|w| w.id.as_str()).collect();
+
+        let (persisting, new) = self
+            .warnings
+            .iter()
+            .cloned()
+            .partition(|w| previous.contains(w.id.as_str()));
+        let fixed = baseline
+            .warnings
+            .iter()
+            .filter(|w| !current.contains(w.id.as_str()))
+            .cloned()
+            .collect();
+
+        WarningDiff {
+            new,
+            fixed,
+            persisting,
+        }
+    }
+
+    /// Set the per-file metrics from each warned file's line count, as
+    /// returned by `lines` (`None` for files that couldn't be read)
+    pub fn set_file_lines<F>(&mut self, mut lines: F)
+    where
+        F: FnMut(&std::path::Path) -> Option<usize>,
+    {
+        let mut paths: Vec<&PathBuf> = self.warnings.iter().map(|w| &w.file_path).collect();
+        paths.sort();
+        paths.dedup();
+        self.files = paths
+            .into_iter()
+            .map(|path| FileMetrics::new(path.clone(), 0, lines(path)))
+            .collect();
+        self.recount_files();
+    }
+
+    // Keep `files` in step with the warnings: recount them, drop files left
+    // without warnings and re-sort densest first (unreadable files last)
+    fn recount_files(&mut self) {
+        if self.files.is_empty() {
+            return;
+        }
+        let mut counts: HashMap<&PathBuf, usize> = HashMap::new();
+        for warning in &self.warnings {
+            *counts.entry(&warning.file_path).or_default() += 1;
+        }
+        let mut files: Vec<FileMetrics> = std::mem::take(&mut self.files)
+            .into_iter()
+            .filter_map(|file| {
+                let warnings = *counts.get(&file.path)?;
+                Some(FileMetrics::new(file.path, warnings, file.lines))
+            })
+            .collect();
+        files.sort_by(|a, b| {
+            match (a.density, b.density) {
+                (Some(x), Some(y)) => y.partial_cmp(&x).unwrap_or(Ordering::Equal),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            }
+            .then_with(|| a.path.cmp(&b.path))
+        });
+        self.files = files;
+    }
+
+    fn effort_points(&self) -> usize {
+        self.warnings
+            .iter()
+            .filter_map(|w| w.effort)
+            .map(|effort| effort.points())
+            .sum()
+    }
+
+    fn swift6_error_count(&self) -> usize {
+        self.warnings
+            .iter()
+            .filter(|w| w.is_error_in_swift6())
+            .count()
+    }
+
+    /// Consume the run and return it with only the warnings matching `predicate`
+    pub fn filter<F>(mut self, predicate: F) -> Self
+    where
+        F: FnMut(&Warning) -> bool,
+    {
+        self.retain(predicate);
+        self
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Warning> {
+        self.warnings.iter()
+    }
+}
+
+impl FromIterator<Warning> for WarningRun {
+    fn from_iter<I: IntoIterator<Item = Warning>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+impl<'a> IntoIterator for &'a WarningRun {
+    type Item = &'a Warning;
+    type IntoIter = std::slice::Iter<'a, Warning>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.warnings.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::WarningType;
+    use std::path::{Path, PathBuf};
+
+    fn warning(line: usize, warning_type: WarningType) -> Warning {
+        Warning {
+            id: format!("File.swift:{line}:0"),
+            warning_type,
+            ..Warning::fixture("File.swift", line)
+        }
+    }
+
+    #[test]
+    fn test_collect_into_run() {
+        let run: WarningRun = (1..=3)
+            .map(|line| warning(line, WarningType::ActorIsolation))
+            .collect();
+
+        assert_eq!(run.total_warnings, 3);
+        assert_eq!(run.warnings.len(), 3);
+        assert!(!run.id.is_empty());
+
+        let lines: Vec<usize> = (&run).into_iter().map(|w| w.line_number).collect();
+        assert_eq!(lines, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_file_density() {
+        let in_file = |path: &str, line: usize| {
+            let mut w = warning(line, WarningType::DataRace);
+            w.id = format!("{path}:{line}:0");
+            w.file_path = PathBuf::from(path);
+            w
+        };
+        let mut run = WarningRun::new(vec![
+            in_file("Big.swift", 1),
+            in_file("Small.swift", 1),
+            in_file("Gone.swift", 1),
+            in_file("Small.swift", 2),
+        ]);
+        run.set_file_lines(|path| match path.to_str() {
+            Some("Big.swift") => Some(2000),
+            Some("Small.swift") => Some(50),
+            _ => None,
+        });
+
+        let densities: Vec<(&str, Option<f64>)> = run
+            .files
+            .iter()
+            .map(|f| (f.path.to_str().unwrap(), f.density))
+            .collect();
+        assert_eq!(
+            densities,
+            [
+                ("Small.swift", Some(4.0)),
+                ("Big.swift", Some(0.05)),
+                ("Gone.swift", None),
+            ]
+        );
+        let json = serde_json::to_value(&run).unwrap();
+        assert!(json["files"][2].get("density").is_none());
+
+        // Filtering keeps the metrics in step with the warnings
+        run.retain(|w| !(w.file_path == Path::new("Small.swift") && w.line_number == 2));
+        run.retain(|w| w.file_path != Path::new("Big.swift"));
+        assert_eq!(run.files[0].warnings, 1);
+        assert_eq!(run.files[0].density, Some(2.0));
+        assert_eq!(run.files.len(), 2);
+    }
+
+    #[test]
+    fn test_filter_map_recomputes_counts() {
+        let mut escalating = warning(2, WarningType::DataRace);
+        escalating.error_in_swift6 = true;
+        let run: WarningRun = vec![
+            warning(1, WarningType::ActorIsolation),
+            escalating,
+            warning(3, WarningType::ActorIsolation),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(run.swift6_error_count, 1);
+
+        let mapped = run.filter_map(|mut w| {
+            if w.warning_type == WarningType::DataRace {
+                return None;
+            }
+            w.message = "redacted".to_string();
+            Some(w)
+        });
+        assert_eq!(mapped.total_warnings, 2);
+        assert_eq!(mapped.swift6_error_count, 0);
+        assert!(mapped.iter().all(|w| w.message == "redacted"));
+    }
+
+    #[test]
+    fn test_filter_keeps_counts_consistent() {
+        let run: WarningRun = vec![
+            warning(1, WarningType::ActorIsolation),
+            warning(2, WarningType::DataRace),
+            warning(3, WarningType::ActorIsolation),
+        ]
+        .into_iter()
+        .collect();
+
+        let filtered = run.filter(|w| w.warning_type == WarningType::ActorIsolation);
+        assert_eq!(filtered.total_warnings, 2);
+        assert_eq!(filtered.warnings.len(), 2);
+
+        let mut run = filtered;
+        run.retain(|w| w.line_number > 1);
+        assert_eq!(run.total_warnings, 1);
+        assert_eq!(run.warnings[0].line_number, 3);
+    }
+
+    fn runs() -> (WarningRun, WarningRun) {
+        let mut first = WarningRun::new(vec![
+            warning(1, WarningType::ActorIsolation),
+            warning(2, WarningType::DataRace),
+        ]);
+        first.commit_sha = Some("aaa".to_string());
+
+        let mut richer = warning(2, WarningType::DataRace);
+        richer.suggested_fix = Some("Use an actor".to_string());
+        richer.column_number = Some(9);
+        let mut second = WarningRun::new(vec![richer, warning(3, WarningType::ActorIsolation)]);
+        second.commit_sha = Some("bbb".to_string());
+        second.branch = Some("main".to_string());
+        (first, second)
+    }
+
+    #[test]
+    fn test_merge_keep_first() {
+        let (first, second) = runs();
+        let merged = first.merge(second, MergePolicy::KeepFirst);
+
+        let lines: Vec<usize> = merged.iter().map(|w| w.line_number).collect();
+        assert_eq!(lines, vec![1, 2, 3]);
+        assert_eq!(merged.total_warnings, 3);
+        assert!(merged.warnings[1].suggested_fix.is_none());
+        assert_eq!(merged.commit_sha.as_deref(), Some("aaa"));
+        assert_eq!(merged.branch.as_deref(), Some("main"));
+    }
+
+    #[test]
+    fn test_merge_keep_richest() {
+        let (first, second) = runs();
+        let merged = first.merge(second, MergePolicy::KeepRichest);
+
+        assert_eq!(merged.total_warnings, 3);
+        assert_eq!(merged.warnings[1].line_number, 2);
+        assert_eq!(
+            merged.warnings[1].suggested_fix.as_deref(),
+            Some("Use an actor")
+        );
+        assert_eq!(merged.warnings[1].column_number, Some(9));
+    }
+
+    #[test]
+    fn test_merge_keep_all() {
+        let (first, second) = runs();
+        let merged = first.merge(second, MergePolicy::KeepAll);
+
+        let lines: Vec<usize> = merged.iter().map(|w| w.line_number).collect();
+        assert_eq!(lines, vec![1, 2, 2, 3]);
+        assert_eq!(merged.total_warnings, 4);
+    }
+
+    fn lines(warnings: &[Warning]) -> Vec<usize> {
+        warnings.iter().map(|w| w.line_number).collect()
+    }
+
+    #[test]
+    fn test_diff_against_empty_baseline() {
+        let run: WarningRun = (1..=2)
+            .map(|line| warning(line, WarningType::DataRace))
+            .collect();
+
+        let diff = run.diff(&WarningRun::new(Vec::new()));
+        assert_eq!(lines(&diff.new), [1, 2]);
+        assert!(diff.fixed.is_empty());
+        assert!(diff.persisting.is_empty());
+    }
+
+    #[test]
+    fn test_diff_identical_runs() {
+        let run: WarningRun = (1..=3)
+            .map(|line| warning(line, WarningType::ActorIsolation))
+            .collect();
+
+        let diff = run.diff(&run.clone());
+        assert!(diff.new.is_empty());
+        assert!(diff.fixed.is_empty());
+        assert_eq!(lines(&diff.persisting), [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_diff_mixed_changes() {
+        let baseline: WarningRun = [1, 2, 3]
+            .into_iter()
+            .map(|line| warning(line, WarningType::ActorIsolation))
+            .collect();
+        let run: WarningRun = [4, 2, 5]
+            .into_iter()
+            .map(|line| warning(line, WarningType::ActorIsolation))
+            .collect();
+
+        let diff = run.diff(&baseline);
+        assert_eq!(lines(&diff.new), [4, 5]);
+        assert_eq!(lines(&diff.fixed), [1, 3]);
+        assert_eq!(lines(&diff.persisting), [2]);
+    }
+
+    #[test]
+    fn test_diff_summary_line() {
+        let baseline: WarningRun = (1..=6)
+            .map(|line| warning(line, WarningType::DataRace))
+            .collect();
+        let run: WarningRun = [5, 6, 7, 8]
+            .into_iter()
+            .map(|line| warning(line, WarningType::DataRace))
+            .collect();
+
+        assert_eq!(
+            run.diff(&baseline).summary_line(),
+            "Baseline: +2 new, -4 fixed, 4 total"
+        );
+        assert_eq!(
+            WarningDiff::default().summary_line(),
+            "Baseline: +0 new, -0 fixed, 0 total"
+        );
+    }
+
+    #[test]
+    fn test_deserialize_older_document() {
+        // Written before ids, totals, optional-field defaults and labels
+        let json = r#"{"warnings": [{"id": "File.swift:3:0", "warning_type": "data_race",
+            "severity": "critical", "file_path": "File.swift", "line_number": 3,
+            "message": "data race"}]}"#;
+
+        let run: WarningRun = serde_json::from_str(json).unwrap();
+        assert_eq!(run.schema_version, 1);
+        assert!(run.commit_sha.is_none());
+        let warning = &run.warnings[0];
+        assert!(warning.column_number.is_none());
+        assert!(warning.suggested_fix.is_none());
+        assert!(warning.labels.is_empty());
+
+        let run = run.migrate();
+        assert_eq!(run.schema_version, SCHEMA_VERSION);
+        assert_eq!(run.total_warnings, 1);
+    }
+
+    #[test]
+    fn test_deserialize_newer_document() {
+        // Fields a later version might add are ignored rather than rejected
+        let json = r#"{"schema_version": 3, "id": "run", "flag": "beta", "total_warnings": 1,
+            "warnings": [{"id": "File.swift:3:0", "warning_type": "data_race",
+            "severity": "critical", "file_path": "File.swift", "line_number": 3,
+            "end_line": 5, "notes": ["captured here"], "message": "data race"}]}"#;
+
+        let run: WarningRun = serde_json::from_str(json).unwrap();
+        assert_eq!(run.schema_version, 3);
+        assert_eq!(run.warnings[0].line_number, 3);
+    }
+
+    #[test]
+    fn test_empty_optional_fields_are_omitted() {
+        let run = WarningRun::new(vec![warning(1, WarningType::DataRace)]);
+        let json: serde_json::Value = serde_json::to_value(&run).unwrap();
+
+        for field in ["commit_sha", "branch", "pull_request", "files"] {
+            assert!(json.get(field).is_none(), "{field}");
+        }
+        for field in ["column_number", "suggested_fix", "labels", "test_code"] {
+            assert!(json["warnings"][0].get(field).is_none(), "{field}");
         }
+
+        let round_tripped: WarningRun = serde_json::from_value(json).unwrap();
+        assert_eq!(round_tripped.id, run.id);
+        assert_eq!(round_tripped.warnings[0].id, run.warnings[0].id);
     }
 }
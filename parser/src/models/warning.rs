@@ -1,17 +1,100 @@
 use super::CodeContext;
+use crate::parser::patterns::SWIFT6_ERROR;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
 use std::path::PathBuf;
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "snake_case")]
 pub enum WarningType {
     ActorIsolation,
     SendableConformance,
     DataRace,
     PerformanceRegression,
+    DeprecatedConcurrencyApi,
+    UnsafeEscapeHatch,
     Unknown,
 }
 
+impl WarningType {
+    pub const ALL: [WarningType; 7] = [
+        WarningType::ActorIsolation,
+        WarningType::SendableConformance,
+        WarningType::DataRace,
+        WarningType::PerformanceRegression,
+        WarningType::DeprecatedConcurrencyApi,
+        WarningType::UnsafeEscapeHatch,
+        WarningType::Unknown,
+    ];
+
+    /// Swift concurrency documentation explaining this kind of warning
+    pub fn docs_url(&self) -> Option<&'static str> {
+        match self {
+            WarningType::ActorIsolation => Some("https://www.swift.org/migration/documentation/swift-6-concurrency-migration-guide/dataracesafety#Isolation-Domains"),
+            WarningType::SendableConformance => Some("https://www.swift.org/migration/documentation/swift-6-concurrency-migration-guide/dataracesafety#Sendable-Types"),
+            WarningType::DataRace => Some("https://www.swift.org/migration/documentation/swift-6-concurrency-migration-guide/dataracesafety"),
+            WarningType::PerformanceRegression => Some("https://developer.apple.com/documentation/swift/concurrency"),
+            WarningType::DeprecatedConcurrencyApi => Some("https://www.swift.org/migration/documentation/swift-6-concurrency-migration-guide/incrementaladoption"),
+            WarningType::UnsafeEscapeHatch => Some("https://www.swift.org/migration/documentation/swift-6-concurrency-migration-guide/commonproblems"),
+            WarningType::Unknown => None,
+        }
+    }
+
+    /// Rough remediation effort, before any `[effort]` config override
+    pub fn default_effort(&self) -> Effort {
+        match self {
+            WarningType::SendableConformance | WarningType::DeprecatedConcurrencyApi => Effort::Low,
+            WarningType::ActorIsolation
+            | WarningType::PerformanceRegression
+            | WarningType::UnsafeEscapeHatch
+            | WarningType::Unknown => Effort::Medium,
+            WarningType::DataRace => Effort::High,
+        }
+    }
+
+    /// The snake_case name used in serialized output
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WarningType::ActorIsolation => "actor_isolation",
+            WarningType::SendableConformance => "sendable_conformance",
+            WarningType::DataRace => "data_race",
+            WarningType::PerformanceRegression => "performance_regression",
+            WarningType::DeprecatedConcurrencyApi => "deprecated_concurrency_api",
+            WarningType::UnsafeEscapeHatch => "unsafe_escape_hatch",
+            WarningType::Unknown => "unknown",
+        }
+    }
+}
+
+/// Rough remediation effort, for planning
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Effort {
+    Low,
+    Medium,
+    High,
+}
+
+impl Effort {
+    /// Estimate points summed into a run's total
+    pub fn points(self) -> usize {
+        match self {
+            Effort::Low => 1,
+            Effort::Medium => 3,
+            Effort::High => 5,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Effort::Low => "low",
+            Effort::Medium => "medium",
+            Effort::High => "high",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum Severity {
@@ -21,6 +104,44 @@ pub enum Severity {
     Low,
 }
 
+impl Severity {
+    /// Every severity, most severe first
+    pub const ALL: [Severity; 4] = [
+        Severity::Critical,
+        Severity::High,
+        Severity::Medium,
+        Severity::Low,
+    ];
+
+    /// One level less severe, bottoming out at `Low`
+    pub fn downgrade(self) -> Self {
+        match self {
+            Severity::Critical => Severity::High,
+            Severity::High => Severity::Medium,
+            Severity::Medium | Severity::Low => Severity::Low,
+        }
+    }
+
+    /// One level more severe, topping out at `Critical`
+    pub fn escalate(self) -> Self {
+        match self {
+            Severity::Low => Severity::Medium,
+            Severity::Medium => Severity::High,
+            Severity::High | Severity::Critical => Severity::Critical,
+        }
+    }
+
+    /// The snake_case name used in serialized output
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Critical => "critical",
+            Severity::High => "high",
+            Severity::Medium => "medium",
+            Severity::Low => "low",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Warning {
     pub id: String,
@@ -28,8 +149,116 @@ pub struct Warning {
     pub severity: Severity,
     pub file_path: PathBuf,
     pub line_number: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub column_number: Option<usize>,
     pub message: String,
+    #[serde(default)]
     pub code_context: CodeContext,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub suggested_fix: Option<String>,
+    /// Set when `--trim-message` stripped the Swift 6 escalation clause
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub error_in_swift6: bool,
+    /// Set when the file matched `--test-path`; severity has already been lowered
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub test_code: bool,
+    /// `--label` key/value pairs, e.g. the scheme that produced the warning
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, String>,
+    /// Remediation estimate from the type's default or the `[effort]` config
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub effort: Option<Effort>,
+    /// The input line the warning was parsed from, kept with `--keep-raw`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw: Option<String>,
+}
+
+impl Warning {
+    /// Whether this warning becomes an error in the Swift 6 language mode
+    pub fn is_error_in_swift6(&self) -> bool {
+        self.error_in_swift6 || SWIFT6_ERROR.is_match(&self.message)
+    }
+}
+
+#[cfg(test)]
+impl Warning {
+    /// Test fixture: a High actor isolation warning at `file_path:line_number`
+    /// with an empty message and context. Suites override fields with struct
+    /// update syntax, so a new `Warning` field only needs a default here.
+    pub(crate) fn fixture(file_path: impl Into<PathBuf>, line_number: usize) -> Self {
+        let file_path = file_path.into();
+        Self {
+            id: format!("{}:{line_number}:0", file_path.display()),
+            warning_type: WarningType::ActorIsolation,
+            severity: Severity::High,
+            file_path,
+            line_number,
+            column_number: None,
+            message: String::new(),
+            code_context: CodeContext::default(),
+            suggested_fix: None,
+            error_in_swift6: false,
+            test_code: false,
+            labels: BTreeMap::new(),
+            effort: None,
+            raw: None,
+        }
+    }
+}
+
+impl fmt::Display for Warning {
+    /// `severity type at file:line[:col] — message`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} at {}:{}",
+            self.severity.as_str(),
+            self.warning_type.as_str(),
+            self.file_path.display(),
+            self.line_number
+        )?;
+        if let Some(column) = self.column_number {
+            write!(f, ":{column}")?;
+        }
+        write!(f, " — {}", self.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn warning(message: &str) -> Warning {
+        Warning {
+            id: "id".to_string(),
+            column_number: Some(24),
+            message: message.to_string(),
+            ..Warning::fixture("/src/Item.swift", 37)
+        }
+    }
+
+    #[test]
+    fn test_is_error_in_swift6() {
+        assert!(warning("main actor-isolated property 'count' can not be mutated from a Sendable closure; this is an error in the Swift 6 language mode").is_error_in_swift6());
+        assert!(warning("Type 'NetworkManager' does not conform to the 'Sendable' protocol; this is an error in Swift 6").is_error_in_swift6());
+        assert!(!warning(
+            "actor-isolated property 'data' can not be referenced from a non-isolated context"
+        )
+        .is_error_in_swift6());
+    }
+
+    #[test]
+    fn test_display() {
+        let mut w = warning("actor-isolated property 'data' can not be referenced");
+        assert_eq!(
+            w.to_string(),
+            "high actor_isolation at /src/Item.swift:37:24 — actor-isolated property 'data' can not be referenced"
+        );
+
+        w.column_number = None;
+        assert_eq!(
+            w.to_string(),
+            "high actor_isolation at /src/Item.swift:37 — actor-isolated property 'data' can not be referenced"
+        );
+    }
 }
@@ -0,0 +1,153 @@
+use crate::parser::xcresult::XcresultSchema;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+lazy_static! {
+    // Any compiler diagnostic line, regardless of file type or category
+    static ref DIAGNOSTIC_LINE: Regex = Regex::new(
+        r"^[^:]+:\d+:\d+:\s*(warning|error|note):"
+    ).unwrap();
+
+    // Section markers that xcodebuild prints even for clean builds
+    static ref BUILD_MARKER: Regex = Regex::new(
+        r"^(\*\* (BUILD|TEST|CLEAN|ARCHIVE) \w+ \*\*|=== BUILD TARGET |Build settings from command line:)"
+    ).unwrap();
+}
+
+/// Input formats the parser chain knows how to read
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum InputFormat {
+    Xcresult,
+    XcodebuildJson,
+    RawLog,
+}
+
+impl InputFormat {
+    /// Name of the parser that reads this format, as reported in run output
+    pub fn parser_name(self) -> &'static str {
+        match self {
+            InputFormat::Xcresult => "xcresult",
+            InputFormat::XcodebuildJson => "xcodebuild",
+            InputFormat::RawLog => "rawlog",
+        }
+    }
+}
+
+/// Whether the top-level keys of `value` are those of an xcresult export: a
+/// legacy `_values` array, a declared `resultBundleVersion` or an Xcode 16
+/// `warnings` array
+pub fn is_xcresult_document(value: &Value) -> bool {
+    value.get("_values").is_some_and(Value::is_array)
+        || value.get("resultBundleVersion").is_some()
+        || XcresultSchema::detect(value) == XcresultSchema::Xcode16
+}
+
+/// The first JSON document of `content` when it is an xcresult export, or
+/// its parse error when `content` is a multi-line document that doesn't parse
+/// (e.g. a truncated export). `None` for anything else, including
+/// one-object-per-line xcodebuild output.
+pub fn xcresult_document(content: &str) -> Option<serde_json::Result<Value>> {
+    let trimmed = content.trim_start();
+    if !trimmed.starts_with('{') {
+        return None;
+    }
+    let first = serde_json::Deserializer::from_str(trimmed)
+        .into_iter::<Value>()
+        .next()?;
+    match first {
+        Ok(value) => is_xcresult_document(&value).then_some(Ok(value)),
+        Err(e) => {
+            let first_line = trimmed.lines().next().unwrap_or_default();
+            serde_json::from_str::<Value>(first_line)
+                .is_err()
+                .then_some(Err(e))
+        }
+    }
+}
+
+/// Detect which known format `content` is in, if any
+pub fn detect_format(content: &str) -> Option<InputFormat> {
+    let trimmed = content.trim_start();
+    if let Some(Ok(_)) = xcresult_document(content) {
+        return Some(InputFormat::Xcresult);
+    }
+
+    if trimmed.starts_with('[')
+        && serde_json::from_str::<Value>(content).is_ok_and(|v| v.is_array())
+    {
+        return Some(InputFormat::XcodebuildJson);
+    }
+
+    let mut saw_raw_log = false;
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if line.starts_with('{') && serde_json::from_str::<Value>(line).is_ok_and(|v| v.is_object())
+        {
+            return Some(InputFormat::XcodebuildJson);
+        }
+
+        let (_, line) = crate::parser::rawlog::split_fastlane_prefix(line);
+        if DIAGNOSTIC_LINE.is_match(line) || BUILD_MARKER.is_match(line) {
+            saw_raw_log = true;
+        }
+    }
+
+    saw_raw_log.then_some(InputFormat::RawLog)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_known_formats() {
+        assert_eq!(
+            detect_format(r#"{"_values": []}"#),
+            Some(InputFormat::Xcresult)
+        );
+        assert_eq!(
+            detect_format(r#"{"resultBundleVersion": 4, "warnings": []}"#),
+            Some(InputFormat::Xcresult)
+        );
+        assert_eq!(
+            detect_format(r#"{"warnings": [{"message": "data race detected"}]}"#),
+            Some(InputFormat::Xcresult)
+        );
+        assert_eq!(
+            detect_format(r#"{"type": "warning", "message": "test"}"#),
+            Some(InputFormat::XcodebuildJson)
+        );
+        assert_eq!(
+            detect_format("[\n  {\"type\": \"warning\", \"message\": \"test\"}\n]"),
+            Some(InputFormat::XcodebuildJson)
+        );
+        assert_eq!(
+            detect_format("/test/File.swift:10:5: warning: variable 'x' was never used"),
+            Some(InputFormat::RawLog)
+        );
+        assert_eq!(
+            detect_format("=== BUILD TARGET App OF PROJECT App ===\n** BUILD SUCCEEDED **"),
+            Some(InputFormat::RawLog)
+        );
+        assert_eq!(
+            detect_format(
+                "[10:42:05]: ▸ /test/File.swift:10:5: warning: variable 'x' was never used"
+            ),
+            Some(InputFormat::RawLog)
+        );
+    }
+
+    #[test]
+    fn test_detect_unknown_format() {
+        assert_eq!(detect_format("this is not a build log"), None);
+        assert_eq!(detect_format("{not json"), None);
+        assert_eq!(detect_format(""), None);
+    }
+}
@@ -1,11 +1,17 @@
+pub mod detect;
+pub mod pattern_pack;
 pub mod patterns;
 pub mod rawlog;
+pub mod source_map;
 pub mod warnings;
 pub mod xcodebuild;
 pub mod xcresult;
 
+pub use detect::*;
+pub use pattern_pack::*;
 pub use patterns::*;
 pub use rawlog::*;
+pub use source_map::*;
 pub use warnings::*;
 pub use xcodebuild::*;
 pub use xcresult::*;
@@ -0,0 +1,231 @@
+use crate::error::{ParseError, Result};
+use crate::models::{Severity, WarningType};
+use crate::parser::patterns::{categorize_warning, HIGH_CONFIDENCE};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Deserialize;
+use std::path::Path;
+use std::sync::Arc;
+
+lazy_static! {
+    // The `warning:` keyword stays English in localized toolchains; only the
+    // message text after it is translated
+    static ref WARNING_LINE: Regex = Regex::new(
+        r"^[^:]+:\d+:\d+:\s*warning:\s*(?P<message>.+)$"
+    ).unwrap();
+}
+
+/// Fewest warning lines before the localized-log heuristic is trusted
+const MIN_LOCALIZED_SAMPLE: usize = 3;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PackFile {
+    #[serde(default)]
+    patterns: Vec<PatternEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PatternEntry {
+    regex: toml::Spanned<String>,
+    #[serde(rename = "type")]
+    warning_type: WarningType,
+    severity: Severity,
+}
+
+#[derive(Debug)]
+struct CustomPattern {
+    regex: Regex,
+    warning_type: WarningType,
+    severity: Severity,
+}
+
+/// Extra message patterns loaded with `--patterns`, e.g. for compilers whose
+/// diagnostics are localized. They are tried in file order before the
+/// built-in English patterns.
+#[derive(Debug, Clone, Default)]
+pub struct PatternPack {
+    patterns: Arc<Vec<CustomPattern>>,
+}
+
+impl PatternPack {
+    /// Load a TOML file of `[[patterns]]` entries, each with a `regex`, a
+    /// warning `type` and a `severity`
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Self::from_toml(&content)
+            .map_err(|e| ParseError::ConfigError(format!("{}: {e}", path.display())))
+    }
+
+    /// Parse a pack, reporting every invalid pattern with its line and column
+    fn from_toml(content: &str) -> std::result::Result<Self, String> {
+        let file: PackFile = toml::from_str(content).map_err(|e| e.to_string())?;
+        let mut patterns = Vec::new();
+        let mut errors = Vec::new();
+        for entry in file.patterns {
+            match Regex::new(entry.regex.get_ref()) {
+                Ok(regex) => patterns.push(CustomPattern {
+                    regex,
+                    warning_type: entry.warning_type,
+                    severity: entry.severity,
+                }),
+                Err(e) => {
+                    let (line, column) = line_column(content, entry.regex.span().start);
+                    errors.push(format!(
+                        "invalid pattern '{}' at line {line}, column {column}: {e}",
+                        entry.regex.get_ref()
+                    ));
+                }
+            }
+        }
+        if !errors.is_empty() {
+            return Err(errors.join("\n"));
+        }
+        Ok(Self {
+            patterns: Arc::new(patterns),
+        })
+    }
+
+    /// Number of patterns in the pack
+    pub fn len(&self) -> usize {
+        self.patterns.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Categorize `message` with the first matching pack pattern, falling
+    /// back to [`categorize_warning`]
+    pub fn categorize(&self, message: &str) -> (WarningType, Severity, f32) {
+        self.patterns
+            .iter()
+            .find(|pattern| pattern.regex.is_match(message))
+            .map_or_else(
+                || categorize_warning(message),
+                |pattern| (pattern.warning_type, pattern.severity, HIGH_CONFIDENCE),
+            )
+    }
+
+    /// Whether the `warning:` lines in `content` look like a localized
+    /// compiler's: most stay uncategorized and are written mostly in non-ASCII
+    /// text. Returns `(unmatched, total)` when so.
+    pub fn localized_warnings(&self, content: &str) -> Option<(usize, usize)> {
+        let messages: Vec<&str> = content
+            .lines()
+            .filter_map(|line| WARNING_LINE.captures(line.trim_start()))
+            .filter_map(|caps| caps.name("message"))
+            .map(|m| m.as_str())
+            .collect();
+        if messages.len() < MIN_LOCALIZED_SAMPLE {
+            return None;
+        }
+
+        let unmatched = messages
+            .iter()
+            .filter(|message| self.categorize(message).0 == WarningType::Unknown)
+            .count();
+        let non_ascii = messages
+            .iter()
+            .filter(|message| message.chars().any(|c| c.is_alphabetic() && !c.is_ascii()))
+            .count();
+        // At least 80% unmatched and half non-ASCII
+        (unmatched * 5 >= messages.len() * 4 && non_ascii * 2 >= messages.len())
+            .then_some((unmatched, messages.len()))
+    }
+}
+
+/// 1-based line and column of byte `offset` in `content`
+fn line_column(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().map_or(0, |l| l.chars().count()) + 1;
+    (line, column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GERMAN_PACK: &str = r#"
+[[patterns]]
+regex = "(?i)akteur-isoliert"
+type = "actor_isolation"
+severity = "high"
+
+[[patterns]]
+regex = "(?i)nicht.*'Sendable'"
+type = "sendable_conformance"
+severity = "medium"
+"#;
+
+    #[test]
+    fn test_pack_matches_localized_message() {
+        let pack = PatternPack::from_toml(GERMAN_PACK).unwrap();
+
+        assert_eq!(
+            pack.categorize(
+                "Akteur-isolierte Eigenschaft 'count' kann nicht aus einem nicht isolierten Kontext referenziert werden"
+            ),
+            (WarningType::ActorIsolation, Severity::High, HIGH_CONFIDENCE)
+        );
+        assert_eq!(
+            pack.categorize("Typ 'Cache' entspricht nicht dem Protokoll 'Sendable'")
+                .0,
+            WarningType::SendableConformance
+        );
+        // English messages still use the built-in patterns
+        assert_eq!(
+            pack.categorize("data race detected in shared state").0,
+            WarningType::DataRace
+        );
+        assert_eq!(
+            PatternPack::default()
+                .categorize("Akteur-isolierte Eigenschaft 'count' kann nicht referenziert werden")
+                .0,
+            WarningType::Unknown
+        );
+    }
+
+    #[test]
+    fn test_invalid_pattern_is_reported() {
+        let err = PatternPack::from_toml(
+            "[[patterns]]\nregex = \"(unclosed\"\ntype = \"data_race\"\nseverity = \"high\"\n",
+        )
+        .unwrap_err();
+        assert!(
+            err.contains("invalid pattern '(unclosed' at line 2, column 9"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_localized_warnings_heuristic() {
+        let japanese = [
+            "/src/A.swift:1:1: warning: メインアクター分離プロパティ 'count' は変更できません",
+            "/src/B.swift:2:1: warning: 型 'Cache' は送信可能プロトコルに準拠していません",
+            "/src/C.swift:3:1: warning: 変数 'x' は一度も使用されていません",
+        ]
+        .join("\n");
+        assert_eq!(
+            PatternPack::default().localized_warnings(&japanese),
+            Some((3, 3))
+        );
+
+        let english = [
+            "/src/A.swift:1:1: warning: variable 'x' was never used",
+            "/src/B.swift:2:1: warning: initialization of immutable value 'y' was never used",
+            "/src/C.swift:3:1: warning: will never be executed",
+        ]
+        .join("\n");
+        // Unmatched, but plain English warnings are not a locale problem
+        assert_eq!(PatternPack::default().localized_warnings(&english), None);
+
+        let pack = PatternPack::from_toml(
+            "[[patterns]]\nregex = \"アクター|送信可能|変数\"\ntype = \"actor_isolation\"\nseverity = \"high\"\n",
+        )
+        .unwrap();
+        assert_eq!(pack.localized_warnings(&japanese), None);
+    }
+}
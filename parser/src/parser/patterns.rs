@@ -1,4 +1,4 @@
-use crate::models::{Severity, WarningType};
+use crate::models::{CodeContext, Severity, WarningType};
 use lazy_static::lazy_static;
 use regex::Regex;
 
@@ -13,6 +13,79 @@ lazy_static! {
         r"(?i)(type\s+'[^']+'\s+does\s+not\s+conform\s+to.*sendable)|(capture.*requires.*sendable)|(.*non-sendable.*)"
     ).unwrap();
 
+    // Missing conformance, usually fixed by annotating the type, e.g.
+    // "type 'Config' does not conform to the 'Sendable' protocol"
+    pub static ref SENDABLE_MISSING_CONFORMANCE: Regex = Regex::new(
+        r"(?i)does\s+not\s+conform\s+to\s+(the\s+)?'?Sendable'?"
+    ).unwrap();
+
+    // Non-Sendable values actually shared across isolation domains, e.g.
+    // "non-sendable type 'Cache' returned by call to actor-isolated function cannot cross actor boundary"
+    pub static ref SENDABLE_BOUNDARY_CROSSING: Regex = Regex::new(
+        r"(?i)non-sendable.*\b(cross(es|ing)?)\s+(an?\s+|the\s+)?actor\s+boundar(y|ies)"
+    ).unwrap();
+
+    // Captures in `@Sendable` closures, e.g.
+    // "capture of 'self' with non-sendable type 'Foo' in a `@Sendable` closure"
+    pub static ref SENDABLE_CLOSURE_CAPTURE: Regex = Regex::new(
+        r"(?i)capture\s+of\s+'[^']+'.*in\s+a\s+`?@Sendable`?\s+closure"
+    ).unwrap();
+
+    // Main-actor access from `deinit`, which cannot `await`, e.g.
+    // "call to main actor-isolated instance method 'stop()' from a nonisolated deinit"
+    pub static ref DEINIT_MAIN_ACTOR: Regex = Regex::new(
+        r"(?i)main\s+actor-isolated.*\bnonisolated\s+deinit\b"
+    ).unwrap();
+
+    // Swift 6 `isolated deinit` (SE-0371), e.g.
+    // "isolated deinit is only available in macOS 15.4.0 or newer" or
+    // "'isolated' deinit requires frontend flag -enable-experimental-feature IsolatedDeinit".
+    // "nonisolated deinit" has no word boundary before "isolated", so it doesn't match.
+    pub static ref ISOLATED_DEINIT: Regex = Regex::new(
+        r"(?i)\bisolated'?\s+deinit\b"
+    ).unwrap();
+
+    // Misused isolation assumptions, e.g.
+    // "main actor-isolated property 'title' accessed without await inside assumeIsolated",
+    // "'assumeIsolated' is unavailable from asynchronous contexts" or
+    // "non-sendable result type 'Snapshot' of 'MainActor.run' cannot be returned".
+    // Diagnostics that only mention a closure passed to these APIs don't match.
+    pub static ref ISOLATION_ASSUMPTION: Regex = Regex::new(
+        r"(?i)(\binside\s+'?(MainActor\.)?assumeIsolated\b)|('?(MainActor\.)?assumeIsolated'?\s+is\s+unavailable)|(\bresult\s+type\b.*\bof\s+'?MainActor\.run\b)"
+    ).unwrap();
+
+    // Conformances whose witness isolation doesn't match the protocol requirement, e.g.
+    // "main actor-isolated instance method 'update()' cannot be used to satisfy nonisolated protocol requirement"
+    pub static ref WITNESS_ISOLATION_MISMATCH: Regex = Regex::new(
+        r"(?i)isolated\s+.*\b(can\s*not|cannot)\s+(be\s+used\s+to\s+)?satisfy\s+.*requirement"
+    ).unwrap();
+
+    // `isolated` parameters and the `#isolation` macro, e.g.
+    // "isolated parameter 'actor' is unused" or
+    // "'#isolation' can only be used as a default argument".
+    // `-` is a word boundary, so "non-isolated" and "actor-isolated" are
+    // excluded by the character before "isolated" instead.
+    pub static ref ISOLATED_PARAMETER: Regex = Regex::new(
+        r"(?i)((^|[^-\w'])'?isolated'?\s+parameter)|(#isolation\b)"
+    ).unwrap();
+
+    // Escalation clause appended to warnings that become errors under Swift 6
+    pub static ref SWIFT6_ERROR: Regex = Regex::new(
+        r"(?i)this\s+is\s+an\s+error\s+in\s+(the\s+)?Swift\s+6(\s+language\s+mode)?"
+    ).unwrap();
+
+    // Trailing escalation boilerplate, e.g. "; this will be an error in a future Swift language mode"
+    pub static ref TRAILING_ESCALATION: Regex = Regex::new(
+        r"(?i)\s*;\s*this\s+(is|will\s+be)\s+an\s+error\s+in\s+(the\s+|a\s+future\s+)?Swift(\s+\d+)?(\s+language\s+mode)?\s*\.?\s*$"
+    ).unwrap();
+
+    // Concurrency-safety escape hatches, e.g.
+    // "'nonisolated(unsafe)' is unnecessary for a constant with 'Sendable' type" or
+    // "class 'Cache' must be 'final' to conform to '@unchecked Sendable'"
+    pub static ref UNSAFE_ESCAPE_HATCH: Regex = Regex::new(
+        r"(?i)(nonisolated\s*\(\s*unsafe\s*\))|(@?unchecked\s+Sendable)"
+    ).unwrap();
+
     // Data race patterns
     pub static ref DATA_RACE: Regex = Regex::new(
         r"(?i)(data\s+race|race\s+condition|concurrent\s+access|mutation\s+of\s+captured\s+var)"
@@ -28,40 +101,284 @@ lazy_static! {
         r"(?i)(task.*cancelled|task.*leaked|detached\s+task)"
     ).unwrap();
 
+    // Deprecated or inadvisable concurrency APIs, e.g.
+    // "'withUnsafeContinuation' is deprecated" or
+    // "instance method 'wait' is unavailable from asynchronous contexts"
+    pub static ref DEPRECATED_CONCURRENCY_API: Regex = Regex::new(
+        r"(?i)('[^']*(continuation|dispatch|detach|task|async|actor|thread|semaphore|operationqueue)[^']*'\s+(is|was)\s+deprecated)|(unavailable\s+from\s+asynchronous\s+contexts?)|(dispatchqueue\S*'?\s+usage\s+in\s+(an\s+)?async\s+context)"
+    ).unwrap();
+
+    // Looser secondary patterns for messages that mention concurrency concepts
+    // without matching any of the specific categories above
+    pub static ref POSSIBLY_CONCURRENCY: Regex = Regex::new(
+        r"(?i)(concurren|isolat|\bactors?\b|\basync\b|\bawait\b|\btask\b|sendable|@MainActor)"
+    ).unwrap();
+
     // MainActor related warnings
     pub static ref MAIN_ACTOR: Regex = Regex::new(
         r"(?i)(main\s+actor.*isolation|call\s+to\s+main\s+actor|main\s+actor.*unsafe)"
     ).unwrap();
 }
 
-pub fn categorize_warning(message: &str) -> (WarningType, Severity) {
+/// Whether a Sendable warning is specifically about a closure capture
+pub fn is_sendable_closure_capture(message: &str) -> bool {
+    SENDABLE_CLOSURE_CAPTURE.is_match(message)
+}
+
+/// Whether a Sendable warning is about a non-Sendable value crossing an actor boundary
+pub fn is_sendable_boundary_crossing(message: &str) -> bool {
+    SENDABLE_BOUNDARY_CROSSING.is_match(message)
+}
+
+/// Whether an actor isolation warning is a main-actor access from `deinit`
+pub fn is_deinit_main_actor(message: &str) -> bool {
+    DEINIT_MAIN_ACTOR.is_match(message)
+}
+
+/// Whether an actor isolation warning is about an `isolated deinit`
+pub fn is_isolated_deinit(message: &str) -> bool {
+    ISOLATED_DEINIT.is_match(message)
+}
+
+/// Whether an actor isolation warning is about `MainActor.assumeIsolated` or
+/// `MainActor.run`
+pub fn is_isolation_assumption(message: &str) -> bool {
+    ISOLATION_ASSUMPTION.is_match(message)
+}
+
+/// Whether an actor isolation warning is a conformance whose witness isolation
+/// doesn't match the protocol requirement
+pub fn is_witness_isolation_mismatch(message: &str) -> bool {
+    WITNESS_ISOLATION_MISMATCH.is_match(message)
+}
+
+/// Whether an actor isolation warning is about an `isolated` parameter or
+/// the `#isolation` macro
+pub fn is_isolated_parameter(message: &str) -> bool {
+    ISOLATED_PARAMETER.is_match(message)
+}
+
+/// Confidence for messages matched by one of the specific category patterns
+pub const HIGH_CONFIDENCE: f32 = 1.0;
+
+/// Confidence for messages only matched by the looser secondary patterns
+pub const LOW_CONFIDENCE: f32 = 0.5;
+
+/// Categorize a diagnostic message, returning its type, severity and a confidence
+/// score in `0.0..=1.0` that the message is concurrency-related at all.
+pub fn categorize_warning(message: &str) -> (WarningType, Severity, f32) {
     // Check for data races first (most critical)
     if DATA_RACE.is_match(message) {
-        return (WarningType::DataRace, Severity::Critical);
+        return (WarningType::DataRace, Severity::Critical, HIGH_CONFIDENCE);
     }
 
     // Check for actor isolation violations
-    if ACTOR_ISOLATION.is_match(message) || MAIN_ACTOR.is_match(message) {
-        return (WarningType::ActorIsolation, Severity::High);
+    if ACTOR_ISOLATION.is_match(message)
+        || MAIN_ACTOR.is_match(message)
+        || DEINIT_MAIN_ACTOR.is_match(message)
+        || ISOLATED_DEINIT.is_match(message)
+        || ISOLATION_ASSUMPTION.is_match(message)
+        || WITNESS_ISOLATION_MISMATCH.is_match(message)
+    {
+        return (WarningType::ActorIsolation, Severity::High, HIGH_CONFIDENCE);
+    }
+
+    // Usually dead or misplaced isolation plumbing rather than an unsafe access
+    if ISOLATED_PARAMETER.is_match(message) {
+        return (
+            WarningType::ActorIsolation,
+            Severity::Medium,
+            HIGH_CONFIDENCE,
+        );
+    }
+
+    // Check for escape-hatch annotations (only reported when opted in). These
+    // mention Sendable, so they must be matched before Sendable conformance.
+    if UNSAFE_ESCAPE_HATCH.is_match(message) {
+        return (
+            WarningType::UnsafeEscapeHatch,
+            Severity::Medium,
+            HIGH_CONFIDENCE,
+        );
     }
 
-    // Check for Sendable conformance issues
-    if SENDABLE_CONFORMANCE.is_match(message) {
-        return (WarningType::SendableConformance, Severity::High);
+    // Check for Sendable conformance issues. A missing conformance is often a
+    // one-line annotation; values shared across actors are genuine risks.
+    if SENDABLE_CONFORMANCE.is_match(message) || SENDABLE_BOUNDARY_CROSSING.is_match(message) {
+        let severity = if SENDABLE_MISSING_CONFORMANCE.is_match(message)
+            && !SENDABLE_BOUNDARY_CROSSING.is_match(message)
+        {
+            Severity::Medium
+        } else {
+            Severity::High
+        };
+        return (WarningType::SendableConformance, severity, HIGH_CONFIDENCE);
     }
 
     // Check for task-related issues
     if TASK_WARNINGS.is_match(message) {
-        return (WarningType::ActorIsolation, Severity::Medium);
+        return (
+            WarningType::ActorIsolation,
+            Severity::Medium,
+            HIGH_CONFIDENCE,
+        );
     }
 
     // Check for performance issues
     if PERFORMANCE.is_match(message) {
-        return (WarningType::PerformanceRegression, Severity::Medium);
+        return (
+            WarningType::PerformanceRegression,
+            Severity::Medium,
+            HIGH_CONFIDENCE,
+        );
+    }
+
+    // Check for deprecated concurrency APIs (only reported when opted in)
+    if DEPRECATED_CONCURRENCY_API.is_match(message) {
+        return (
+            WarningType::DeprecatedConcurrencyApi,
+            Severity::Medium,
+            HIGH_CONFIDENCE,
+        );
+    }
+
+    // Possibly concurrency-related, but not specific enough to categorize
+    if POSSIBLY_CONCURRENCY.is_match(message) {
+        return (WarningType::Unknown, Severity::Low, LOW_CONFIDENCE);
     }
 
     // Default to unknown
-    (WarningType::Unknown, Severity::Low)
+    (WarningType::Unknown, Severity::Low, 0.0)
+}
+
+/// The built-in `suggested_fix` for a categorized message, shared by every
+/// parser. `[fixes]` templates from `--config` take precedence.
+pub fn builtin_fix(warning_type: WarningType, message: &str) -> Option<String> {
+    match warning_type {
+        WarningType::ActorIsolation => {
+            if is_isolated_deinit(message) {
+                Some("'isolated deinit' (SE-0371) needs a Swift 6 compiler and a deployment target whose runtime supports it; raise them, or keep 'deinit' nonisolated and finish main-actor cleanup before the last reference is released.".to_string())
+            } else if is_isolation_assumption(message) {
+                Some("'MainActor.assumeIsolated' only holds in synchronous code already running on the main actor and traps otherwise; from async code use 'await MainActor.run' or make the caller '@MainActor', and return only Sendable values from 'MainActor.run'.".to_string())
+            } else if is_deinit_main_actor(message) {
+                Some("'deinit' cannot 'await' the main actor: move cleanup off the main actor or capture the state it needs before deinit.".to_string())
+            } else if is_witness_isolation_mismatch(message) {
+                Some("Match the protocol requirement's isolation: mark the witness 'nonisolated', or isolate the conformance (e.g. '@MainActor' on the conformance) if every caller is on that actor.".to_string())
+            } else if is_isolated_parameter(message) {
+                Some("Pass the caller's isolation on (e.g. 'isolation: isolated (any Actor)? = #isolation') so callees run on the same actor, or remove the 'isolated' parameter if the function doesn't need it.".to_string())
+            } else if message.contains("can not be mutated") || message.contains("cannot be mutated") {
+                Some("Consider using 'await' or @MainActor to safely mutate the actor-isolated property.".to_string())
+            } else if message.contains("can not be referenced") || message.contains("cannot be referenced") {
+                Some("Use 'await' to access the actor-isolated member, or move this code into an actor context.".to_string())
+            } else if message.contains("Main actor") {
+                Some("Use '@MainActor' annotation or dispatch to the main queue with 'await MainActor.run'.".to_string())
+            } else {
+                Some("Ensure proper actor isolation by using 'await' or moving code to appropriate actor context.".to_string())
+            }
+        }
+        WarningType::SendableConformance => {
+            if is_sendable_closure_capture(message) {
+                Some("Capture a Sendable copy of the value in the closure's capture list (e.g. '[value]'), or make the captured type Sendable.".to_string())
+            } else if is_sendable_boundary_crossing(message) {
+                Some("Avoid sharing the value across actors: make its type Sendable, pass a copy, or mark the parameter 'sending' to transfer ownership.".to_string())
+            } else if message.contains("does not conform") {
+                Some("Add 'Sendable' conformance to the type or use '@unchecked Sendable' if thread-safe.".to_string())
+            } else if message.contains("capture") {
+                Some("Ensure captured values conform to 'Sendable' or restructure to avoid capture.".to_string())
+            } else {
+                Some("Review Sendable conformance requirements for concurrent contexts.".to_string())
+            }
+        }
+        WarningType::DataRace => {
+            Some("Protect shared mutable state with proper synchronization (actors, locks, or atomic operations).".to_string())
+        }
+        WarningType::PerformanceRegression => {
+            Some("Review async/await usage patterns and consider optimizing concurrency structure.".to_string())
+        }
+        WarningType::DeprecatedConcurrencyApi => {
+            if message.contains("Continuation") {
+                Some("Use 'withCheckedContinuation' or 'withCheckedThrowingContinuation', which verify the continuation is resumed exactly once.".to_string())
+            } else if message.contains("DispatchQueue") {
+                Some("Replace 'DispatchQueue.main.async' with 'await MainActor.run' or a '@MainActor' function.".to_string())
+            } else if message.contains("unavailable from asynchronous contexts") {
+                Some("Avoid blocking calls in async code; await a Task or use an AsyncStream instead.".to_string())
+            } else {
+                Some("Migrate to the structured-concurrency replacement (async/await, 'async let', or task groups).".to_string())
+            }
+        }
+        WarningType::UnsafeEscapeHatch => {
+            Some("Justify the escape hatch with a comment naming the synchronization that makes it safe, or remove the annotation and fix the isolation.".to_string())
+        }
+        WarningType::Unknown => None,
+    }
+}
+
+/// Opt-in detection behavior shared by all parsers
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DetectionOptions {
+    /// Report uncategorized messages matched by the looser secondary patterns
+    pub include_low_confidence: bool,
+    /// Report deprecated/inadvisable concurrency API usage
+    pub detect_deprecated: bool,
+    /// Report `nonisolated(unsafe)` / `@unchecked Sendable` escape hatches
+    pub audit_unsafe: bool,
+    /// Strip trailing escalation clauses from messages before ids are derived
+    pub trim_message: bool,
+    /// Keep every uncategorized warning so the caller can list them
+    pub keep_unknown: bool,
+    /// Trim trailing whitespace and stray carriage returns from code context
+    pub normalize_context: bool,
+    /// Keep the input line each warning was parsed from, for auditing
+    pub keep_raw: bool,
+}
+
+impl DetectionOptions {
+    /// Normalize a diagnostic message, returning it along with whether a Swift 6
+    /// escalation clause was stripped from it
+    pub fn normalize_message(&self, message: &str) -> (String, bool) {
+        if self.trim_message {
+            trim_escalation(message)
+        } else {
+            (message.to_string(), false)
+        }
+    }
+
+    /// Apply `normalize_context` to freshly extracted code context
+    pub fn finish_context(&self, context: CodeContext) -> CodeContext {
+        if self.normalize_context {
+            context.normalize_whitespace()
+        } else {
+            context
+        }
+    }
+}
+
+/// Strip a trailing "; this is an error in the Swift 6 language mode" (or similar)
+/// clause. The flag reports whether the stripped clause was the Swift 6 one.
+pub fn trim_escalation(message: &str) -> (String, bool) {
+    match TRAILING_ESCALATION.find(message) {
+        Some(clause) => (
+            message[..clause.start()].to_string(),
+            SWIFT6_ERROR.is_match(clause.as_str()),
+        ),
+        None => (message.to_string(), false),
+    }
+}
+
+/// Whether a categorized message should be reported under `options`
+pub fn should_report(
+    warning_type: WarningType,
+    confidence: f32,
+    options: &DetectionOptions,
+) -> bool {
+    match warning_type {
+        WarningType::Unknown => {
+            options.keep_unknown || (options.include_low_confidence && confidence > 0.0)
+        }
+        WarningType::DeprecatedConcurrencyApi => options.detect_deprecated,
+        WarningType::UnsafeEscapeHatch => options.audit_unsafe,
+        _ => true,
+    }
 }
 
 #[cfg(test)]
@@ -78,7 +395,7 @@ mod tests {
         ];
 
         for (i, message) in messages.iter().enumerate() {
-            let (warning_type, severity) = categorize_warning(message);
+            let (warning_type, severity, _) = categorize_warning(message);
             println!("Message {i}: {message} -> {warning_type:?}");
             assert_eq!(
                 warning_type,
@@ -98,11 +415,292 @@ mod tests {
         ];
 
         for message in messages {
-            let (warning_type, _) = categorize_warning(message);
+            let (warning_type, _, _) = categorize_warning(message);
             assert_eq!(warning_type, WarningType::SendableConformance);
         }
     }
 
+    #[test]
+    fn test_sendable_severity_by_risk() {
+        let conformance = [
+            "Type 'MyClass' does not conform to the 'Sendable' protocol",
+            "stored property 'cache' of 'Sendable'-conforming class 'Store' has non-sendable type; type 'Cache' does not conform to 'Sendable'",
+        ];
+        for message in conformance {
+            let (warning_type, severity, _) = categorize_warning(message);
+            assert_eq!(warning_type, WarningType::SendableConformance, "{message}");
+            assert_eq!(severity, Severity::Medium, "{message}");
+        }
+
+        let crossing = [
+            "value of non-Sendable type 'Cache' crosses actor boundary",
+            "non-sendable type 'Item' returned by implicitly asynchronous call to actor-isolated function cannot cross actor boundary",
+            "passing non-sendable parameter to async function",
+        ];
+        for message in crossing {
+            let (warning_type, severity, _) = categorize_warning(message);
+            assert_eq!(warning_type, WarningType::SendableConformance, "{message}");
+            assert_eq!(severity, Severity::High, "{message}");
+        }
+        assert!(is_sendable_boundary_crossing(crossing[0]));
+        assert!(!is_sendable_boundary_crossing(conformance[0]));
+    }
+
+    #[test]
+    fn test_deinit_main_actor_patterns() {
+        let messages = [
+            "call to main actor-isolated instance method 'stopTimer()' from a nonisolated deinit",
+            "main actor-isolated property 'observer' can not be referenced from a nonisolated deinit; this is an error in the Swift 6 language mode",
+        ];
+
+        for message in messages {
+            let (warning_type, severity, _) = categorize_warning(message);
+            assert_eq!(warning_type, WarningType::ActorIsolation, "{message}");
+            assert_eq!(severity, Severity::High);
+            assert!(is_deinit_main_actor(message), "{message}");
+        }
+
+        assert!(!is_deinit_main_actor(
+            "main actor-isolated property 'count' can not be mutated from a Sendable closure"
+        ));
+    }
+
+    #[test]
+    fn test_isolated_deinit_patterns() {
+        let messages = [
+            "isolated deinit is only available in macOS 15.4.0 or newer",
+            "'isolated' deinit requires frontend flag -enable-experimental-feature IsolatedDeinit to enable the usage of this language feature",
+            "isolated deinit has no effect on class 'Store' that is not isolated to a global actor",
+            "'isolated deinit' can only be used within classes",
+        ];
+
+        for message in messages {
+            let (warning_type, severity, _) = categorize_warning(message);
+            assert_eq!(warning_type, WarningType::ActorIsolation, "{message}");
+            assert_eq!(severity, Severity::High, "{message}");
+            assert!(is_isolated_deinit(message), "{message}");
+        }
+
+        // Main-actor access from a plain deinit is a different problem
+        let nonisolated =
+            "call to main actor-isolated instance method 'stopTimer()' from a nonisolated deinit";
+        assert!(!is_isolated_deinit(nonisolated));
+        assert!(is_deinit_main_actor(nonisolated));
+    }
+
+    #[test]
+    fn test_isolation_assumption_patterns() {
+        let messages = [
+            "main actor-isolated property 'title' accessed without await inside assumeIsolated",
+            "call to main actor-isolated instance method 'reload()' inside 'MainActor.assumeIsolated' closure from a nonisolated context",
+            "'assumeIsolated' is unavailable from asynchronous contexts; use 'await' instead",
+            "non-sendable result type 'Snapshot' of 'MainActor.run' cannot be returned",
+        ];
+
+        for message in messages {
+            let (warning_type, severity, confidence) = categorize_warning(message);
+            assert_eq!(warning_type, WarningType::ActorIsolation, "{message}");
+            assert_eq!(severity, Severity::High, "{message}");
+            assert_eq!(confidence, HIGH_CONFIDENCE, "{message}");
+            assert!(is_isolation_assumption(message), "{message}");
+        }
+
+        // Only the API names match, not words that contain them
+        assert!(!is_isolation_assumption(
+            "actor-isolated property 'runCount' is accessed from MainActor.running"
+        ));
+
+        // Closures merely passed to these APIs keep their own category
+        let capture = "capture of 'model' with non-sendable type 'Model' in a `@Sendable` closure passed to 'MainActor.run'";
+        assert!(!is_isolation_assumption(capture));
+        let (warning_type, _, _) = categorize_warning(capture);
+        assert_eq!(warning_type, WarningType::SendableConformance);
+        assert!(is_sendable_closure_capture(capture));
+        let quoted = "capture of 'model' with non-sendable type 'Model' in a '@Sendable' closure passed to 'MainActor.run'";
+        assert_eq!(
+            categorize_warning(quoted).0,
+            WarningType::SendableConformance
+        );
+    }
+
+    #[test]
+    fn test_witness_isolation_mismatch_patterns() {
+        let messages = [
+            "main actor-isolated instance method 'update()' cannot be used to satisfy nonisolated protocol requirement",
+            "main actor-isolated instance method 'render()' cannot satisfy nonisolated requirement",
+            "actor-isolated property 'name' cannot be used to satisfy nonisolated requirement from protocol 'Named'; this is an error in the Swift 6 language mode",
+            "global actor 'DatabaseActor'-isolated instance method 'load()' can not satisfy nonisolated requirement",
+        ];
+
+        for message in messages {
+            let (warning_type, severity, _) = categorize_warning(message);
+            assert_eq!(warning_type, WarningType::ActorIsolation, "{message}");
+            assert_eq!(severity, Severity::High);
+            assert!(is_witness_isolation_mismatch(message), "{message}");
+        }
+
+        assert!(!is_witness_isolation_mismatch(
+            "actor-isolated property 'shared' can not be referenced from a non-isolated context"
+        ));
+    }
+
+    #[test]
+    fn test_isolated_parameter_patterns() {
+        let messages = [
+            "isolated parameter 'actor' is unused",
+            "'isolated' parameter has non-actor type 'Int'",
+            "cannot have more than one 'isolated' parameter",
+            "'#isolation' can only be used as a default argument",
+            "#isolation macro default argument is ignored for nonisolated function 'load()'",
+        ];
+
+        for message in messages {
+            let (warning_type, severity, _) = categorize_warning(message);
+            assert_eq!(warning_type, WarningType::ActorIsolation, "{message}");
+            assert_eq!(severity, Severity::Medium, "{message}");
+            assert!(is_isolated_parameter(message), "{message}");
+        }
+
+        assert!(!is_isolated_parameter(
+            "actor-isolated property 'shared' can not be referenced from a non-isolated context"
+        ));
+        assert!(!is_isolated_parameter(
+            "passing non-isolated parameter 'handler' to main actor-isolated method"
+        ));
+        assert!(!is_isolated_parameter(
+            "actor-isolated parameter 'store' cannot be captured"
+        ));
+
+        // Sendable problems with such parameters keep their own category
+        let capture = "capture of non-sendable type 'Handler' from non-isolated parameter 'handler' in a `@Sendable` closure";
+        assert!(!is_isolated_parameter(capture));
+        assert_eq!(
+            categorize_warning(capture).0,
+            WarningType::SendableConformance
+        );
+    }
+
+    #[test]
+    fn test_unsafe_escape_hatch_patterns() {
+        let messages = [
+            "'nonisolated(unsafe)' is unnecessary for a constant with 'Sendable' type 'Int', consider removing it",
+            "'nonisolated(unsafe)' has no effect on property 'cache', consider using 'nonisolated'",
+            "class 'ImageCache' must be 'final' to conform to '@unchecked Sendable'",
+            "conformance of 'Box' to protocol 'Sendable' is already unchecked Sendable",
+        ];
+
+        for message in messages {
+            let (warning_type, severity, _) = categorize_warning(message);
+            assert_eq!(warning_type, WarningType::UnsafeEscapeHatch, "{message}");
+            assert_eq!(severity, Severity::Medium);
+            assert!(!should_report(
+                warning_type,
+                HIGH_CONFIDENCE,
+                &DetectionOptions::default()
+            ));
+            assert!(should_report(
+                warning_type,
+                HIGH_CONFIDENCE,
+                &DetectionOptions {
+                    audit_unsafe: true,
+                    ..DetectionOptions::default()
+                }
+            ));
+        }
+
+        let (warning_type, _, _) =
+            categorize_warning("Type 'MyClass' does not conform to the 'Sendable' protocol");
+        assert_eq!(warning_type, WarningType::SendableConformance);
+    }
+
+    #[test]
+    fn test_sendable_closure_capture_patterns() {
+        let messages = [
+            "capture of 'self' with non-sendable type 'Foo' in a `@Sendable` closure",
+            "Capture of 'item' with non-sendable type 'Item?' in a @Sendable closure",
+        ];
+
+        for message in messages {
+            let (warning_type, _, _) = categorize_warning(message);
+            assert_eq!(warning_type, WarningType::SendableConformance);
+            assert!(is_sendable_closure_capture(message), "{message}");
+        }
+
+        assert!(!is_sendable_closure_capture(
+            "Type 'MyClass' does not conform to the 'Sendable' protocol"
+        ));
+    }
+
+    #[test]
+    fn test_confidence_scores() {
+        let (_, _, confidence) = categorize_warning("data race detected in shared state");
+        assert_eq!(confidence, HIGH_CONFIDENCE);
+
+        let borderline = [
+            "reference to captured var 'count' in concurrently-executing code",
+            "'self' may be accessed from an async context",
+            "global variable 'cache' is not isolated",
+        ];
+        for message in borderline {
+            let (warning_type, severity, confidence) = categorize_warning(message);
+            assert_eq!(warning_type, WarningType::Unknown, "{message}");
+            assert_eq!(severity, Severity::Low);
+            assert_eq!(confidence, LOW_CONFIDENCE);
+            assert!(!should_report(
+                warning_type,
+                confidence,
+                &DetectionOptions::default()
+            ));
+            assert!(should_report(
+                warning_type,
+                confidence,
+                &DetectionOptions {
+                    include_low_confidence: true,
+                    ..DetectionOptions::default()
+                }
+            ));
+        }
+
+        let (warning_type, _, confidence) = categorize_warning("variable 'unused' was never used");
+        assert_eq!(confidence, 0.0);
+        assert!(!should_report(
+            warning_type,
+            confidence,
+            &DetectionOptions {
+                include_low_confidence: true,
+                ..DetectionOptions::default()
+            }
+        ));
+    }
+
+    #[test]
+    fn test_deprecated_concurrency_api_patterns() {
+        let messages = [
+            "'withUnsafeContinuation' is deprecated: use withCheckedContinuation",
+            "'DispatchQueue.main.async' usage in async context",
+            "instance method 'wait' is unavailable from asynchronous contexts; Await a Task handle instead",
+            "'detach(priority:operation:)' is deprecated: use Task.detached",
+        ];
+
+        for message in messages {
+            let (warning_type, severity, _) = categorize_warning(message);
+            assert_eq!(
+                warning_type,
+                WarningType::DeprecatedConcurrencyApi,
+                "{message}"
+            );
+            assert_eq!(severity, Severity::Medium);
+            assert!(!should_report(
+                warning_type,
+                1.0,
+                &DetectionOptions::default()
+            ));
+        }
+
+        let (warning_type, _, _) = categorize_warning("function 'deprecated()' is deprecated");
+        assert_eq!(warning_type, WarningType::Unknown);
+    }
+
     #[test]
     fn test_data_race_patterns() {
         let messages = vec![
@@ -112,9 +710,48 @@ mod tests {
         ];
 
         for message in messages {
-            let (warning_type, severity) = categorize_warning(message);
+            let (warning_type, severity, _) = categorize_warning(message);
             assert_eq!(warning_type, WarningType::DataRace);
             assert_eq!(severity, Severity::Critical);
         }
     }
+
+    #[test]
+    fn test_trim_escalation() {
+        let (message, swift6) = trim_escalation(
+            "main actor-isolated property 'count' can not be mutated from a Sendable closure; this is an error in the Swift 6 language mode",
+        );
+        assert_eq!(
+            message,
+            "main actor-isolated property 'count' can not be mutated from a Sendable closure"
+        );
+        assert!(swift6);
+
+        let (message, swift6) = trim_escalation(
+            "capture of 'self' with non-sendable type 'Model' in a `@Sendable` closure; this will be an error in a future Swift language mode",
+        );
+        assert_eq!(
+            message,
+            "capture of 'self' with non-sendable type 'Model' in a `@Sendable` closure"
+        );
+        assert!(!swift6);
+
+        let untouched =
+            "actor-isolated property 'data' can not be referenced from a non-isolated context";
+        assert_eq!(trim_escalation(untouched), (untouched.to_string(), false));
+    }
+
+    #[test]
+    fn test_builtin_fix() {
+        let message = "capture of 'self' with non-sendable type 'Model' in a `@Sendable` closure";
+        let (warning_type, _, _) = categorize_warning(message);
+        assert!(builtin_fix(warning_type, message)
+            .unwrap()
+            .starts_with("Capture a Sendable copy"));
+        assert!(builtin_fix(WarningType::DataRace, "data race detected").is_some());
+        assert_eq!(
+            builtin_fix(WarningType::Unknown, "unused variable 'x'"),
+            None
+        );
+    }
 }
@@ -1,9 +1,12 @@
 use crate::error::Result;
 use crate::models::{CodeContext, Warning};
-use crate::parser::patterns::categorize_warning;
+use crate::parser::pattern_pack::PatternPack;
+use crate::parser::patterns::{builtin_fix, should_report, DetectionOptions};
+use crate::parser::source_map::SourceMap;
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::io::BufRead;
+use std::ops::ControlFlow;
 use std::path::PathBuf;
 
 lazy_static! {
@@ -14,65 +17,231 @@ lazy_static! {
     static ref WARNING_PATTERN: Regex = Regex::new(
         r"^(?P<file_path>[^:]+\.swift):(?P<line>\d+):(?P<column>\d+):\s*warning:\s*(?P<message>.+)$"
     ).unwrap();
+
+    // Warnings as reformatted by xcpretty under fastlane `scan`/`gym`, which
+    // drop the `warning:` keyword after a ⚠️ marker, e.g.
+    // ⚠️  /path/to/File.swift:12:5: capture of 'self' with non-sendable type
+    static ref MARKED_WARNING_PATTERN: Regex = Regex::new(
+        r"^(?P<file_path>[^:]+\.swift):(?P<line>\d+):(?P<column>\d+):\s*(?P<message>.+)$"
+    ).unwrap();
+
+    // Status markers and `[HH:MM:SS]: ` timestamps fastlane prints before log lines
+    static ref FASTLANE_PREFIX: Regex = Regex::new(
+        r"^(?:(?:\[\d{2}:\d{2}:\d{2}\]:|▸|⚠️|⚠|❌|✅|›)\s*)+"
+    ).unwrap();
+
+    // Any diagnostic line (warning, error, note) for any file type
+    static ref DIAGNOSTIC_PATTERN: Regex = Regex::new(
+        r"^[^:]+:\d+:\d+:\s*(warning|error|note):"
+    ).unwrap();
+
+    // Caret/underline marker printed under an echoed source line
+    static ref CARET_LINE: Regex = Regex::new(r"^\s*[\^~]+\s*$").unwrap();
+
+    // Quoted identifiers/types inside diagnostic prose
+    static ref QUOTED: Regex = Regex::new(r#"'[^']*'|"[^"]*""#).unwrap();
+
+    // Calls and member access, e.g. `await store.load()`; prose keeps these in quotes
+    static ref CODE_SYNTAX: Regex = Regex::new(r"[()]|[A-Za-z_]\.[A-Za-z_]").unwrap();
+}
+
+/// Split fastlane status markers and timestamps off the start of `line`,
+/// returning `(prefix, rest)`
+pub(crate) fn split_fastlane_prefix(line: &str) -> (&str, &str) {
+    let end = FASTLANE_PREFIX.find(line).map_or(0, |m| m.end());
+    line.split_at(end)
+}
+
+/// A matched warning line whose message may still grow with continuation lines
+struct PendingWarning {
+    file_path: String,
+    line_number: usize,
+    column_number: usize,
+    message: String,
+    continuation: Vec<String>,
+    raw: Option<String>,
 }
 
 pub struct RawLogParser {
     context_lines: usize,
+    detection: DetectionOptions,
+    source_map: SourceMap,
+    patterns: PatternPack,
 }
 
 impl RawLogParser {
     pub fn new(context_lines: usize) -> Self {
-        Self { context_lines }
+        Self {
+            context_lines,
+            detection: DetectionOptions::default(),
+            source_map: SourceMap::default(),
+            patterns: PatternPack::default(),
+        }
+    }
+
+    pub fn with_detection(mut self, detection: DetectionOptions) -> Self {
+        self.detection = detection;
+        self
+    }
+
+    /// Read code context through `source_map` instead of the reported paths
+    pub fn with_source_map(mut self, source_map: SourceMap) -> Self {
+        self.source_map = source_map;
+        self
+    }
+
+    /// Try `patterns` before the built-in ones when categorizing messages
+    pub fn with_patterns(mut self, patterns: PatternPack) -> Self {
+        self.patterns = patterns;
+        self
     }
 
     /// Parse warnings from raw xcodebuild log text
     pub fn parse_stream<R: BufRead>(&self, reader: R) -> Result<Vec<Warning>> {
         let mut warnings = Vec::new();
+        self.for_each_warning(reader, |warning| {
+            warnings.push(warning);
+            ControlFlow::Continue(())
+        })?;
+        Ok(warnings)
+    }
+
+    /// Parse warnings from raw xcodebuild log text, passing each to `f` as soon
+    /// as it is complete. Stops reading once `f` breaks.
+    pub fn for_each_warning<R, F>(&self, reader: R, mut f: F) -> Result<()>
+    where
+        R: BufRead,
+        F: FnMut(Warning) -> ControlFlow<()>,
+    {
+        let mut current: Option<PendingWarning> = None;
+        let mut emit =
+            |pending: Option<PendingWarning>| match pending.and_then(|p| self.build_warning(p)) {
+                Some(warning) => f(warning),
+                None => ControlFlow::Continue(()),
+            };
 
         for line_result in reader.lines() {
             let line = line_result?;
-            if let Some(warning) = self.parse_warning_line(&line) {
-                warnings.push(warning);
+
+            if let Some(next) = self.match_warning_line(&line) {
+                if emit(current.replace(next)).is_break() {
+                    return Ok(());
+                }
+                continue;
+            }
+
+            if let Some(pending) = current.as_mut() {
+                if CARET_LINE.is_match(&line) {
+                    // The previous line was an echo of the source, not message text
+                    pending.continuation.pop();
+                    if emit(current.take()).is_break() {
+                        return Ok(());
+                    }
+                    continue;
+                }
+
+                if Self::is_continuation_line(&line) {
+                    pending.continuation.push(line.trim().to_string());
+                    continue;
+                }
+
+                if emit(current.take()).is_break() {
+                    return Ok(());
+                }
             }
         }
 
-        Ok(warnings)
+        let _ = emit(current.take());
+        Ok(())
     }
 
-    /// Parse a single line for Swift compiler warnings
-    fn parse_warning_line(&self, line: &str) -> Option<Warning> {
-        if let Some(captures) = WARNING_PATTERN.captures(line.trim()) {
-            let file_path = captures.name("file_path")?.as_str();
-            let line_number: usize = captures.name("line")?.as_str().parse().ok()?;
-            let column_number: usize = captures.name("column")?.as_str().parse().ok()?;
-            let message = captures.name("message")?.as_str().trim();
-
-            // Only process Swift concurrency warnings
-            let (warning_type, severity) = categorize_warning(message);
-            if warning_type == crate::models::WarningType::Unknown {
-                return None;
-            }
+    /// Indented prose that wraps the previous warning's message. Echoed source
+    /// lines are excluded by rejecting anything that looks like Swift code.
+    fn is_continuation_line(line: &str) -> bool {
+        if line.trim().is_empty() || !line.starts_with(char::is_whitespace) {
+            return false;
+        }
+        if DIAGNOSTIC_PATTERN.is_match(line.trim()) {
+            return false;
+        }
 
-            // Generate stable warning ID
-            let id = format!("{}:{}:{}", file_path, line_number, message.len());
-
-            // Extract code context from file
-            let code_context = self.extract_code_context(file_path, line_number);
-
-            Some(Warning {
-                id,
-                warning_type,
-                severity,
-                file_path: PathBuf::from(file_path),
-                line_number,
-                column_number: Some(column_number),
-                message: message.to_string(),
-                code_context,
-                suggested_fix: self.suggest_fix(&warning_type, message),
-            })
-        } else {
-            None
+        let unquoted = QUOTED.replace_all(line, "");
+        !["=", "{", "}", ";", "//"]
+            .iter()
+            .any(|token| unquoted.contains(token))
+            && !CODE_SYNTAX.is_match(&unquoted)
+    }
+
+    /// Match a single line against the Swift compiler warning format, after
+    /// stripping any fastlane status markers
+    fn match_warning_line(&self, line: &str) -> Option<PendingWarning> {
+        let raw = self.detection.keep_raw.then(|| line.to_string());
+        let (prefix, line) = split_fastlane_prefix(line.trim());
+        let captures = WARNING_PATTERN.captures(line).or_else(|| {
+            // Errors keep their keyword, so a bare location after ⚠️ is a warning
+            prefix
+                .contains('⚠')
+                .then(|| MARKED_WARNING_PATTERN.captures(line))
+                .flatten()
+        })?;
+        Some(PendingWarning {
+            file_path: captures.name("file_path")?.as_str().to_string(),
+            line_number: captures.name("line")?.as_str().parse().ok()?,
+            column_number: captures.name("column")?.as_str().parse().ok()?,
+            message: captures.name("message")?.as_str().trim().to_string(),
+            continuation: Vec::new(),
+            raw,
+        })
+    }
+
+    /// Build a warning once its full (possibly wrapped) message is known
+    fn build_warning(&self, pending: PendingWarning) -> Option<Warning> {
+        let PendingWarning {
+            file_path,
+            line_number,
+            column_number,
+            mut message,
+            continuation,
+            raw,
+        } = pending;
+
+        for part in continuation {
+            message.push(' ');
+            message.push_str(&part);
         }
+        let (message, error_in_swift6) = self.detection.normalize_message(&message);
+
+        // Only process Swift concurrency warnings
+        let (warning_type, severity, confidence) = self.patterns.categorize(&message);
+        if !should_report(warning_type, confidence, &self.detection) {
+            return None;
+        }
+
+        // Generate stable warning ID
+        let id = format!("{}:{}:{}", file_path, line_number, message.len());
+
+        // Extract code context from file
+        let code_context =
+            crate::profile::time_context(|| self.extract_code_context(&file_path, line_number));
+        let code_context = self.detection.finish_context(code_context);
+        let suggested_fix = builtin_fix(warning_type, &message);
+
+        Some(Warning {
+            id,
+            warning_type,
+            severity,
+            file_path: PathBuf::from(file_path),
+            line_number,
+            column_number: Some(column_number),
+            message,
+            code_context,
+            suggested_fix,
+            error_in_swift6,
+            test_code: false,
+            labels: Default::default(),
+            effort: None,
+            raw,
+        })
     }
 
     /// Extract code context around the warning line
@@ -86,7 +255,7 @@ impl RawLogParser {
             after: Vec::new(),
         };
 
-        if let Ok(file) = File::open(file_path) {
+        if let Ok(file) = File::open(self.source_map.resolve(file_path)) {
             let reader = BufReader::new(file);
             let lines: Vec<String> = reader
                 .lines()
@@ -111,45 +280,6 @@ impl RawLogParser {
 
         context
     }
-
-    /// Suggest fixes for different warning types
-    fn suggest_fix(
-        &self,
-        warning_type: &crate::models::WarningType,
-        message: &str,
-    ) -> Option<String> {
-        use crate::models::WarningType;
-
-        match warning_type {
-            WarningType::ActorIsolation => {
-                if message.contains("can not be mutated") || message.contains("cannot be mutated") {
-                    Some("Consider using 'await' or @MainActor to safely mutate the actor-isolated property.".to_string())
-                } else if message.contains("can not be referenced") || message.contains("cannot be referenced") {
-                    Some("Use 'await' to access the actor-isolated member, or move this code into an actor context.".to_string())
-                } else if message.contains("Main actor") {
-                    Some("Use '@MainActor' annotation or dispatch to the main queue with 'await MainActor.run'.".to_string())
-                } else {
-                    Some("Ensure proper actor isolation by using 'await' or moving code to appropriate actor context.".to_string())
-                }
-            }
-            WarningType::SendableConformance => {
-                if message.contains("does not conform") {
-                    Some("Add 'Sendable' conformance to the type or use '@unchecked Sendable' if thread-safe.".to_string())
-                } else if message.contains("capture") {
-                    Some("Ensure captured values conform to 'Sendable' or restructure to avoid capture.".to_string())
-                } else {
-                    Some("Review Sendable conformance requirements for concurrent contexts.".to_string())
-                }
-            }
-            WarningType::DataRace => {
-                Some("Protect shared mutable state with proper synchronization (actors, locks, or atomic operations).".to_string())
-            }
-            WarningType::PerformanceRegression => {
-                Some("Review async/await usage patterns and consider optimizing concurrency structure.".to_string())
-            }
-            WarningType::Unknown => None,
-        }
-    }
 }
 
 #[cfg(test)]
@@ -194,7 +324,7 @@ mod tests {
         let warning = &warnings[0];
 
         assert_eq!(warning.warning_type, WarningType::SendableConformance);
-        assert_eq!(warning.severity, Severity::High);
+        assert_eq!(warning.severity, Severity::Medium);
         assert_eq!(warning.line_number, 78);
         assert_eq!(warning.column_number, Some(15));
         assert!(warning
@@ -263,6 +393,97 @@ mod tests {
         assert_eq!(warnings[0].warning_type, WarningType::ActorIsolation);
     }
 
+    #[test]
+    fn test_low_confidence_warnings_opt_in() {
+        let log_content = r#"
+/test/File.swift:12:3: warning: reference to captured var 'count' in concurrently-executing code
+/test/File.swift:25:10: warning: variable 'unused' was never used; consider replacing with '_' or removing it
+        "#
+        .trim();
+
+        let default_parser = RawLogParser::new(0);
+        let warnings = default_parser
+            .parse_stream(Cursor::new(log_content))
+            .unwrap();
+        assert!(warnings.is_empty());
+
+        let parser = RawLogParser::new(0).with_detection(DetectionOptions {
+            include_low_confidence: true,
+            ..DetectionOptions::default()
+        });
+        let warnings = parser.parse_stream(Cursor::new(log_content)).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].line_number, 12);
+        assert_eq!(warnings[0].warning_type, WarningType::Unknown);
+        assert_eq!(warnings[0].severity, Severity::Low);
+    }
+
+    #[test]
+    fn test_wrapped_message_is_reconstructed() {
+        let log_content = r#"
+/project/Model.swift:14:9: warning: passing argument of non-sendable type 'Model' outside of main
+      actor-isolated context may introduce data races
+        model.refresh()
+        ^
+/project/Model.swift:20:3: note: consider making 'Model' conform to 'Sendable'
+Build completed
+        "#
+        .trim();
+
+        let parser = RawLogParser::new(0);
+        let warnings = parser.parse_stream(Cursor::new(log_content)).unwrap();
+
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(
+            warnings[0].message,
+            "passing argument of non-sendable type 'Model' outside of main actor-isolated context may introduce data races"
+        );
+        assert_eq!(
+            warnings[0].id,
+            format!("/project/Model.swift:14:{}", warnings[0].message.len())
+        );
+    }
+
+    #[test]
+    fn test_echoed_source_is_not_continuation() {
+        let log_content = r#"
+/project/Item.swift:37:24: warning: main actor-isolated property 'count' can not be mutated from a Sendable closure
+            self.model.count += 1          // warning in "targeted"
+/project/Item.swift:22:9: note: mutation of this property is only permitted within the actor
+        "#
+        .trim();
+
+        let parser = RawLogParser::new(0);
+        let warnings = parser.parse_stream(Cursor::new(log_content)).unwrap();
+
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(
+            warnings[0].message,
+            "main actor-isolated property 'count' can not be mutated from a Sendable closure"
+        );
+    }
+
+    #[test]
+    fn test_echoed_call_without_caret_is_not_continuation() {
+        let log_content = r#"
+/project/Store.swift:12:15: warning: actor-isolated instance method 'load()' can not be referenced from a non-isolated context
+        await store.load()
+Build completed
+        "#
+        .trim();
+
+        let parser = RawLogParser::new(0);
+        let warnings = parser.parse_stream(Cursor::new(log_content)).unwrap();
+
+        assert_eq!(warnings.len(), 1);
+        let message = "actor-isolated instance method 'load()' can not be referenced from a non-isolated context";
+        assert_eq!(warnings[0].message, message);
+        assert_eq!(
+            warnings[0].id,
+            format!("/project/Store.swift:12:{}", message.len())
+        );
+    }
+
     #[test]
     fn test_multiple_warnings() {
         let log_content = r#"
@@ -368,6 +589,93 @@ File.swift: some incomplete line
         assert!(warning.code_context.after.is_empty());
     }
 
+    #[test]
+    fn test_normalize_context_across_line_endings() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = ["actor Store {", "    var count = 0  ", "}\t"];
+        let lf = dir.path().join("LF.swift");
+        let crlf = dir.path().join("CRLF.swift");
+        std::fs::write(&lf, source.join("\n")).unwrap();
+        std::fs::write(&crlf, source.join("\r\n").replace("0  ", "0 \r ")).unwrap();
+
+        let context = |path: &std::path::Path, normalize: bool| {
+            let log = format!(
+                "{}:2:9: warning: actor-isolated property 'count' can not be referenced",
+                path.display()
+            );
+            let parser = RawLogParser::new(1).with_detection(DetectionOptions {
+                normalize_context: normalize,
+                ..DetectionOptions::default()
+            });
+            let warnings = parser.parse_stream(Cursor::new(log)).unwrap();
+            warnings[0].code_context.clone()
+        };
+
+        let normalized = context(&lf, true);
+        assert_eq!(normalized.before, ["actor Store {"]);
+        assert_eq!(normalized.line, "    var count = 0");
+        assert_eq!(normalized.after, ["}"]);
+
+        let from_crlf = context(&crlf, true);
+        assert_eq!(from_crlf.before, normalized.before);
+        assert_eq!(from_crlf.line, normalized.line);
+        assert_eq!(from_crlf.after, normalized.after);
+
+        // Raw context is kept as-is without the flag
+        assert_eq!(context(&lf, false).line, "    var count = 0  ");
+        assert_eq!(context(&crlf, false).line, "    var count = 0 \r ");
+    }
+
+    #[test]
+    fn test_for_each_warning_stops_early() {
+        let mut log = b"/src/A.swift:1:1: warning: actor-isolated property 'a' can not be mutated from a non-isolated context\n\
+/src/B.swift:2:1: warning: data race detected in shared state\n"
+            .to_vec();
+        // A malformed region that fails line decoding if it is ever read
+        log.extend_from_slice(b"\xff\xfe garbage\n");
+        let parser = RawLogParser::new(0);
+
+        assert!(parser.parse_stream(Cursor::new(&log)).is_err());
+
+        let mut seen = Vec::new();
+        parser
+            .for_each_warning(Cursor::new(&log), |warning| {
+                seen.push(warning);
+                ControlFlow::Break(())
+            })
+            .unwrap();
+        assert_eq!(seen.len(), 1);
+        assert_eq!(seen[0].file_path, PathBuf::from("/src/A.swift"));
+    }
+
+    #[test]
+    fn test_path_with_spaces() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("My Project");
+        std::fs::create_dir(&project).unwrap();
+        let file = project.join("Shared State.swift");
+        std::fs::write(
+            &file,
+            "actor Store {}\nlet shared = Store()\nfunc read() {}\n",
+        )
+        .unwrap();
+
+        let log_content = format!(
+            "{}:2:5: warning: actor-isolated property 'shared' can not be referenced from a non-isolated context",
+            file.display()
+        );
+        let parser = RawLogParser::new(1);
+        let warnings = parser.parse_stream(Cursor::new(log_content)).unwrap();
+
+        assert_eq!(warnings.len(), 1);
+        let warning = &warnings[0];
+        assert_eq!(warning.file_path, file);
+        assert_eq!(warning.line_number, 2);
+        assert_eq!(warning.column_number, Some(5));
+        assert_eq!(warning.code_context.line, "let shared = Store()");
+        assert_eq!(warning.code_context.before, ["actor Store {}"]);
+    }
+
     #[test]
     fn test_suggested_fixes() {
         let test_cases = vec![
@@ -383,6 +691,34 @@ File.swift: some incomplete line
                 "/test/File.swift:55:12: warning: Type 'MyClass' does not conform to the 'Sendable' protocol",
                 "Add 'Sendable' conformance to the type or use '@unchecked Sendable' if thread-safe."
             ),
+            (
+                "/test/File.swift:61:17: warning: capture of 'self' with non-sendable type 'Foo' in a `@Sendable` closure",
+                "Capture a Sendable copy of the value in the closure's capture list"
+            ),
+            (
+                "/test/File.swift:62:14: warning: value of non-Sendable type 'Cache' crosses actor boundary",
+                "mark the parameter 'sending'"
+            ),
+            (
+                "/test/File.swift:64:9: warning: call to main actor-isolated instance method 'stopTimer()' from a nonisolated deinit",
+                "move cleanup off the main actor or capture the state it needs before deinit"
+            ),
+            (
+                "/test/File.swift:65:5: warning: isolated deinit is only available in macOS 15.4.0 or newer",
+                "'isolated deinit' (SE-0371) needs a Swift 6 compiler"
+            ),
+            (
+                "/test/File.swift:65:20: warning: main actor-isolated property 'title' accessed without await inside assumeIsolated",
+                "from async code use 'await MainActor.run'"
+            ),
+            (
+                "/test/File.swift:66:10: warning: main actor-isolated instance method 'update()' cannot be used to satisfy nonisolated protocol requirement",
+                "Match the protocol requirement's isolation"
+            ),
+            (
+                "/test/File.swift:68:31: warning: isolated parameter 'actor' is unused",
+                "remove the 'isolated' parameter if the function doesn't need it"
+            ),
             (
                 "/test/File.swift:70:20: warning: data race condition detected in concurrent memory access",
                 "Protect shared mutable state with proper synchronization (actors, locks, or atomic operations)."
@@ -0,0 +1,101 @@
+use crate::error::{ParseError, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Maps file paths as reported by the compiler to the files to read code
+/// context from. Generated sources that use `#sourceLocation(file:line:)` make
+/// the compiler report the original template path, which may not exist on disk.
+///
+/// Keys match a reported path exactly, or as a leading directory so one entry
+/// can remap a whole template tree; the longest matching key wins. Warnings
+/// keep their reported path.
+#[derive(Debug, Clone, Default)]
+pub struct SourceMap {
+    entries: Arc<HashMap<PathBuf, PathBuf>>,
+}
+
+impl SourceMap {
+    pub fn new(entries: HashMap<PathBuf, PathBuf>) -> Self {
+        Self {
+            entries: Arc::new(entries),
+        }
+    }
+
+    /// Load a JSON object of `"reported/path": "on/disk/path"` pairs
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let entries: HashMap<PathBuf, PathBuf> = serde_json::from_str(&content).map_err(|e| {
+            ParseError::InvalidFormat(format!("source map {}: {e}", path.display()))
+        })?;
+        Ok(Self::new(entries))
+    }
+
+    /// The on-disk path to read for `reported`, or `reported` itself when unmapped
+    pub fn resolve(&self, reported: impl AsRef<Path>) -> PathBuf {
+        let reported = reported.as_ref();
+        if let Some(mapped) = self.entries.get(reported) {
+            return mapped.clone();
+        }
+
+        self.entries
+            .iter()
+            .filter_map(|(from, to)| {
+                let rest = reported.strip_prefix(from).ok()?;
+                Some((from.components().count(), to.join(rest)))
+            })
+            .max_by_key(|(depth, _)| *depth)
+            .map_or_else(|| reported.to_path_buf(), |(_, mapped)| mapped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source_map(entries: &[(&str, &str)]) -> SourceMap {
+        SourceMap::new(
+            entries
+                .iter()
+                .map(|(from, to)| (PathBuf::from(from), PathBuf::from(to)))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_resolve_exact_and_directory_entries() {
+        let map = source_map(&[
+            ("Templates/Model.swift.stencil", "Generated/Model.swift"),
+            ("Templates", "Generated"),
+            ("Templates/API", "Generated/Network"),
+        ]);
+
+        assert_eq!(
+            map.resolve("Templates/Model.swift.stencil"),
+            PathBuf::from("Generated/Model.swift")
+        );
+        assert_eq!(
+            map.resolve("Templates/Store.swift"),
+            PathBuf::from("Generated/Store.swift")
+        );
+        assert_eq!(
+            map.resolve("Templates/API/Client.swift"),
+            PathBuf::from("Generated/Network/Client.swift")
+        );
+        // Prefixes only match whole components
+        assert_eq!(
+            map.resolve("TemplatesOld/View.swift"),
+            PathBuf::from("TemplatesOld/View.swift")
+        );
+    }
+
+    #[test]
+    fn test_load_rejects_non_object() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("map.json");
+        std::fs::write(&path, "[\"a\", \"b\"]").unwrap();
+
+        let err = SourceMap::load(&path).unwrap_err();
+        assert!(err.to_string().contains("source map"));
+    }
+}
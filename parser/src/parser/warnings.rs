@@ -1,5 +1,10 @@
-use crate::cli::WarningTypeFilter;
+use crate::cli::{DedupKey, Taxonomy, ThresholdOp, WarningTypeFilter};
+use crate::error::{ParseError, Result};
 use crate::models::{Warning, WarningType};
+use crate::parser::patterns::{categorize_warning, should_report, DetectionOptions};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
 
 pub fn filter_warnings(warnings: Vec<Warning>, filter: Option<WarningTypeFilter>) -> Vec<Warning> {
     match filter {
@@ -9,6 +14,7 @@ pub fn filter_warnings(warnings: Vec<Warning>, filter: Option<WarningTypeFilter>
                 WarningTypeFilter::Sendable => WarningType::SendableConformance,
                 WarningTypeFilter::DataRace => WarningType::DataRace,
                 WarningTypeFilter::Performance => WarningType::PerformanceRegression,
+                WarningTypeFilter::Deprecated => WarningType::DeprecatedConcurrencyApi,
             };
             warnings
                 .into_iter()
@@ -19,9 +25,549 @@ pub fn filter_warnings(warnings: Vec<Warning>, filter: Option<WarningTypeFilter>
     }
 }
 
-pub fn check_threshold(warnings: &[Warning], threshold: Option<usize>) -> bool {
+/// The type `warning_type` is displayed and grouped under with `taxonomy`.
+/// The coarse isolation, sendability and data race buckets reuse the actor
+/// isolation, Sendable conformance and data race types. Only formatters use
+/// this; filters and gates always see the detected type.
+pub fn taxonomy_type(warning_type: WarningType, taxonomy: Taxonomy) -> WarningType {
+    match (taxonomy, warning_type) {
+        (Taxonomy::Fine, _) => warning_type,
+        (Taxonomy::Coarse, WarningType::SendableConformance | WarningType::UnsafeEscapeHatch) => {
+            WarningType::SendableConformance
+        }
+        (Taxonomy::Coarse, WarningType::DataRace) => WarningType::DataRace,
+        (
+            Taxonomy::Coarse,
+            WarningType::ActorIsolation
+            | WarningType::DeprecatedConcurrencyApi
+            | WarningType::PerformanceRegression
+            | WarningType::Unknown,
+        ) => WarningType::ActorIsolation,
+    }
+}
+
+/// Split off the uncategorized warnings that `options` would otherwise have
+/// dropped (parsed with `keep_unknown` set), returning `(kept, uncategorized)`
+pub fn split_uncategorized(
+    warnings: Vec<Warning>,
+    options: &DetectionOptions,
+) -> (Vec<Warning>, Vec<Warning>) {
+    let options = DetectionOptions {
+        keep_unknown: false,
+        ..*options
+    };
+    warnings.into_iter().partition(|w| {
+        w.warning_type != WarningType::Unknown || {
+            let (_, _, confidence) = categorize_warning(&w.message);
+            should_report(w.warning_type, confidence, &options)
+        }
+    })
+}
+
+pub fn check_threshold(warnings: &[Warning], threshold: Option<usize>, op: ThresholdOp) -> bool {
     match threshold {
-        Some(limit) => warnings.len() <= limit,
+        Some(limit) => op.passes(warnings.len(), limit),
+        None => true,
+    }
+}
+
+// Directories that only contain dependency or build output
+const GENERATED_DIRS: [&str; 4] = ["DerivedData", ".build", "Pods", "Carthage"];
+
+/// Whether `path` looks like a dependency or compiler/codegen-produced file
+pub fn is_generated_path(path: &Path) -> bool {
+    let in_generated_dir = path.components().any(|component| {
+        component
+            .as_os_str()
+            .to_str()
+            .is_some_and(|name| GENERATED_DIRS.contains(&name))
+    });
+
+    let generated_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.ends_with(".generated.swift"));
+
+    in_generated_dir || generated_name
+}
+
+pub fn drop_generated(warnings: Vec<Warning>) -> Vec<Warning> {
+    warnings
+        .into_iter()
+        .filter(|w| !is_generated_path(&w.file_path))
+        .collect()
+}
+
+/// Distinct files with at least one warning, in sorted order
+pub fn affected_files(warnings: &[Warning]) -> Vec<&Path> {
+    let files: BTreeSet<&Path> = warnings.iter().map(|w| w.file_path.as_path()).collect();
+    files.into_iter().collect()
+}
+
+/// Number of lines in the file at `path`, or `None` if it can't be read
+pub fn count_lines(path: &Path) -> Option<usize> {
+    let bytes = std::fs::read(path).ok()?;
+    let newlines = bytes.iter().filter(|b| **b == b'\n').count();
+    // A last line without a trailing newline still counts
+    Some(newlines + usize::from(bytes.last().is_some_and(|b| *b != b'\n')))
+}
+
+pub fn check_affected_files(warnings: &[Warning], max_files: Option<usize>) -> bool {
+    match max_files {
+        Some(limit) => affected_files(warnings).len() <= limit,
         None => true,
     }
 }
+
+/// Globs identifying test sources, whose warnings are down-ranked
+#[derive(Debug, Clone)]
+pub struct TestPaths {
+    globs: GlobSet,
+}
+
+impl TestPaths {
+    pub fn new<S: AsRef<str>>(patterns: &[S]) -> Result<Self> {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            let glob = Glob::new(pattern.as_ref())
+                .map_err(|e| ParseError::InvalidFormat(format!("invalid test path glob: {e}")))?;
+            builder.add(glob);
+        }
+        let globs = builder
+            .build()
+            .map_err(|e| ParseError::InvalidFormat(format!("invalid test path glob: {e}")))?;
+        Ok(Self { globs })
+    }
+
+    pub fn is_match(&self, path: &Path) -> bool {
+        self.globs.is_match(path)
+    }
+}
+
+/// Mark warnings in test sources and lower their severity by one level
+pub fn downrank_tests(mut warnings: Vec<Warning>, test_paths: &TestPaths) -> Vec<Warning> {
+    for warning in &mut warnings {
+        if test_paths.is_match(&warning.file_path) {
+            warning.test_code = true;
+            warning.severity = warning.severity.downgrade();
+        }
+    }
+    warnings
+}
+
+/// Raise the severity of every warning in a file that has more than `limit`
+/// warnings of the same type, so hotspots outrank scattered one-offs
+pub fn escalate_hotspots(mut warnings: Vec<Warning>, limit: usize) -> Vec<Warning> {
+    let mut counts: HashMap<(&Path, WarningType), usize> = HashMap::new();
+    for warning in &warnings {
+        *counts
+            .entry((warning.file_path.as_path(), warning.warning_type))
+            .or_default() += 1;
+    }
+    let hot: Vec<bool> = warnings
+        .iter()
+        .map(|w| counts[&(w.file_path.as_path(), w.warning_type)] > limit)
+        .collect();
+
+    for (warning, hot) in warnings.iter_mut().zip(hot) {
+        if hot {
+            warning.severity = warning.severity.escalate();
+        }
+    }
+    warnings
+}
+
+// Stable across runs and platforms for the same salt (FNV-1a), unlike the std
+// hasher. The salt keeps common names from being looked up in a dictionary.
+fn path_token(salt: &str, name: &str) -> String {
+    let hash = salt
+        .bytes()
+        .chain([0])
+        .chain(name.bytes())
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+    format!("{:08x}", hash as u32)
+}
+
+/// A salt for one run's `--anonymize-paths` tokens when none is configured
+pub fn random_salt() -> String {
+    use std::hash::BuildHasher;
+    let state = std::collections::hash_map::RandomState::new();
+    format!("{:016x}", state.hash_one(std::process::id()))
+}
+
+/// `path` with every directory and file name replaced by a token hashed with
+/// `salt`. The depth, the root and the file extension are kept.
+pub fn anonymize_path(path: &Path, salt: &str) -> PathBuf {
+    let last = path.components().count().saturating_sub(1);
+    path.components()
+        .enumerate()
+        .map(|(i, component)| match component {
+            Component::Normal(name) => {
+                let name = name.to_string_lossy();
+                match name
+                    .rsplit_once('.')
+                    .filter(|(stem, _)| i == last && !stem.is_empty())
+                {
+                    Some((stem, extension)) => {
+                        format!("{}.{extension}", path_token(salt, stem))
+                    }
+                    None => path_token(salt, &name),
+                }
+            }
+            other => other.as_os_str().to_string_lossy().into_owned(),
+        })
+        .collect()
+}
+
+/// Replace file paths, including where ids embed them, with anonymized ones so
+/// reports can be shared without revealing the directory structure
+pub fn anonymize_paths(mut warnings: Vec<Warning>, salt: &str) -> Vec<Warning> {
+    for warning in &mut warnings {
+        let anonymized = anonymize_path(&warning.file_path, salt);
+        warning.id = warning.id.replace(
+            &*warning.file_path.to_string_lossy(),
+            &anonymized.to_string_lossy(),
+        );
+        warning.file_path = anonymized;
+    }
+    warnings
+}
+
+// Whitespace-collapsed message text, so re-wrapped diagnostics compare equal
+fn normalized_message(message: &str) -> String {
+    message.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Drop later warnings whose `key` matches an earlier one, keeping input order
+pub fn dedup_warnings(warnings: Vec<Warning>, key: DedupKey) -> Vec<Warning> {
+    let mut seen = HashSet::new();
+    warnings
+        .into_iter()
+        .filter(|w| {
+            let dedup_key = match key {
+                DedupKey::Id => w.id.clone(),
+                DedupKey::Message => normalized_message(&w.message),
+                DedupKey::FileMessage => format!(
+                    "{}\0{}",
+                    w.file_path.display(),
+                    normalized_message(&w.message)
+                ),
+            };
+            seen.insert(dedup_key)
+        })
+        .collect()
+}
+
+/// Collapse warnings with the same file and normalized message whose lines are
+/// within `window` of a warning already kept, e.g. re-emitted by an incremental
+/// build after a whitespace-only edit. A window of 0 only collapses exact lines.
+pub fn dedup_window(warnings: Vec<Warning>, window: usize) -> Vec<Warning> {
+    let mut kept_lines: HashMap<(String, String), Vec<usize>> = HashMap::new();
+    warnings
+        .into_iter()
+        .filter(|w| {
+            let key = (
+                w.file_path.display().to_string(),
+                normalized_message(&w.message),
+            );
+            let lines = kept_lines.entry(key).or_default();
+            if lines.iter().any(|&l| l.abs_diff(w.line_number) <= window) {
+                return false;
+            }
+            lines.push(w.line_number);
+            true
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Severity;
+    use std::path::PathBuf;
+
+    fn warning(file: &str, line: usize) -> Warning {
+        Warning::fixture(file, line)
+    }
+
+    #[test]
+    fn test_drop_generated_paths() {
+        let generated = [
+            "/Users/me/Library/Developer/Xcode/DerivedData/App-abc/Build/Intermediates/File.swift",
+            "/project/.build/checkouts/swift-nio/Sources/NIO/Channel.swift",
+            "/project/Pods/Alamofire/Source/Session.swift",
+            "/project/Carthage/Checkouts/Lib/Sources/Lib.swift",
+            "/project/Sources/App/Assets.generated.swift",
+        ];
+        let sources = [
+            "/project/Sources/App/ContentView.swift",
+            "/project/Sources/Build/Builder.swift",
+            "/project/Sources/App/generated.swift",
+        ];
+
+        let warnings: Vec<Warning> = generated
+            .iter()
+            .chain(sources.iter())
+            .map(|path| warning(path, 1))
+            .collect();
+
+        for path in generated {
+            assert!(is_generated_path(Path::new(path)), "{path}");
+        }
+
+        let kept: Vec<String> = drop_generated(warnings)
+            .iter()
+            .map(|w| w.file_path.display().to_string())
+            .collect();
+        assert_eq!(kept, sources);
+    }
+
+    #[test]
+    fn test_affected_files_concentrated() {
+        let warnings: Vec<Warning> = (1..=5).map(|line| warning("/src/A.swift", line)).collect();
+
+        assert_eq!(affected_files(&warnings), vec![Path::new("/src/A.swift")]);
+        assert!(check_affected_files(&warnings, Some(1)));
+    }
+
+    #[test]
+    fn test_affected_files_spread() {
+        let warnings = vec![
+            warning("/src/C.swift", 1),
+            warning("/src/A.swift", 2),
+            warning("/src/B.swift", 3),
+            warning("/src/A.swift", 4),
+        ];
+
+        assert_eq!(
+            affected_files(&warnings),
+            vec![
+                Path::new("/src/A.swift"),
+                Path::new("/src/B.swift"),
+                Path::new("/src/C.swift")
+            ]
+        );
+        assert!(!check_affected_files(&warnings, Some(2)));
+        assert!(check_affected_files(&warnings, Some(3)));
+        assert!(check_affected_files(&warnings, None));
+    }
+
+    #[test]
+    fn test_dedup_by_message_ignores_path() {
+        let message = "actor-isolated property 'data' can not be referenced";
+        let mut absolute = warning("/project/Sources/DataManager.swift", 42);
+        absolute.message = message.to_string();
+        let mut symlinked = warning(
+            "/Users/me/Library/Developer/Xcode/DerivedData/App-abc/SourcePackages/DataManager.swift",
+            42,
+        );
+        symlinked.message = format!("{message}  ");
+        let mut same_file = warning("/project/Sources/DataManager.swift", 57);
+        same_file.message = message.to_string();
+        let warnings = vec![absolute, symlinked, same_file];
+
+        assert_eq!(dedup_warnings(warnings.clone(), DedupKey::Id).len(), 3);
+        assert_eq!(
+            dedup_warnings(warnings.clone(), DedupKey::FileMessage).len(),
+            2
+        );
+
+        let deduped = dedup_warnings(warnings, DedupKey::Message);
+        assert_eq!(deduped.len(), 1);
+        assert_eq!(
+            deduped[0].file_path,
+            PathBuf::from("/project/Sources/DataManager.swift")
+        );
+    }
+
+    #[test]
+    fn test_dedup_window_collapses_nearby_lines() {
+        let message = "actor-isolated property 'data' can not be referenced";
+        let at = |file: &str, line: usize| {
+            let mut w = warning(file, line);
+            w.message = message.to_string();
+            w
+        };
+        let warnings = vec![
+            at("/project/Store.swift", 42),
+            at("/project/Store.swift", 43),
+            at("/project/Other.swift", 43),
+            at("/project/Store.swift", 42),
+        ];
+
+        let exact = dedup_window(warnings.clone(), 0);
+        let lines: Vec<usize> = exact.iter().map(|w| w.line_number).collect();
+        assert_eq!(lines, [42, 43, 43]);
+
+        let windowed = dedup_window(warnings, 1);
+        assert_eq!(windowed.len(), 2);
+        assert_eq!(windowed[0].line_number, 42);
+        assert_eq!(windowed[1].file_path, PathBuf::from("/project/Other.swift"));
+    }
+
+    #[test]
+    fn test_threshold_operators_at_boundary() {
+        let warnings = vec![
+            warning("/project/A.swift", 1),
+            warning("/project/B.swift", 2),
+        ];
+
+        assert!(check_threshold(&warnings, Some(2), ThresholdOp::Le));
+        assert!(!check_threshold(&warnings, Some(1), ThresholdOp::Le));
+        assert!(!check_threshold(&warnings, Some(2), ThresholdOp::Lt));
+        assert!(check_threshold(&warnings, Some(3), ThresholdOp::Lt));
+
+        // `--threshold 0` allows no warnings under `le`; under `lt` nothing passes
+        assert!(check_threshold(&[], Some(0), ThresholdOp::Le));
+        assert!(!check_threshold(&[], Some(0), ThresholdOp::Lt));
+        assert!(check_threshold(&warnings, None, ThresholdOp::Lt));
+    }
+
+    #[test]
+    fn test_downrank_tests() {
+        let test_paths = TestPaths::new(&["**/*Tests/**", "**/Tests/**"]).unwrap();
+        let mut in_tests = warning("/project/Tests/CacheTests.swift", 10);
+        in_tests.severity = Severity::Critical;
+        let mut in_test_target = warning("/project/AppTests/ModelTests.swift", 4);
+        in_test_target.severity = Severity::Low;
+        let production = warning("/project/Sources/App/Cache.swift", 10);
+
+        let warnings = downrank_tests(vec![in_tests, in_test_target, production], &test_paths);
+
+        assert!(warnings[0].test_code);
+        assert_eq!(warnings[0].severity, Severity::High);
+        assert!(warnings[1].test_code);
+        assert_eq!(warnings[1].severity, Severity::Low);
+        assert!(!warnings[2].test_code);
+        assert_eq!(warnings[2].severity, Severity::High);
+    }
+
+    #[test]
+    fn test_taxonomies_on_same_inputs() {
+        use crate::parser::categorize_warning;
+
+        let messages = [
+            "actor-isolated property 'count' can not be mutated from a non-isolated context",
+            "'nonisolated(unsafe)' is unnecessary for a constant",
+            "'DispatchSemaphore.wait' is unavailable from asynchronous contexts",
+            "Type 'Cache' does not conform to the 'Sendable' protocol",
+            "data race detected in shared state",
+            "potential deadlock in task group",
+        ];
+        let warnings: Vec<Warning> = messages
+            .iter()
+            .enumerate()
+            .map(|(i, message)| {
+                let mut w = warning("/project/File.swift", i + 1);
+                w.warning_type = categorize_warning(message).0;
+                w
+            })
+            .collect();
+        let types = |taxonomy: Taxonomy| -> Vec<WarningType> {
+            warnings
+                .iter()
+                .map(|w| taxonomy_type(w.warning_type, taxonomy))
+                .collect()
+        };
+
+        assert_eq!(
+            types(Taxonomy::Fine),
+            [
+                WarningType::ActorIsolation,
+                WarningType::UnsafeEscapeHatch,
+                WarningType::DeprecatedConcurrencyApi,
+                WarningType::SendableConformance,
+                WarningType::DataRace,
+                WarningType::PerformanceRegression,
+            ]
+        );
+        assert_eq!(
+            types(Taxonomy::Coarse),
+            [
+                WarningType::ActorIsolation,
+                WarningType::SendableConformance,
+                WarningType::ActorIsolation,
+                WarningType::SendableConformance,
+                WarningType::DataRace,
+                WarningType::ActorIsolation,
+            ]
+        );
+        assert_eq!(
+            taxonomy_type(WarningType::Unknown, Taxonomy::Coarse),
+            WarningType::ActorIsolation
+        );
+    }
+
+    #[test]
+    fn test_anonymize_paths_stable_per_file() {
+        let warnings = anonymize_paths(
+            vec![
+                warning("/Users/me/Corp/Sources/Billing/Ledger.swift", 10),
+                warning("/Users/me/Corp/Sources/Billing/Ledger.swift", 42),
+                warning("/Users/me/Corp/Sources/Billing/Invoice.swift", 10),
+            ],
+            "salt",
+        );
+
+        assert_eq!(warnings[0].file_path, warnings[1].file_path);
+        assert_ne!(warnings[0].file_path, warnings[2].file_path);
+
+        let path = warnings[0].file_path.to_string_lossy().into_owned();
+        assert!(!path.contains("Corp") && !path.contains("Ledger"));
+        assert!(path.starts_with('/') && path.ends_with(".swift"));
+        assert_eq!(warnings[0].file_path.components().count(), 7);
+        // Shared directories keep sharing a token
+        assert_eq!(
+            warnings[0].file_path.parent(),
+            warnings[2].file_path.parent()
+        );
+        assert_eq!(warnings[0].id, format!("{path}:10:0"));
+    }
+
+    #[test]
+    fn test_anonymize_path_depends_on_salt() {
+        let path = Path::new("/Users/me/Sources/App.swift");
+        assert_eq!(anonymize_path(path, "a"), anonymize_path(path, "a"));
+        assert_ne!(anonymize_path(path, "a"), anonymize_path(path, "b"));
+        // The unsalted token of a common name can't be precomputed
+        assert_ne!(
+            anonymize_path(Path::new("Sources"), &random_salt()),
+            anonymize_path(Path::new("Sources"), "")
+        );
+    }
+
+    #[test]
+    fn test_escalate_hotspots() {
+        let mut warnings: Vec<Warning> = (1..=3)
+            .map(|line| warning("/project/Sources/Hotspot.swift", line))
+            .collect();
+        warnings.extend((1..=2).map(|line| warning("/project/Sources/AtLimit.swift", line)));
+        let mut other_type = warning("/project/Sources/AtLimit.swift", 9);
+        other_type.warning_type = WarningType::DataRace;
+        warnings.push(other_type);
+        let mut critical = warning("/project/Sources/Hotspot.swift", 4);
+        critical.severity = Severity::Critical;
+        warnings.push(critical);
+
+        let warnings = escalate_hotspots(warnings, 2);
+        let severities: Vec<Severity> = warnings.iter().map(|w| w.severity).collect();
+
+        assert_eq!(
+            severities,
+            [
+                // Above the limit: four actor isolation warnings in one file
+                Severity::Critical,
+                Severity::Critical,
+                Severity::Critical,
+                // At the limit: two of one type plus one of another
+                Severity::High,
+                Severity::High,
+                Severity::High,
+                // Already critical
+                Severity::Critical,
+            ]
+        );
+    }
+}
@@ -1,11 +1,14 @@
 use crate::error::Result;
-use crate::models::{CodeContext, Warning};
-use crate::parser::patterns::categorize_warning;
+use crate::models::{CodeContext, Severity, Warning};
+use crate::parser::pattern_pack::PatternPack;
+use crate::parser::patterns::{builtin_fix, should_report, DetectionOptions};
+use crate::parser::source_map::SourceMap;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::fs::File;
 use std::io::BufRead;
 use std::io::BufReader;
+use std::ops::ControlFlow;
 use std::path::PathBuf;
 
 // XcodeBuild diagnostic structure based on actual xcodebuild JSON output
@@ -40,34 +43,162 @@ pub struct XcodeBuildMessage {
     pub column_number: Option<u64>,
 }
 
+/// Whether `line` can start a JSON array of diagnostics: `[` followed by an
+/// object, the end of the array, or nothing yet on this line
+fn opens_json_array(line: &str) -> bool {
+    line.trim_start()
+        .strip_prefix('[')
+        .is_some_and(|rest| matches!(rest.trim_start().chars().next(), None | Some('{' | ']')))
+}
+
+/// The diagnostic carried by `value`. Newer `xcodebuild -json` streams wrap
+/// diagnostics as `{"kind":"diagnostic","payload":{...}}` among other build
+/// events, which carry no diagnostic; objects without a `kind` are flat
+/// diagnostics already.
+fn unwrap_event(value: &Value) -> Option<&Value> {
+    match (value.get("kind"), value.get("payload")) {
+        (Some(kind), Some(payload)) => (kind == "diagnostic").then_some(payload),
+        _ => Some(value),
+    }
+}
+
+/// The severity xcodebuild set explicitly on a diagnostic, if it is one that
+/// should override categorization. A plain `warning` carries no information
+/// beyond the diagnostic type, so it keeps the categorized severity.
+fn explicit_severity(value: &str) -> Option<Severity> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "error" | "fatal" | "critical" => Some(Severity::Critical),
+        "high" => Some(Severity::High),
+        "medium" => Some(Severity::Medium),
+        "low" | "note" | "remark" => Some(Severity::Low),
+        _ => None,
+    }
+}
+
 pub struct XcodeBuildParser {
     context_lines: usize,
+    detection: DetectionOptions,
+    source_map: SourceMap,
+    patterns: PatternPack,
 }
 
 impl XcodeBuildParser {
     pub fn new(context_lines: usize) -> Self {
-        Self { context_lines }
+        Self {
+            context_lines,
+            detection: DetectionOptions::default(),
+            source_map: SourceMap::default(),
+            patterns: PatternPack::default(),
+        }
+    }
+
+    pub fn with_detection(mut self, detection: DetectionOptions) -> Self {
+        self.detection = detection;
+        self
+    }
+
+    /// Read code context through `source_map` instead of the reported paths
+    pub fn with_source_map(mut self, source_map: SourceMap) -> Self {
+        self.source_map = source_map;
+        self
+    }
+
+    /// Try `patterns` before the built-in ones when categorizing messages
+    pub fn with_patterns(mut self, patterns: PatternPack) -> Self {
+        self.patterns = patterns;
+        self
     }
 
     pub fn parse_stream<R: BufRead>(&self, reader: R) -> Result<Vec<Warning>> {
         let mut warnings = Vec::new();
+        self.for_each_warning(reader, |warning| {
+            warnings.push(warning);
+            ControlFlow::Continue(())
+        })?;
+        Ok(warnings)
+    }
 
-        for line in reader.lines() {
+    /// Parse warnings from xcodebuild JSON output, passing each to `f` as soon
+    /// as it is parsed. Stops reading once `f` breaks.
+    pub fn for_each_warning<R, F>(&self, reader: R, mut f: F) -> Result<()>
+    where
+        R: BufRead,
+        F: FnMut(Warning) -> ControlFlow<()>,
+    {
+        let mut lines = reader.lines();
+        let mut first = true;
+
+        for line in lines.by_ref() {
             let line = line?;
             if line.trim().is_empty() {
                 continue;
             }
 
+            // Some tools dump a single (possibly pretty-printed) JSON array of
+            // diagnostics instead of one object per line. Only input that opens
+            // with one qualifies; `[1/3] Compiling` progress lines don't.
+            if std::mem::take(&mut first) && opens_json_array(&line) {
+                let mut buffer = line;
+                for rest in lines.by_ref() {
+                    buffer.push('\n');
+                    buffer.push_str(&rest?);
+                }
+                let warnings = match self.parse_array(&buffer) {
+                    Some(warnings) => warnings,
+                    // Not an array after all, so read it line by line
+                    None => buffer
+                        .lines()
+                        .filter_map(|line| self.parse_line(line))
+                        .collect(),
+                };
+                for warning in warnings {
+                    if f(warning).is_break() {
+                        break;
+                    }
+                }
+                return Ok(());
+            }
+
             // Try to parse each line as JSON
             if let Some(warning) = self.parse_line(&line) {
-                warnings.push(warning);
+                if f(warning).is_break() {
+                    return Ok(());
+                }
             }
         }
 
-        Ok(warnings)
+        Ok(())
+    }
+
+    fn parse_array(&self, content: &str) -> Option<Vec<Warning>> {
+        match serde_json::from_str::<Value>(content) {
+            Ok(Value::Array(items)) => Some(
+                items
+                    .iter()
+                    .filter_map(|item| {
+                        let warning = self.extract_warning_from_value(unwrap_event(item)?)?;
+                        Some(self.with_raw(warning, || item.to_string()))
+                    })
+                    .collect(),
+            ),
+            _ => None,
+        }
     }
 
     fn parse_line(&self, line: &str) -> Option<Warning> {
+        let warning = self.parse_json_line(line)?;
+        Some(self.with_raw(warning, || line.to_string()))
+    }
+
+    /// Attach the input the warning came from when `keep_raw` is set
+    fn with_raw(&self, mut warning: Warning, raw: impl FnOnce() -> String) -> Warning {
+        if self.detection.keep_raw {
+            warning.raw = Some(raw());
+        }
+        warning
+    }
+
+    fn parse_json_line(&self, line: &str) -> Option<Warning> {
         // Try parsing as XcodeBuildDiagnostic first
         if let Ok(diagnostic) = serde_json::from_str::<XcodeBuildDiagnostic>(line) {
             return self.extract_warning_from_diagnostic(&diagnostic);
@@ -80,7 +211,7 @@ impl XcodeBuildParser {
 
         // Try parsing as generic JSON and extract common fields
         if let Ok(json) = serde_json::from_str::<Value>(line) {
-            return self.extract_warning_from_value(&json);
+            return self.extract_warning_from_value(unwrap_event(&json)?);
         }
 
         None
@@ -95,13 +226,19 @@ impl XcodeBuildParser {
             return None;
         }
 
-        let message = &diagnostic.message;
-        let (warning_type, severity) = categorize_warning(message);
+        let (message, error_in_swift6) = self.detection.normalize_message(&diagnostic.message);
+        let message = &message;
+        let (warning_type, severity, confidence) = self.patterns.categorize(message);
 
         // Only process Swift concurrency warnings
-        if warning_type == crate::models::WarningType::Unknown {
+        if !should_report(warning_type, confidence, &self.detection) {
             return None;
         }
+        let severity = diagnostic
+            .severity
+            .as_deref()
+            .and_then(explicit_severity)
+            .unwrap_or(severity);
 
         let file_path = diagnostic.file.as_deref().unwrap_or("unknown");
         let line_number = diagnostic.line.unwrap_or(0) as usize;
@@ -109,7 +246,9 @@ impl XcodeBuildParser {
 
         let id = format!("{}:{}:{}", file_path, line_number, message.len());
 
-        let code_context = self.extract_code_context(file_path, line_number);
+        let code_context =
+            crate::profile::time_context(|| self.extract_code_context(file_path, line_number));
+        let code_context = self.detection.finish_context(code_context);
 
         Some(Warning {
             id,
@@ -120,7 +259,12 @@ impl XcodeBuildParser {
             column_number,
             message: message.clone(),
             code_context,
-            suggested_fix: self.suggest_fix(&warning_type, message),
+            suggested_fix: builtin_fix(warning_type, message),
+            error_in_swift6,
+            test_code: false,
+            labels: Default::default(),
+            effort: None,
+            raw: None,
         })
     }
 
@@ -129,10 +273,11 @@ impl XcodeBuildParser {
             return None;
         }
 
-        let msg = &message.message;
-        let (warning_type, severity) = categorize_warning(msg);
+        let (msg, error_in_swift6) = self.detection.normalize_message(&message.message);
+        let msg = &msg;
+        let (warning_type, severity, confidence) = self.patterns.categorize(msg);
 
-        if warning_type == crate::models::WarningType::Unknown {
+        if !should_report(warning_type, confidence, &self.detection) {
             return None;
         }
 
@@ -142,7 +287,9 @@ impl XcodeBuildParser {
 
         let id = format!("{}:{}:{}", file_path, line_number, msg.len());
 
-        let code_context = self.extract_code_context(file_path, line_number);
+        let code_context =
+            crate::profile::time_context(|| self.extract_code_context(file_path, line_number));
+        let code_context = self.detection.finish_context(code_context);
 
         Some(Warning {
             id,
@@ -153,7 +300,12 @@ impl XcodeBuildParser {
             column_number,
             message: msg.clone(),
             code_context,
-            suggested_fix: self.suggest_fix(&warning_type, msg),
+            suggested_fix: builtin_fix(warning_type, msg),
+            error_in_swift6,
+            test_code: false,
+            labels: Default::default(),
+            effort: None,
+            raw: None,
         })
     }
 
@@ -164,12 +316,20 @@ impl XcodeBuildParser {
             return None;
         }
 
-        let message = json.get("message")?.as_str()?;
-        let (warning_type, severity) = categorize_warning(message);
+        let (message, error_in_swift6) = self
+            .detection
+            .normalize_message(json.get("message")?.as_str()?);
+        let message = message.as_str();
+        let (warning_type, severity, confidence) = self.patterns.categorize(message);
 
-        if warning_type == crate::models::WarningType::Unknown {
+        if !should_report(warning_type, confidence, &self.detection) {
             return None;
         }
+        let severity = json
+            .get("severity")
+            .and_then(|v| v.as_str())
+            .and_then(explicit_severity)
+            .unwrap_or(severity);
 
         let file_path = json
             .get("file")
@@ -191,7 +351,9 @@ impl XcodeBuildParser {
 
         let id = format!("{}:{}:{}", file_path, line_number, message.len());
 
-        let code_context = self.extract_code_context(file_path, line_number);
+        let code_context =
+            crate::profile::time_context(|| self.extract_code_context(file_path, line_number));
+        let code_context = self.detection.finish_context(code_context);
 
         Some(Warning {
             id,
@@ -202,13 +364,18 @@ impl XcodeBuildParser {
             column_number,
             message: message.to_string(),
             code_context,
-            suggested_fix: self.suggest_fix(&warning_type, message),
+            suggested_fix: builtin_fix(warning_type, message),
+            error_in_swift6,
+            test_code: false,
+            labels: Default::default(),
+            effort: None,
+            raw: None,
         })
     }
 
     fn extract_code_context(&self, file_path: &str, line_number: usize) -> CodeContext {
         // Try to read the actual file and extract context
-        if let Ok(file) = File::open(file_path) {
+        if let Ok(file) = File::open(self.source_map.resolve(file_path)) {
             let reader = BufReader::new(file);
             let lines: Vec<String> = reader.lines().map(|l| l.unwrap_or_default()).collect();
 
@@ -237,40 +404,129 @@ impl XcodeBuildParser {
             after: Vec::new(),
         }
     }
+}
 
-    fn suggest_fix(
-        &self,
-        warning_type: &crate::models::WarningType,
-        message: &str,
-    ) -> Option<String> {
-        use crate::models::WarningType;
-
-        match warning_type {
-            WarningType::ActorIsolation => {
-                if message.contains("can not be referenced") || message.contains("cannot be referenced") {
-                    Some("Consider using 'await' to access the actor-isolated member, or move this code into an actor context.".to_string())
-                } else if message.contains("Main actor") {
-                    Some("Consider using '@MainActor' annotation or dispatching to the main queue.".to_string())
-                } else {
-                    Some("Ensure proper actor isolation by using 'await' or moving code to appropriate actor context.".to_string())
-                }
-            }
-            WarningType::SendableConformance => {
-                if message.contains("does not conform") {
-                    Some("Add 'Sendable' conformance to the type or use '@unchecked Sendable' if thread-safe.".to_string())
-                } else if message.contains("capture") {
-                    Some("Ensure captured values conform to 'Sendable' or restructure to avoid capture.".to_string())
-                } else {
-                    Some("Review Sendable conformance requirements for concurrent contexts.".to_string())
-                }
-            }
-            WarningType::DataRace => {
-                Some("Protect shared mutable state with proper synchronization (locks, actors, or atomic operations).".to_string())
-            }
-            WarningType::PerformanceRegression => {
-                Some("Review async/await usage patterns and consider optimizing concurrency structure.".to_string())
-            }
-            WarningType::Unknown => None,
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::WarningType;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_parse_event_stream() {
+        let content = include_str!("../../tests/fixtures/xcodebuild_event_stream.json");
+        let parser = XcodeBuildParser::new(0);
+        let warnings = parser.parse_stream(Cursor::new(content)).unwrap();
+
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0].warning_type, WarningType::ActorIsolation);
+        assert_eq!(
+            warnings[0].file_path,
+            PathBuf::from("/project/Sources/App/Store.swift")
+        );
+        assert_eq!(warnings[0].line_number, 42);
+        assert_eq!(warnings[0].column_number, Some(17));
+        assert_eq!(warnings[1].warning_type, WarningType::SendableConformance);
+        assert_eq!(warnings[1].line_number, 8);
+
+        // Build and task events are skipped even when their text looks like a warning
+        assert!(warnings.iter().all(|w| !w.message.contains("task output")));
+    }
+
+    #[test]
+    fn test_parse_event_array() {
+        let content = r#"[
+            {"kind": "buildStarted", "payload": {"scheme": "App"}},
+            {"kind": "diagnostic", "payload": {"type": "warning", "message": "data race detected in shared state", "file": "/project/Queue.swift", "line": 9}},
+            {"type": "warning", "message": "actor-isolated property 'state' can not be referenced", "file": "/project/Actor.swift", "line": 3}
+        ]"#;
+        let warnings = XcodeBuildParser::new(0)
+            .parse_stream(Cursor::new(content))
+            .unwrap();
+
+        let types: Vec<WarningType> = warnings.iter().map(|w| w.warning_type).collect();
+        assert_eq!(types, [WarningType::DataRace, WarningType::ActorIsolation]);
+    }
+
+    #[test]
+    fn test_explicit_severity_overrides_category() {
+        let content = [
+            r#"{"type": "warning", "severity": "error", "message": "Type 'Cache' does not conform to the 'Sendable' protocol", "file": "/project/Cache.swift", "line": 4}"#,
+            r#"{"type": "warning", "severity": "Low", "message": "data race detected in shared state", "file": "/project/Queue.swift", "line": 9}"#,
+            r#"{"type": "warning", "severity": "warning", "message": "data race detected in shared state", "file": "/project/Queue.swift", "line": 20}"#,
+            r#"{"type": "warning", "message": "Type 'Cache' does not conform to the 'Sendable' protocol", "file": "/project/Cache.swift", "line": 30}"#,
+            r#"{"kind": "diagnostic", "payload": {"type": "warning", "severity": "high", "message": "Type 'Cache' does not conform to the 'Sendable' protocol", "file": "/project/Cache.swift", "line": 40, "extra": true}}"#,
+        ]
+        .join("\n");
+        let warnings = XcodeBuildParser::new(0)
+            .parse_stream(Cursor::new(content))
+            .unwrap();
+
+        let severities: Vec<Severity> = warnings.iter().map(|w| w.severity).collect();
+        assert_eq!(
+            severities,
+            [
+                Severity::Critical,
+                Severity::Low,
+                // A plain "warning" severity keeps the categorized one
+                Severity::Critical,
+                Severity::Medium,
+                Severity::High,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_progress_lines_do_not_start_array_mode() {
+        let warning = r#"{"type": "warning", "message": "data race detected in shared state", "file": "/test/Queue.swift", "line": 7}"#;
+        let parser = XcodeBuildParser::new(0);
+
+        for progress in [
+            "[1/3] Compiling App Store.swift",
+            "[10:42:01]: ▸ Compiling Store.swift",
+            "[ {broken",
+        ] {
+            let content = format!("{progress}\n{warning}\n");
+            let warnings = parser.parse_stream(Cursor::new(content)).unwrap();
+            assert_eq!(warnings.len(), 1, "{progress}");
+            assert_eq!(warnings[0].line_number, 7);
         }
     }
+
+    #[test]
+    fn test_parse_pretty_printed_array() {
+        let content = r#"
+[
+  {
+    "type": "warning",
+    "message": "actor-isolated property 'shared' can not be referenced from a non-isolated context",
+    "file": "/test/Actor.swift",
+    "line": 42,
+    "column": 15
+  },
+  {
+    "type": "note",
+    "message": "property declared here",
+    "file": "/test/Actor.swift",
+    "line": 10
+  },
+  {
+    "type": "warning",
+    "message": "Type 'MyClass' does not conform to the 'Sendable' protocol",
+    "filePath": "/test/Service.swift",
+    "lineNumber": 78
+  }
+]
+"#;
+
+        let parser = XcodeBuildParser::new(0);
+        let warnings = parser.parse_stream(Cursor::new(content)).unwrap();
+
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0].warning_type, WarningType::ActorIsolation);
+        assert_eq!(warnings[0].line_number, 42);
+        assert_eq!(warnings[0].column_number, Some(15));
+        assert_eq!(warnings[1].warning_type, WarningType::SendableConformance);
+        assert_eq!(warnings[1].line_number, 78);
+    }
 }
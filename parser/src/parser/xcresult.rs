@@ -1,6 +1,8 @@
 use crate::error::Result;
 use crate::models::{CodeContext, Warning};
-use crate::parser::patterns::categorize_warning;
+use crate::parser::pattern_pack::PatternPack;
+use crate::parser::patterns::{should_report, DetectionOptions};
+use crate::parser::source_map::SourceMap;
 use lazy_static::lazy_static;
 use regex::Regex;
 use serde_json::{self, Value};
@@ -14,78 +16,204 @@ lazy_static! {
     ).unwrap();
 }
 
+/// Issue layout of an xcresult JSON export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XcresultSchema {
+    /// `_values` array (or a bare array) of issues whose fields are
+    /// `{"_value": ...}` wrappers
+    Legacy,
+    /// Xcode 16 `xcresulttool get build-results`: a `warnings` array of
+    /// issues with plain string fields and a `sourceURL`
+    Xcode16,
+}
+
+impl XcresultSchema {
+    // First `resultBundleVersion` major version using the Xcode 16 layout
+    const XCODE16_VERSION: u64 = 4;
+
+    /// The schema declared by a top-level `resultBundleVersion` (a number,
+    /// string or `{"_value": ...}` wrapper), falling back to sniffing the
+    /// document's shape. Shapes are ambiguous when both arrays are present,
+    /// so a declared version always wins.
+    pub fn detect(value: &Value) -> Self {
+        if let Some(major) = value.get("resultBundleVersion").and_then(version_major) {
+            return if major >= Self::XCODE16_VERSION {
+                XcresultSchema::Xcode16
+            } else {
+                XcresultSchema::Legacy
+            };
+        }
+
+        if value.get("_values").is_none() && value.get("warnings").is_some_and(Value::is_array) {
+            XcresultSchema::Xcode16
+        } else {
+            XcresultSchema::Legacy
+        }
+    }
+
+    fn issues_key(self) -> &'static str {
+        match self {
+            XcresultSchema::Legacy => "_values",
+            XcresultSchema::Xcode16 => "warnings",
+        }
+    }
+
+    fn issues(self, value: &Value) -> Option<&Vec<Value>> {
+        match self {
+            XcresultSchema::Legacy => value
+                .get("_values")
+                .and_then(Value::as_array)
+                .or_else(|| value.as_array()),
+            XcresultSchema::Xcode16 => value.get("warnings").and_then(Value::as_array),
+        }
+    }
+
+    fn field<'a>(self, issue: &'a Value, key: &str) -> Option<&'a str> {
+        let field = issue.get(key)?;
+        match self {
+            XcresultSchema::Legacy => field.get("_value")?.as_str(),
+            XcresultSchema::Xcode16 => field.as_str(),
+        }
+    }
+
+    fn location_url(self, issue: &Value) -> Option<&str> {
+        match self {
+            // Try multiple location keys and normalize to URL string
+            XcresultSchema::Legacy => [
+                "documentLocationInCreatingWorkspace",
+                "documentURL",
+                "documentLocation",
+                "documentLocationInWorkspace",
+            ]
+            .iter()
+            .find_map(|key| {
+                let location = issue.get(*key)?;
+                // `documentURL` holds the URL directly; the others nest it
+                let url = location.get("url").unwrap_or(location);
+                url.get("_value")?.as_str()
+            }),
+            XcresultSchema::Xcode16 => self.field(issue, "sourceURL"),
+        }
+    }
+}
+
+// Major component of a version given as `4`, `"4.1"` or `{"_value": "4"}`
+fn version_major(version: &Value) -> Option<u64> {
+    match version {
+        Value::Number(n) => n.as_u64().or_else(|| n.as_f64().map(|f| f as u64)),
+        Value::String(s) => s.split('.').next()?.trim().parse().ok(),
+        Value::Object(_) => version.get("_value").and_then(version_major),
+        _ => None,
+    }
+}
+
+// `issueType` substrings accepted as warnings unless overridden
+const DEFAULT_ISSUE_TYPES: [&str; 1] = ["warning"];
+
 pub struct XcresultParser {
     context_lines: usize,
+    detection: DetectionOptions,
+    source_map: SourceMap,
+    patterns: PatternPack,
+    issue_types: Vec<String>,
 }
 
 impl XcresultParser {
     pub fn new(context_lines: usize) -> Self {
-        Self { context_lines }
+        Self {
+            context_lines,
+            detection: DetectionOptions::default(),
+            source_map: SourceMap::default(),
+            patterns: PatternPack::default(),
+            issue_types: DEFAULT_ISSUE_TYPES.map(String::from).to_vec(),
+        }
+    }
+
+    pub fn with_detection(mut self, detection: DetectionOptions) -> Self {
+        self.detection = detection;
+        self
+    }
+
+    /// Read code context through `source_map` instead of the reported paths
+    pub fn with_source_map(mut self, source_map: SourceMap) -> Self {
+        self.source_map = source_map;
+        self
+    }
+
+    /// Try `patterns` before the built-in ones when categorizing messages
+    pub fn with_patterns(mut self, patterns: PatternPack) -> Self {
+        self.patterns = patterns;
+        self
+    }
+
+    /// Accept issues whose `issueType` contains any of these substrings,
+    /// ignoring case; an empty list keeps the default of `warning`
+    pub fn with_issue_types(mut self, issue_types: &[String]) -> Self {
+        if !issue_types.is_empty() {
+            self.issue_types = issue_types.iter().map(|t| t.to_lowercase()).collect();
+        }
+        self
+    }
+
+    fn is_accepted_issue_type(&self, issue_type: &str) -> bool {
+        let issue_type = issue_type.to_lowercase();
+        self.issue_types
+            .iter()
+            .any(|t| issue_type.contains(t.as_str()))
     }
 
     pub fn parse_json(&self, json_content: &str) -> Result<Vec<Warning>> {
-        let value: Value = serde_json::from_str(json_content)?;
+        let error = match serde_json::from_str::<Value>(json_content) {
+            Ok(value) => return self.parse_document(&value),
+            Err(e) => e,
+        };
+
+        // `cat`-ed xcresulttool dumps hold several top-level documents back to back
+        let documents = serde_json::Deserializer::from_str(json_content)
+            .into_iter::<Value>()
+            .collect::<std::result::Result<Vec<_>, _>>();
+        match documents {
+            Ok(documents) if documents.len() > 1 => {
+                let mut warnings = Vec::new();
+                for document in &documents {
+                    warnings.extend(self.parse_document(document)?);
+                }
+                Ok(warnings)
+            }
+            _ => Err(error.into()),
+        }
+    }
+
+    fn parse_document(&self, value: &Value) -> Result<Vec<Warning>> {
         let mut warnings = Vec::new();
 
-        let issues: Vec<Value> = if let Some(arr) = value.get("_values").and_then(|v| v.as_array())
-        {
-            arr.clone()
-        } else if value.is_array() {
-            value.as_array().cloned().unwrap_or_default()
-        } else {
-            return Err(crate::error::ParseError::InvalidFormat(
-                "xcresult JSON missing _values array".to_string(),
-            ));
+        let schema = XcresultSchema::detect(value);
+        let issues: Vec<Value> = match schema.issues(value) {
+            Some(issues) => issues.to_vec(),
+            None => {
+                return Err(crate::error::ParseError::InvalidFormat(format!(
+                    "xcresult JSON missing {} array",
+                    schema.issues_key()
+                )))
+            }
         };
 
         for issue in issues {
-            let issue_type = issue
-                .get("issueType")
-                .and_then(|v| v.get("_value"))
-                .and_then(|v| v.as_str())
-                .unwrap_or("");
-            if !issue_type.to_lowercase().contains("warning") {
+            let issue_type = schema.field(&issue, "issueType").unwrap_or("");
+            if !self.is_accepted_issue_type(issue_type) {
                 continue;
             }
 
-            let message = issue
-                .get("message")
-                .and_then(|v| v.get("_value"))
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string();
+            let (message, error_in_swift6) = self
+                .detection
+                .normalize_message(schema.field(&issue, "message").unwrap_or(""));
 
-            let (warning_type, severity) = categorize_warning(&message);
-            if warning_type == crate::models::WarningType::Unknown {
+            let (warning_type, severity, confidence) = self.patterns.categorize(&message);
+            if !should_report(warning_type, confidence, &self.detection) {
                 continue;
             }
 
-            // Try multiple location keys and normalize to URL string
-            let url = issue
-                .get("documentLocationInCreatingWorkspace")
-                .and_then(|d| d.get("url"))
-                .and_then(|u| u.get("_value"))
-                .and_then(|s| s.as_str())
-                .or_else(|| {
-                    issue
-                        .get("documentURL")
-                        .and_then(|u| u.get("_value"))
-                        .and_then(|s| s.as_str())
-                })
-                .or_else(|| {
-                    issue
-                        .get("documentLocation")
-                        .and_then(|d| d.get("url"))
-                        .and_then(|u| u.get("_value"))
-                        .and_then(|s| s.as_str())
-                })
-                .or_else(|| {
-                    issue
-                        .get("documentLocationInWorkspace")
-                        .and_then(|d| d.get("url"))
-                        .and_then(|u| u.get("_value"))
-                        .and_then(|s| s.as_str())
-                });
+            let url = schema.location_url(&issue);
 
             if let Some(url) = url {
                 if let Some(captures) = URL_PARSER.captures(url) {
@@ -95,7 +223,10 @@ impl XcresultParser {
                         .and_then(|m| m.as_str().parse().ok())
                         .unwrap_or(0);
 
-                    let code_context = self.extract_code_context(file_path, line_number);
+                    let code_context = crate::profile::time_context(|| {
+                        self.extract_code_context(file_path, line_number)
+                    });
+                    let code_context = self.detection.finish_context(code_context);
                     let id = format!("{}:{}:{}", file_path, line_number, message.len());
 
                     warnings.push(Warning {
@@ -108,6 +239,11 @@ impl XcresultParser {
                         message,
                         code_context,
                         suggested_fix: None,
+                        error_in_swift6,
+                        test_code: false,
+                        labels: Default::default(),
+                        effort: None,
+                        raw: None,
                     });
                 }
             }
@@ -126,7 +262,7 @@ impl XcresultParser {
             after: Vec::new(),
         };
 
-        if let Ok(file) = fs::File::open(file_path) {
+        if let Ok(file) = fs::File::open(self.source_map.resolve(file_path)) {
             let reader = BufReader::new(file);
             let lines: Vec<String> = reader
                 .lines()
@@ -194,6 +330,86 @@ mod tests {
         assert!(warning.message.contains("Main actor-isolated"));
     }
 
+    // Both arrays present: only the declared version says which one is real
+    const BOTH_SHAPES: &str = r#"
+        "_values": [
+            {
+                "documentURL": { "_value": "file:///Users/test/Legacy.swift#StartingLineNumber=7" },
+                "issueType": { "_value": "Swift Compiler Warning" },
+                "message": { "_value": "data race detected in legacy shape" }
+            }
+        ],
+        "warnings": [
+            {
+                "issueType": "Swift Compiler Warning",
+                "message": "Type 'Cache' does not conform to the 'Sendable' protocol",
+                "sourceURL": "file:///Users/test/Cache.swift#EndingColumnNumber=9&EndingLineNumber=12&StartingColumnNumber=9&StartingLineNumber=12",
+                "targetName": "App"
+            }
+        ]
+    "#;
+
+    fn parse_with_version(version: &str) -> Vec<Warning> {
+        let json = format!("{{ {version} {BOTH_SHAPES} }}");
+        XcresultParser::new(0).parse_json(&json).unwrap()
+    }
+
+    #[test]
+    fn test_declared_legacy_version() {
+        for version in [
+            r#""resultBundleVersion": 3,"#,
+            r#""resultBundleVersion": "3.53","#,
+            r#""resultBundleVersion": { "_type": { "_name": "Int" }, "_value": "3" },"#,
+        ] {
+            let warnings = parse_with_version(version);
+            assert_eq!(warnings.len(), 1, "{version}");
+            assert_eq!(warnings[0].warning_type, WarningType::DataRace);
+            assert_eq!(warnings[0].line_number, 7);
+        }
+    }
+
+    #[test]
+    fn test_declared_xcode16_version() {
+        for version in [
+            r#""resultBundleVersion": 4,"#,
+            r#""resultBundleVersion": "4.0","#,
+        ] {
+            let warnings = parse_with_version(version);
+            assert_eq!(warnings.len(), 1, "{version}");
+            assert_eq!(warnings[0].warning_type, WarningType::SendableConformance);
+            assert_eq!(warnings[0].line_number, 12);
+            assert_eq!(
+                warnings[0].file_path,
+                PathBuf::from("/Users/test/Cache.swift")
+            );
+        }
+    }
+
+    #[test]
+    fn test_schema_sniffed_without_version() {
+        // Legacy wins when both shapes are present, as before versions were read
+        let warnings = parse_with_version("");
+        assert_eq!(warnings[0].warning_type, WarningType::DataRace);
+
+        let xcode16 = r#"{
+            "warnings": [
+                {
+                    "issueType": "Swift Compiler Warning",
+                    "message": "actor-isolated property 'state' can not be referenced",
+                    "sourceURL": "file:///Users/test/Store.swift#StartingLineNumber=3"
+                }
+            ]
+        }"#;
+        let warnings = XcresultParser::new(0).parse_json(xcode16).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].warning_type, WarningType::ActorIsolation);
+
+        // A declared version whose array is missing is malformed
+        assert!(XcresultParser::new(0)
+            .parse_json(r#"{"resultBundleVersion": 4, "_values": []}"#)
+            .is_err());
+    }
+
     #[test]
     fn test_parse_sendable_warning() {
         let json_content = r#"
@@ -222,7 +438,7 @@ mod tests {
         assert_eq!(warnings.len(), 1);
         let warning = &warnings[0];
         assert_eq!(warning.warning_type, WarningType::SendableConformance);
-        assert_eq!(warning.severity, Severity::High);
+        assert_eq!(warning.severity, Severity::Medium);
         assert_eq!(warning.line_number, 78);
     }
 
@@ -299,6 +515,88 @@ mod tests {
         assert_eq!(warnings.len(), 0);
     }
 
+    #[test]
+    fn test_custom_issue_types() {
+        let json_content = r#"
+        {
+            "_values": [
+                {
+                    "documentURL": { "_value": "file:///Users/test/A.swift#StartingLineNumber=3" },
+                    "issueType": { "_value": "Avertissement du mode Swift 6" },
+                    "message": { "_value": "data race detected in shared state" }
+                },
+                {
+                    "documentURL": { "_value": "file:///Users/test/B.swift#StartingLineNumber=5" },
+                    "issueType": { "_value": "Deprecation Warning" },
+                    "message": { "_value": "data race detected in legacy queue" }
+                },
+                {
+                    "documentURL": { "_value": "file:///Users/test/C.swift#StartingLineNumber=7" },
+                    "issueType": { "_value": "Swift Compiler Warning" },
+                    "message": { "_value": "data race detected in cache" }
+                }
+            ]
+        }
+        "#;
+        let files = |warnings: Vec<Warning>| -> Vec<String> {
+            warnings
+                .iter()
+                .map(|w| w.file_path.display().to_string())
+                .collect()
+        };
+
+        let default = XcresultParser::new(0).parse_json(json_content).unwrap();
+        assert_eq!(
+            files(default),
+            ["/Users/test/B.swift", "/Users/test/C.swift"]
+        );
+
+        let custom = XcresultParser::new(0)
+            .with_issue_types(&["AVERTISSEMENT".to_string(), "compiler warning".to_string()])
+            .parse_json(json_content)
+            .unwrap();
+        assert_eq!(
+            files(custom),
+            ["/Users/test/A.swift", "/Users/test/C.swift"]
+        );
+    }
+
+    #[test]
+    fn test_concatenated_documents() {
+        let document = |file: &str, message: &str| {
+            format!(
+                r#"{{
+    "_values": [
+        {{
+            "documentURL": {{ "_value": "file:///Users/test/{file}#StartingLineNumber=4" }},
+            "issueType": {{ "_value": "Swift Compiler Warning" }},
+            "message": {{ "_value": "{message}" }}
+        }}
+    ]
+}}"#
+            )
+        };
+        let json_content = format!(
+            "{}\n{}",
+            document("A.swift", "data race detected in shared state"),
+            document(
+                "B.swift",
+                "Type 'Cache' does not conform to the 'Sendable' protocol"
+            )
+        );
+
+        let warnings = XcresultParser::new(0).parse_json(&json_content).unwrap();
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0].warning_type, WarningType::DataRace);
+        assert_eq!(warnings[1].warning_type, WarningType::SendableConformance);
+        assert!(warnings[1].file_path.ends_with("B.swift"));
+
+        // A malformed trailing document still fails the whole input
+        assert!(XcresultParser::new(0)
+            .parse_json(&format!("{json_content}\n{{\"_values\": ["))
+            .is_err());
+    }
+
     #[test]
     fn test_malformed_json() {
         let parser = XcresultParser::new(2);
@@ -0,0 +1,83 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+// Time spent extracting code context, summed across parsers and threads. The
+// parsers have no handle on the run's `Profile`, so they report here instead.
+static CONTEXT_NANOS: AtomicU64 = AtomicU64::new(0);
+
+/// Run `extract`, adding its duration to the code context extraction total
+pub fn time_context<T>(extract: impl FnOnce() -> T) -> T {
+    let started = Instant::now();
+    let result = extract();
+    CONTEXT_NANOS.fetch_add(started.elapsed().as_nanos() as u64, Ordering::Relaxed);
+    result
+}
+
+/// Total code context extraction time so far in this process
+pub fn context_time() -> Duration {
+    Duration::from_nanos(CONTEXT_NANOS.load(Ordering::Relaxed))
+}
+
+/// Time spent in each stage of a run, in first-seen order. Stages recorded
+/// more than once (e.g. per file of a directory) are summed.
+#[derive(Debug, Default)]
+pub struct Profile {
+    stages: Mutex<Vec<(&'static str, Duration)>>,
+}
+
+impl Profile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&self, stage: &'static str, duration: Duration) {
+        let mut stages = self.stages.lock().unwrap();
+        match stages.iter_mut().find(|(name, _)| *name == stage) {
+            Some((_, total)) => *total += duration,
+            None => stages.push((stage, duration)),
+        }
+    }
+
+    /// Run `f`, recording its duration under `stage`
+    pub fn time<T>(&self, stage: &'static str, f: impl FnOnce() -> T) -> T {
+        let started = Instant::now();
+        let result = f();
+        self.add(stage, started.elapsed());
+        result
+    }
+
+    /// One line per stage, for stderr
+    pub fn report(&self) -> String {
+        let stages = self.stages.lock().unwrap();
+        let mut output = String::from("Profile:\n");
+        for (stage, duration) in stages.iter() {
+            output.push_str(&format!(
+                "  {:<20} {:>10.3} ms\n",
+                stage,
+                duration.as_secs_f64() * 1000.0
+            ));
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_lists_each_stage_once() {
+        let profile = Profile::new();
+        profile.add("read", Duration::from_millis(2));
+        profile.time("parse", || ());
+        profile.add("read", Duration::from_millis(3));
+
+        let report = profile.report();
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].trim_start().starts_with("read"));
+        assert!(lines[1].ends_with("5.000 ms"));
+        assert!(lines[2].trim_start().starts_with("parse"));
+    }
+}
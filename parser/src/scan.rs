@@ -0,0 +1,314 @@
+use crate::error::{ParseError, Result};
+use globset::GlobBuilder;
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use rayon::prelude::*;
+use std::path::{Component, Path, PathBuf};
+
+// Extensions of files picked up when scanning a directory of logs
+const LOG_EXTENSIONS: [&str; 3] = ["log", "txt", "json"];
+
+/// Recursively find log files under `dir`, in sorted order
+pub fn discover_inputs(dir: &Path) -> Result<Vec<PathBuf>> {
+    walk_files(dir, None, |path| {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| LOG_EXTENSIONS.contains(&ext))
+    })
+}
+
+/// Expand a shell-style glob (`*`, `?`, `[..]`, `{a,b}`, `**`) into the files
+/// it matches, in sorted order, for shells that don't expand globs themselves
+pub fn expand_glob(pattern: &str) -> Result<Vec<PathBuf>> {
+    let matcher = GlobBuilder::new(pattern)
+        .literal_separator(true)
+        .build()
+        .map_err(|e| ParseError::InvalidFormat(format!("invalid --input-glob: {e}")))?
+        .compile_matcher();
+
+    // Only walk below the pattern's leading literal directories
+    let is_literal = |c: &Component| {
+        !c.as_os_str()
+            .to_string_lossy()
+            .contains(['*', '?', '[', '{'])
+    };
+    let base: PathBuf = Path::new(pattern)
+        .components()
+        .take_while(is_literal)
+        .collect();
+    // ... and no deeper than the rest of the pattern reaches
+    let rest = Path::new(pattern).components().skip_while(is_literal);
+    let depth = if pattern.contains("**") || separator_in_braces(pattern) {
+        None
+    } else {
+        Some(rest.count())
+    };
+    if base.is_file() {
+        return Ok(vec![base]);
+    }
+    let base = if base.as_os_str().is_empty() {
+        PathBuf::from(".")
+    } else {
+        base
+    };
+    if !base.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let relative_to_cwd = !pattern.starts_with("./") && base == Path::new(".");
+    walk_files(&base, depth, |path| {
+        // Walking "." yields "./x"; unanchored patterns are written as "x"
+        let candidate = match path.strip_prefix(".") {
+            Ok(stripped) if relative_to_cwd => stripped,
+            _ => path,
+        };
+        matcher.is_match(candidate)
+    })
+}
+
+// Whether a `{a,b/c}` alternative in `pattern` spans directories, so the
+// pattern's depth can't be read off its components
+fn separator_in_braces(pattern: &str) -> bool {
+    let mut open = 0_usize;
+    pattern.chars().any(|c| {
+        match c {
+            '{' => open += 1,
+            '}' => open = open.saturating_sub(1),
+            _ => {}
+        }
+        c == '/' && open > 0
+    })
+}
+
+// Recursively collect files under `dir` accepted by `keep`, in sorted order.
+// With `max_depth`, only files that many levels down or less are considered
+// (1 is `dir`'s own entries).
+fn walk_files(
+    dir: &Path,
+    max_depth: Option<usize>,
+    keep: impl Fn(&Path) -> bool,
+) -> Result<Vec<PathBuf>> {
+    let mut found = Vec::new();
+    let mut pending = vec![(dir.to_path_buf(), 1)];
+
+    while let Some((current, depth)) = pending.pop() {
+        for entry in std::fs::read_dir(&current)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                if max_depth.is_none_or(|max| depth < max) {
+                    pending.push((path, depth + 1));
+                }
+            } else if keep(&path) {
+                found.push(path);
+            }
+        }
+    }
+
+    found.sort();
+    Ok(found)
+}
+
+/// Progress bar counting files scanned out of `total`, drawn on stderr. It
+/// draws nothing when stderr is not a terminal, or at all unless `enabled`.
+pub fn progress_bar(total: usize, enabled: bool) -> ProgressBar {
+    progress_bar_to(total, enabled, ProgressDrawTarget::stderr())
+}
+
+// `progress_bar` drawn on `target` instead of stderr
+fn progress_bar_to(total: usize, enabled: bool, target: ProgressDrawTarget) -> ProgressBar {
+    if !enabled {
+        return ProgressBar::hidden();
+    }
+    let bar = ProgressBar::with_draw_target(Some(total as u64), target);
+    if let Ok(style) = ProgressStyle::with_template("Scanning {pos}/{len} files {wide_bar}") {
+        bar.set_style(style);
+    }
+    bar
+}
+
+/// Parse `paths` on a bounded thread pool (`jobs` threads, default one per CPU).
+/// Results are returned in the order of `paths`, independent of scheduling.
+pub fn parse_files<T, F>(paths: &[PathBuf], jobs: Option<usize>, parse: F) -> Result<Vec<T>>
+where
+    T: Send,
+    F: Fn(&Path) -> Result<T> + Sync,
+{
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs.unwrap_or(0))
+        .build()
+        .map_err(|e| ParseError::InvalidFormat(format!("cannot start worker pool: {e}")))?;
+
+    pool.install(|| {
+        paths
+            .par_iter()
+            .map(|path| parse(path))
+            .collect::<Result<_>>()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Warning;
+
+    fn parse(path: &Path) -> Result<Vec<Warning>> {
+        let content = std::fs::read_to_string(path)?;
+        crate::parse_str(&content, 0)
+    }
+
+    #[test]
+    fn test_results_independent_of_jobs() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("nested");
+        std::fs::create_dir(&nested).unwrap();
+
+        for i in 0..6 {
+            let target = if i % 2 == 0 {
+                dir.path()
+            } else {
+                nested.as_path()
+            };
+            std::fs::write(
+                target.join(format!("build-{i}.log")),
+                format!(
+                    "/project/File{i}.swift:{line}:5: warning: actor-isolated property 'shared' can not be referenced\n\
+                     /project/File{i}.swift:{next}:9: warning: data race detected in shared state\n",
+                    line = i + 1,
+                    next = i + 100
+                ),
+            )
+            .unwrap();
+        }
+        std::fs::write(dir.path().join("README.md"), "not a log").unwrap();
+        std::fs::write(
+            dir.path().join("xcresult.json"),
+            include_str!("../tests/fixtures/xcresult_multiple_warnings.json"),
+        )
+        .unwrap();
+
+        let paths = discover_inputs(dir.path()).unwrap();
+        assert_eq!(paths.len(), 7);
+
+        let summarize = |per_file: Vec<Vec<Warning>>| -> Vec<String> {
+            per_file
+                .iter()
+                .flatten()
+                .map(|w| format!("{}:{}", w.file_path.display(), w.line_number))
+                .collect()
+        };
+
+        let sequential = summarize(parse_files(&paths, Some(1), parse).unwrap());
+        let parallel = summarize(parse_files(&paths, Some(4), parse).unwrap());
+        let default = summarize(parse_files(&paths, None, parse).unwrap());
+
+        assert_eq!(sequential.len(), 15);
+        assert_eq!(sequential, parallel);
+        assert_eq!(sequential, default);
+    }
+
+    #[test]
+    fn test_expand_glob() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("nested");
+        std::fs::create_dir(&nested).unwrap();
+        for path in [
+            dir.path().join("a.json"),
+            dir.path().join("b.json"),
+            dir.path().join("c.log"),
+            nested.join("d.json"),
+        ] {
+            std::fs::write(path, "").unwrap();
+        }
+        let root = dir.path().display();
+
+        let names = |pattern: String| -> Vec<String> {
+            expand_glob(&pattern)
+                .unwrap()
+                .iter()
+                .map(|p| p.strip_prefix(dir.path()).unwrap().display().to_string())
+                .collect()
+        };
+        assert_eq!(names(format!("{root}/*.json")), ["a.json", "b.json"]);
+        assert_eq!(
+            names(format!("{root}/**/*.json")),
+            ["a.json", "b.json", "nested/d.json"]
+        );
+        assert_eq!(names(format!("{root}/{{a,c}}.*")), ["a.json", "c.log"]);
+        assert_eq!(names(format!("{root}/*/d.json")), ["nested/d.json"]);
+        assert_eq!(
+            names(format!("{root}/{{a.json,nested/d.json}}")),
+            ["a.json", "nested/d.json"]
+        );
+        assert!(names(format!("{root}/missing/*.json")).is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_expand_glob_stays_at_pattern_depth() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.json"), "").unwrap();
+        // Walking into this would never end
+        std::os::unix::fs::symlink(dir.path(), dir.path().join("loop")).unwrap();
+
+        let files = expand_glob(&format!("{}/*.json", dir.path().display())).unwrap();
+        assert_eq!(files, [dir.path().join("a.json")]);
+    }
+
+    #[test]
+    fn test_progress_bar_counts_files() {
+        let bar = progress_bar(3, true);
+        assert_eq!(bar.length(), Some(3));
+        bar.inc(2);
+        assert_eq!(bar.position(), 2);
+        assert!(progress_bar(3, false).is_hidden());
+    }
+
+    /// A terminal that records what is written to it
+    #[derive(Debug, Clone, Default)]
+    struct Screen(std::sync::Arc<std::sync::Mutex<String>>);
+
+    impl indicatif::TermLike for Screen {
+        fn width(&self) -> u16 {
+            80
+        }
+        fn move_cursor_up(&self, _: usize) -> std::io::Result<()> {
+            Ok(())
+        }
+        fn move_cursor_down(&self, _: usize) -> std::io::Result<()> {
+            Ok(())
+        }
+        fn move_cursor_right(&self, _: usize) -> std::io::Result<()> {
+            Ok(())
+        }
+        fn move_cursor_left(&self, _: usize) -> std::io::Result<()> {
+            Ok(())
+        }
+        fn write_line(&self, s: &str) -> std::io::Result<()> {
+            self.write_str(&format!("{s}\n"))
+        }
+        fn write_str(&self, s: &str) -> std::io::Result<()> {
+            self.0.lock().unwrap().push_str(s);
+            Ok(())
+        }
+        fn clear_line(&self) -> std::io::Result<()> {
+            Ok(())
+        }
+        fn flush(&self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_progress_bar_hidden_only_when_quiet() {
+        for enabled in [true, false] {
+            let screen = Screen::default();
+            let target = ProgressDrawTarget::term_like(Box::new(screen.clone()));
+            let bar = progress_bar_to(3, enabled, target);
+            bar.inc(1);
+            bar.finish();
+
+            let drawn = screen.0.lock().unwrap().clone();
+            assert_eq!(bar.is_hidden(), !enabled);
+            assert_eq!(drawn.contains("Scanning 1/3 files"), enabled, "{drawn:?}");
+        }
+    }
+}
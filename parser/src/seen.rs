@@ -0,0 +1,134 @@
+use crate::error::{ParseError, Result};
+use crate::models::Warning;
+use std::collections::HashSet;
+use std::fs::{self, OpenOptions};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// How long to wait for another process to release the seen-db lock
+const LOCK_TIMEOUT: Duration = Duration::from_secs(30);
+const LOCK_POLL: Duration = Duration::from_millis(50);
+
+/// Exclusive lock on a seen-db, held as a `<path>.lock` file until dropped
+struct SeenLock {
+    path: PathBuf,
+}
+
+impl SeenLock {
+    fn acquire(db: &Path) -> Result<Self> {
+        let mut path = db.as_os_str().to_owned();
+        path.push(".lock");
+        let path = PathBuf::from(path);
+
+        let started = Instant::now();
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(_) => return Ok(Self { path }),
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    if started.elapsed() > LOCK_TIMEOUT {
+                        return Err(ParseError::IoError(std::io::Error::new(
+                            ErrorKind::TimedOut,
+                            format!(
+                                "timed out waiting for {}; remove it if no other run is active",
+                                path.display()
+                            ),
+                        )));
+                    }
+                    thread::sleep(LOCK_POLL);
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+impl Drop for SeenLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Drop warnings whose id is already recorded in the JSON id list at `db`, and
+/// record the ids of the remaining ones. A missing file is an empty list.
+pub fn retain_unseen(db: &Path, warnings: Vec<Warning>) -> Result<Vec<Warning>> {
+    let _lock = SeenLock::acquire(db)?;
+
+    let mut ids: Vec<String> = match fs::read_to_string(db) {
+        Ok(content) if !content.trim().is_empty() => serde_json::from_str(&content)?,
+        Ok(_) => Vec::new(),
+        Err(e) if e.kind() == ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(e.into()),
+    };
+    let mut seen: HashSet<String> = ids.iter().cloned().collect();
+
+    let unseen: Vec<Warning> = warnings
+        .into_iter()
+        .filter(|w| {
+            let new = seen.insert(w.id.clone());
+            if new {
+                ids.push(w.id.clone());
+            }
+            new
+        })
+        .collect();
+
+    // Write then rename so readers never observe a partially written file
+    let mut tmp = db.as_os_str().to_owned();
+    tmp.push(".tmp");
+    fs::write(&tmp, serde_json::to_string_pretty(&ids)?)?;
+    fs::rename(&tmp, db)?;
+
+    Ok(unseen)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn warning(id: &str) -> Warning {
+        Warning {
+            id: id.to_string(),
+            ..Warning::fixture("/project/File.swift", 1)
+        }
+    }
+
+    fn ids(warnings: &[Warning]) -> Vec<&str> {
+        warnings.iter().map(|w| w.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_sequential_runs_report_each_warning_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = dir.path().join("seen.json");
+
+        let first = retain_unseen(&db, vec![warning("a"), warning("b"), warning("a")]).unwrap();
+        assert_eq!(ids(&first), ["a", "b"]);
+
+        let second = retain_unseen(&db, vec![warning("b"), warning("c")]).unwrap();
+        assert_eq!(ids(&second), ["c"]);
+
+        let stored: Vec<String> = serde_json::from_str(&fs::read_to_string(&db).unwrap()).unwrap();
+        assert_eq!(stored, ["a", "b", "c"]);
+        assert!(!dir.path().join("seen.json.lock").exists());
+    }
+
+    #[test]
+    fn test_lock_is_exclusive() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = dir.path().join("seen.json");
+
+        let held = SeenLock::acquire(&db).unwrap();
+        let waiter = {
+            let db = db.clone();
+            thread::spawn(move || retain_unseen(&db, vec![warning("a")]).unwrap())
+        };
+        thread::sleep(LOCK_POLL * 4);
+        assert!(!db.exists(), "writer must wait for the lock");
+
+        drop(held);
+        assert_eq!(ids(&waiter.join().unwrap()), ["a"]);
+        assert!(db.exists());
+    }
+}
@@ -0,0 +1,167 @@
+use crate::error::{ParseError, Result};
+use crate::models::{WarningRun, WarningType};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Warning counts for a single historical run
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TrendPoint {
+    pub run_id: String,
+    pub commit_sha: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub total_warnings: usize,
+    pub by_type: BTreeMap<String, usize>,
+}
+
+impl TrendPoint {
+    pub fn from_run(run: &WarningRun) -> Self {
+        let mut by_type: BTreeMap<String, usize> = WarningType::ALL
+            .iter()
+            .map(|t| (t.as_str().to_string(), 0))
+            .collect();
+        for warning in run {
+            *by_type
+                .entry(warning.warning_type.as_str().to_string())
+                .or_insert(0) += 1;
+        }
+
+        Self {
+            run_id: run.id.clone(),
+            commit_sha: run.commit_sha.clone(),
+            created_at: run.created_at,
+            total_warnings: run.total_warnings,
+            by_type,
+        }
+    }
+}
+
+/// Load every `*.json` `WarningRun` in `dir`, ordered by creation time then file name
+pub fn load_runs(dir: &Path) -> Result<Vec<WarningRun>> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+
+    let mut runs = Vec::with_capacity(paths.len());
+    for path in paths {
+        let content = std::fs::read_to_string(&path)?;
+        let run: WarningRun = serde_json::from_str(&content).map_err(|e| {
+            ParseError::InvalidFormat(format!("{} is not a WarningRun: {e}", path.display()))
+        })?;
+        runs.push(run.migrate());
+    }
+
+    // Stable sort keeps file-name order for runs created at the same instant
+    runs.sort_by_key(|run| run.created_at);
+    Ok(runs)
+}
+
+pub fn build_trend(runs: &[WarningRun]) -> Vec<TrendPoint> {
+    runs.iter().map(TrendPoint::from_run).collect()
+}
+
+pub fn format_trend_json(points: &[TrendPoint]) -> Result<String> {
+    Ok(serde_json::to_string_pretty(points)?)
+}
+
+pub fn format_trend_csv(points: &[TrendPoint]) -> String {
+    let mut output = String::from("created_at,commit_sha,total_warnings");
+    for warning_type in WarningType::ALL {
+        output.push(',');
+        output.push_str(warning_type.as_str());
+    }
+    output.push('\n');
+
+    for point in points {
+        output.push_str(&format!(
+            "{},{},{}",
+            point.created_at.to_rfc3339(),
+            point.commit_sha.as_deref().unwrap_or(""),
+            point.total_warnings
+        ));
+        for warning_type in WarningType::ALL {
+            let count = point.by_type.get(warning_type.as_str()).unwrap_or(&0);
+            output.push_str(&format!(",{count}"));
+        }
+        output.push('\n');
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Warning;
+    use chrono::TimeZone;
+
+    fn warning(warning_type: WarningType) -> Warning {
+        Warning {
+            id: "File.swift:1:0".to_string(),
+            warning_type,
+            ..Warning::fixture("File.swift", 1)
+        }
+    }
+
+    fn write_run(dir: &Path, name: &str, day: u32, sha: &str, types: &[WarningType]) {
+        let mut run: WarningRun = types.iter().map(|t| warning(*t)).collect();
+        run.commit_sha = Some(sha.to_string());
+        run.created_at = Utc.with_ymd_and_hms(2025, 1, day, 12, 0, 0).unwrap();
+        std::fs::write(dir.join(name), serde_json::to_string(&run).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_trend_over_historical_runs() {
+        let dir = tempfile::tempdir().unwrap();
+        // File names deliberately out of chronological order
+        write_run(dir.path(), "a.json", 3, "ccc", &[WarningType::DataRace]);
+        write_run(
+            dir.path(),
+            "b.json",
+            1,
+            "aaa",
+            &[
+                WarningType::ActorIsolation,
+                WarningType::ActorIsolation,
+                WarningType::DataRace,
+            ],
+        );
+        write_run(
+            dir.path(),
+            "c.json",
+            2,
+            "bbb",
+            &[
+                WarningType::ActorIsolation,
+                WarningType::SendableConformance,
+            ],
+        );
+        std::fs::write(dir.path().join("notes.txt"), "ignored").unwrap();
+
+        let runs = load_runs(dir.path()).unwrap();
+        let points = build_trend(&runs);
+
+        let shas: Vec<_> = points.iter().map(|p| p.commit_sha.as_deref()).collect();
+        assert_eq!(shas, vec![Some("aaa"), Some("bbb"), Some("ccc")]);
+
+        let totals: Vec<_> = points.iter().map(|p| p.total_warnings).collect();
+        assert_eq!(totals, vec![3, 2, 1]);
+
+        assert_eq!(points[0].by_type["actor_isolation"], 2);
+        assert_eq!(points[0].by_type["data_race"], 1);
+        assert_eq!(points[1].by_type["sendable_conformance"], 1);
+        assert_eq!(points[2].by_type["actor_isolation"], 0);
+
+        let csv = format_trend_csv(&points);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "created_at,commit_sha,total_warnings,actor_isolation,sendable_conformance,data_race,performance_regression,deprecated_concurrency_api,unsafe_escape_hatch,unknown"
+        );
+        assert_eq!(lines[1], "2025-01-01T12:00:00+00:00,aaa,3,2,0,1,0,0,0,0");
+        assert_eq!(lines.len(), 4);
+    }
+}
@@ -1,4 +1,5 @@
 use std::io::Write;
+use swiftconcur_parser::error::ParseError;
 use swiftconcur_parser::models::WarningType;
 use swiftconcur_parser::parser::XcresultParser;
 use swiftconcur_parser::{
@@ -39,17 +40,63 @@ mod integration_tests {
         let cli = Cli {
             input: temp_file.path().to_string_lossy().to_string(),
             format: OutputFormat::Json,
-            baseline: None,
-            threshold: None,
-            filter: None,
-            context: 3,
-            verbose: false,
+            ..Cli::default()
         };
 
         let result = run(cli).unwrap();
         assert_eq!(result, 0); // Should return 0 because no threshold set
     }
 
+    #[test]
+    fn test_run_collapses_repeated_log_lines() {
+        let mut log = NamedTempFile::new().unwrap();
+        for _ in 0..3 {
+            writeln!(log, "/project/Actor.swift:30:12: warning: actor-isolated property 'state' can not be referenced from a non-isolated context").unwrap();
+        }
+        log.flush().unwrap();
+        let output = NamedTempFile::new().unwrap();
+
+        let cli = Cli {
+            input: log.path().to_string_lossy().to_string(),
+            format: OutputFormat::Markdown,
+            collapse_duplicates: true,
+            output: Some(output.path().to_path_buf()),
+            ..Cli::default()
+        };
+        assert_eq!(run(cli).unwrap(), 0);
+
+        let markdown = std::fs::read_to_string(output.path()).unwrap();
+        assert!(markdown.contains("**Total Warnings:** 3\n"), "{markdown}");
+        assert!(markdown.contains("/project/Actor.swift (×3)"), "{markdown}");
+        assert_eq!(markdown.matches("### ").count(), 1);
+    }
+
+    #[test]
+    fn test_run_with_unversioned_xcode16_xcresult() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(
+            temp_file,
+            r#"{{"warnings": [{{"issueType": "Swift Compiler Warning", "message": "data race detected in shared state", "sourceURL": "file:///project/Store.swift#StartingLineNumber=3"}}]}}"#
+        )
+        .unwrap();
+        temp_file.flush().unwrap();
+        let output = NamedTempFile::new().unwrap();
+
+        let cli = Cli {
+            input: temp_file.path().to_string_lossy().to_string(),
+            output: Some(output.path().to_path_buf()),
+            ..Cli::default()
+        };
+        assert_eq!(run(cli).unwrap(), 0);
+
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(output.path()).unwrap()).unwrap();
+        assert_eq!(json["total_warnings"], 1);
+        assert_eq!(json["detected_format"], "xcresult");
+        assert_eq!(json["warnings"][0]["file_path"], "/project/Store.swift");
+        assert_eq!(json["warnings"][0]["line_number"], 3);
+    }
+
     #[test]
     fn test_run_with_empty_xcresult_json() {
         let mut temp_file = NamedTempFile::new().unwrap();
@@ -65,11 +112,7 @@ mod integration_tests {
         let cli = Cli {
             input: temp_file.path().to_string_lossy().to_string(),
             format: OutputFormat::Json,
-            baseline: None,
-            threshold: None,
-            filter: None,
-            context: 3,
-            verbose: false,
+            ..Cli::default()
         };
 
         let result = run(cli).unwrap();
@@ -101,11 +144,8 @@ mod integration_tests {
         let cli = Cli {
             input: temp_file.path().to_string_lossy().to_string(),
             format: OutputFormat::Json,
-            baseline: None,
             threshold: Some(0), // Set threshold to 0, so 1 warning should exceed it
-            filter: None,
-            context: 3,
-            verbose: false,
+            ..Cli::default()
         };
 
         let result = run(cli).unwrap();
@@ -121,17 +161,48 @@ mod integration_tests {
         let cli = Cli {
             input: temp_file.path().to_string_lossy().to_string(),
             format: OutputFormat::Json,
-            baseline: None,
-            threshold: None,
-            filter: None,
-            context: 3,
-            verbose: false,
+            ..Cli::default()
         };
 
         let result = run(cli).unwrap();
         assert_eq!(result, 0); // Should return 0 because no threshold set and warnings exist
     }
 
+    #[test]
+    fn test_strict_detection_rejects_unknown_format() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(temp_file, "this is not a build log\nneither is this").unwrap();
+        temp_file.flush().unwrap();
+
+        let cli = Cli {
+            input: temp_file.path().to_string_lossy().to_string(),
+            strict_detection: true,
+            ..Cli::default()
+        };
+
+        let result = run(cli);
+        assert!(matches!(result, Err(ParseError::InvalidFormat(_))));
+    }
+
+    #[test]
+    fn test_strict_detection_accepts_clean_build_log() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(
+            temp_file,
+            "=== BUILD TARGET App OF PROJECT App ===\n** BUILD SUCCEEDED **"
+        )
+        .unwrap();
+        temp_file.flush().unwrap();
+
+        let cli = Cli {
+            input: temp_file.path().to_string_lossy().to_string(),
+            strict_detection: true,
+            ..Cli::default()
+        };
+
+        assert_eq!(run(cli).unwrap(), 0);
+    }
+
     #[test]
     fn test_format_detection_xcresult_vs_xcodebuild() {
         // Test xcresult format detection
@@ -441,3 +512,1472 @@ mod cli_integration_tests {
         assert!(stdout.contains("\"total_warnings\": 0"));
     }
 }
+
+#[cfg(test)]
+mod output_tests {
+    use assert_cmd::Command;
+    use predicates::prelude::*;
+    use std::fs;
+
+    #[test]
+    fn test_output_file_with_stdout_summary() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let input = temp_dir.path().join("build.log");
+        let output = temp_dir.path().join("warnings.json");
+        fs::write(
+            &input,
+            "/project/Actor.swift:30:12: warning: actor-isolated property 'state' can not be referenced from a non-isolated context\n\
+             /project/Queue.swift:95:10: warning: data race condition detected in shared memory access\n",
+        )
+        .unwrap();
+
+        Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .arg("-f")
+            .arg(&input)
+            .arg("--output")
+            .arg(&output)
+            .arg("--summary")
+            .arg("--threshold")
+            .arg("5")
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("SwiftConcur: 2 warnings - PASS"))
+            .stdout(predicate::str::contains(
+                "By type: actor_isolation=1, data_race=1",
+            ))
+            .stdout(predicate::str::contains("total_warnings").not());
+
+        let written = fs::read_to_string(&output).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&written).unwrap();
+        assert_eq!(json["total_warnings"], 2);
+    }
+
+    #[test]
+    fn test_labels_attached_to_every_warning() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let input = temp_dir.path().join("build.log");
+        fs::write(
+            &input,
+            "/project/Actor.swift:30:12: warning: actor-isolated property 'state' can not be referenced from a non-isolated context\n\
+             /project/Queue.swift:95:10: warning: data race condition detected in shared memory access\n",
+        )
+        .unwrap();
+
+        let output = Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .arg("-f")
+            .arg(&input)
+            .args(["--label", "scheme=Release", "--label", "config=a=b"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let warnings = json["warnings"].as_array().unwrap();
+        assert_eq!(warnings.len(), 2);
+        for warning in warnings {
+            assert_eq!(warning["labels"]["scheme"], "Release");
+            assert_eq!(warning["labels"]["config"], "a=b");
+        }
+
+        Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .arg("-f")
+            .arg(&input)
+            .args(["--label", "scheme"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("expected KEY=VALUE"));
+    }
+
+    #[test]
+    fn test_warn_on_unknown_lists_uncategorized_warnings() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let input = temp_dir.path().join("build.log");
+        fs::write(
+            &input,
+            "/project/Actor.swift:30:12: warning: actor-isolated property 'state' can not be referenced from a non-isolated context\n\
+             /project/Worker.swift:12:5: warning: global variable 'cache' is shared mutable state\n",
+        )
+        .unwrap();
+
+        let output = Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .arg("-f")
+            .arg(&input)
+            .arg("--warn-on-unknown")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains(
+            "Uncategorized warning: /project/Worker.swift:12: global variable 'cache' is shared mutable state"
+        ));
+        assert!(!stderr.contains("Actor.swift"));
+
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(json["total_warnings"], 1);
+    }
+
+    #[test]
+    fn test_directory_scan_progress_stays_off_stdout() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        for i in 0..20 {
+            fs::write(
+                temp_dir.path().join(format!("build-{i}.log")),
+                format!(
+                    "/project/File{i}.swift:3:5: warning: data race detected in shared state\n"
+                ),
+            )
+            .unwrap();
+        }
+
+        let output = Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .arg("-f")
+            .arg(temp_dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+
+        // stdout carries only the report
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(json["total_warnings"], 20);
+        // The progress bar goes to stderr and is only drawn on a terminal
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(!stderr.contains("Scanning"), "{stderr}");
+    }
+
+    #[test]
+    fn test_input_glob_merges_matching_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+        for name in ["actor_isolation.json", "sendable_warnings.json"] {
+            fs::copy(fixtures.join(name), temp_dir.path().join(name)).unwrap();
+        }
+        fs::write(temp_dir.path().join("notes.txt"), "not a log\n").unwrap();
+        let pattern = format!("{}/*.json", temp_dir.path().display());
+
+        let output = Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .args(["--input-glob", &pattern])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let files: std::collections::BTreeSet<&str> = json["warnings"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|w| w["file_path"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            files.into_iter().collect::<Vec<_>>(),
+            [
+                "Sources/MyApp/DataManager.swift",
+                "Sources/MyApp/MyClass.swift",
+                "Sources/MyApp/MyService.swift",
+                "Sources/MyApp/NetworkService.swift",
+                "Sources/MyApp/Processor.swift",
+                "Sources/MyApp/ViewModel.swift",
+            ]
+        );
+
+        let missing = format!("{}/*.xcresult", temp_dir.path().display());
+        Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .args(["--input-glob", &missing])
+            .assert()
+            .code(2)
+            .stderr(predicate::str::contains("matched no files"));
+        Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .args(["--input-glob", &missing, "--allow-empty"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("\"total_warnings\": 0"));
+    }
+
+    #[test]
+    fn test_require_context_lists_missing_sources() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let source = temp_dir.path().join("Actor.swift");
+        fs::write(&source, "actor Store {\n    var state = 0\n}\n").unwrap();
+        let input = temp_dir.path().join("build.log");
+        fs::write(
+            &input,
+            format!(
+                "{}:2:9: warning: actor-isolated property 'state' can not be referenced from a non-isolated context\n\
+                 /nonexistent/Queue.swift:95:10: warning: data race detected in shared memory access\n",
+                source.display()
+            ),
+        )
+        .unwrap();
+
+        Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .arg("-f")
+            .arg(&input)
+            .arg("--require-context")
+            .assert()
+            .code(2)
+            .stderr(predicate::str::contains("/nonexistent/Queue.swift"))
+            .stderr(predicate::str::contains("Actor.swift").not());
+
+        // Without the flag missing sources only leave the context empty
+        Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .arg("-f")
+            .arg(&input)
+            .assert()
+            .success();
+    }
+
+    #[test]
+    fn test_source_map_reads_context_from_generated_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let generated = temp_dir.path().join("Generated").join("Store.swift");
+        fs::create_dir_all(generated.parent().unwrap()).unwrap();
+        fs::write(&generated, "actor Store {\n    var state = 0\n}\n").unwrap();
+        // `#sourceLocation` makes the compiler report the template, which isn't on disk
+        let input = temp_dir.path().join("build.log");
+        fs::write(
+            &input,
+            "/templates/Store.swift:2:9: warning: actor-isolated property 'state' can not be referenced from a non-isolated context\n",
+        )
+        .unwrap();
+        let source_map = temp_dir.path().join("source-map.json");
+        fs::write(
+            &source_map,
+            serde_json::json!({ "/templates/Store.swift": generated }).to_string(),
+        )
+        .unwrap();
+
+        let output = Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .arg("-f")
+            .arg(&input)
+            .arg("--source-map")
+            .arg(&source_map)
+            .arg("--require-context")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let run: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let warning = &run["warnings"][0];
+        assert_eq!(warning["file_path"], "/templates/Store.swift");
+        assert_eq!(warning["code_context"]["line"], "    var state = 0");
+        assert_eq!(warning["code_context"]["before"][0], "actor Store {");
+
+        // Unmapped, the template path has no context to read
+        Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .arg("-f")
+            .arg(&input)
+            .arg("--require-context")
+            .assert()
+            .code(2)
+            .stderr(predicate::str::contains("/templates/Store.swift"));
+    }
+
+    #[test]
+    fn test_scope_lookups_read_mapped_sources_of_anonymized_paths() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let generated = temp_dir.path().join("Store.swift");
+        fs::write(
+            &generated,
+            "final class Store {\n    func load() {\n        Task {\n            use(self)\n        }\n    }\n}\n",
+        )
+        .unwrap();
+        let source_map = temp_dir.path().join("source-map.json");
+        fs::write(
+            &source_map,
+            serde_json::json!({ "/templates/Store.swift": generated }).to_string(),
+        )
+        .unwrap();
+
+        Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .args(["--context", "0", "--format", "markdown"])
+            .args(["--include-decl", "--function-relative", "--anonymize-paths"])
+            .arg("--source-map")
+            .arg(&source_map)
+            .write_stdin("/templates/Store.swift:4:17: warning: capture of 'self' with non-sendable type 'Store' in a `@Sendable` closure\n")
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("templates").not())
+            .stdout(predicate::str::contains("(line 2 of func load())"))
+            .stdout(predicate::str::contains(
+                "**Declaration:** `final class Store {` (line 1)",
+            ));
+    }
+
+    #[test]
+    fn test_baseline_delta_line() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let baseline = temp_dir.path().join("baseline.json");
+        let previous = temp_dir.path().join("previous.log");
+        fs::write(
+            &previous,
+            "/project/Actor.swift:30:12: warning: actor-isolated property 'state' can not be referenced from a non-isolated context\n\
+             /project/Queue.swift:95:10: warning: data race condition detected in shared memory access\n",
+        )
+        .unwrap();
+        Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .arg("-f")
+            .arg(&previous)
+            .arg("--emit-baseline-on-pass")
+            .arg(&baseline)
+            .assert()
+            .success();
+
+        let current = temp_dir.path().join("current.log");
+        fs::write(
+            &current,
+            "/project/Actor.swift:30:12: warning: actor-isolated property 'state' can not be referenced from a non-isolated context\n\
+             /project/Model.swift:12:5: warning: capture of 'self' with non-sendable type 'Model' in a `@Sendable` closure\n\
+             /project/Store.swift:8:3: warning: main actor-isolated property 'items' can not be mutated from a nonisolated context\n",
+        )
+        .unwrap();
+
+        let output = Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .arg("-f")
+            .arg(&current)
+            .arg("--baseline")
+            .arg(&baseline)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr
+            .lines()
+            .any(|line| line == "Baseline: +2 new, -1 fixed, 3 total"));
+        // Stdout stays parseable
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap();
+
+        Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .arg("-f")
+            .arg(&current)
+            .arg("--baseline")
+            .arg(&baseline)
+            .arg("--quiet")
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("Baseline:").not());
+    }
+
+    #[test]
+    fn test_pattern_pack_matches_localized_log() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let input = temp_dir.path().join("build.log");
+        fs::write(
+            &input,
+            "/project/Store.swift:12:9: warning: Akteur-isolierte Eigenschaft 'count' kann nicht aus einem nicht isolierten Kontext verändert werden\n\
+             /project/Cache.swift:4:7: warning: Typ 'Cache' erfüllt nicht das Protokoll 'Sendable'\n\
+             /project/View.swift:30:5: warning: Variable 'größe' wurde nie verwendet\n",
+        )
+        .unwrap();
+        let pack = temp_dir.path().join("de.toml");
+        fs::write(
+            &pack,
+            "[[patterns]]\nregex = \"(?i)akteur-isoliert\"\ntype = \"actor_isolation\"\nseverity = \"high\"\n",
+        )
+        .unwrap();
+
+        // Nothing matches the English patterns, so the run hints at the locale
+        Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .arg("-f")
+            .arg(&input)
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("\"total_warnings\": 0"))
+            .stderr(predicate::str::contains(
+                "Hint: 3 of 3 compiler warnings matched no pattern and look localized",
+            ));
+
+        let output = Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .arg("-f")
+            .arg(&input)
+            .arg("--patterns")
+            .arg(&pack)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let run: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(run["total_warnings"], 1);
+        assert_eq!(run["warnings"][0]["warning_type"], "actor_isolation");
+        assert_eq!(run["warnings"][0]["severity"], "high");
+        assert!(!String::from_utf8(output.stderr).unwrap().contains("Hint:"));
+    }
+
+    #[test]
+    fn test_density_per_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let small = temp_dir.path().join("Small.swift");
+        let big = temp_dir.path().join("Big.swift");
+        fs::write(&small, "actor Store {}\n".repeat(50)).unwrap();
+        fs::write(&big, "actor Store {}\n".repeat(2000)).unwrap();
+        let log: String = [(&small, 3), (&small, 9), (&big, 40), (&big, 41)]
+            .iter()
+            .map(|(path, line)| {
+                format!(
+                    "{}:{line}:5: warning: actor-isolated property 'state' can not be referenced from a non-isolated context\n",
+                    path.display()
+                )
+            })
+            .chain(["/missing/Gone.swift:1:1: warning: data race detected in shared state\n".to_string()])
+            .collect();
+        let input = temp_dir.path().join("build.log");
+        fs::write(&input, log).unwrap();
+
+        let output = Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .arg("-f")
+            .arg(&input)
+            .arg("--density")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let run: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let files = run["files"].as_array().unwrap();
+        assert_eq!(files.len(), 3);
+        assert_eq!(files[0]["path"], small.to_str().unwrap());
+        assert_eq!(files[0]["lines"], 50);
+        assert_eq!(files[0]["density"], 4.0);
+        assert_eq!(files[1]["path"], big.to_str().unwrap());
+        assert_eq!(files[1]["density"], 0.1);
+        // Unreadable files keep their count but have no density
+        assert_eq!(files[2]["warnings"], 1);
+        assert!(files[2].get("density").is_none());
+
+        Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .arg("-f")
+            .arg(&input)
+            .arg("--density")
+            .arg("--summary")
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(format!(
+                "  Warnings per 100 lines: {} 4.0, {} 0.1\n",
+                small.display(),
+                big.display()
+            )));
+
+        // Off by default
+        Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .arg("-f")
+            .arg(&input)
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("\"files\"").not());
+    }
+
+    #[test]
+    fn test_fail_fast_stops_at_first_warning() {
+        let log = "/src/A.swift:1:1: warning: actor-isolated property 'a' can not be mutated from a non-isolated context\n\
+                   /src/B.swift:2:1: warning: data race detected in shared state\n";
+
+        let output = Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .args(["--fail-fast", "--threshold", "0"])
+            .write_stdin(log)
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert!(output.stdout.is_empty());
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains("Fail-fast: /src/A.swift:1:"), "{stderr}");
+        assert!(!stderr.contains("B.swift"), "{stderr}");
+
+        // Fail-fast needs a threshold that a single warning fails
+        Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .args(["--fail-fast"])
+            .write_stdin(log)
+            .assert()
+            .code(2);
+    }
+
+    #[test]
+    fn test_fail_fast_never_reads_past_the_stop() {
+        // A later region of the log isn't valid UTF-8
+        let mut log = b"/src/A.swift:1:1: warning: actor-isolated property 'a' can not be mutated from a non-isolated context\n\
+                        CompileSwift normal arm64 /src/B.swift\n"
+            .to_vec();
+        log.extend_from_slice(b"/src/B.swift:2:1: warning: caf\xE9\n");
+
+        Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .args(["--fail-fast", "--threshold", "0"])
+            .write_stdin(log.clone())
+            .assert()
+            .code(1)
+            .stderr(predicate::str::contains("Fail-fast: /src/A.swift:1:"));
+
+        Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .args(["--threshold", "0"])
+            .write_stdin(log)
+            .assert()
+            .code(2)
+            .stderr(predicate::str::contains("not valid UTF-8"));
+    }
+
+    #[test]
+    fn test_fail_fast_skips_later_glob_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("a.log"),
+            "/src/A.swift:1:1: warning: actor-isolated property 'a' can not be mutated from a non-isolated context\n",
+        )
+        .unwrap();
+        fs::write(temp_dir.path().join("b.log"), b"caf\xE9\n").unwrap();
+        let pattern = format!("{}/*.log", temp_dir.path().display());
+
+        Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .args(["--fail-fast", "--threshold", "0", "--input-glob", &pattern])
+            .assert()
+            .code(1);
+
+        Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .args(["--threshold", "0", "--input-glob", &pattern])
+            .assert()
+            .code(2);
+    }
+
+    #[test]
+    fn test_github_step_summary_is_appended() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let input = temp_dir.path().join("build.log");
+        fs::write(
+            &input,
+            "/project/Actor.swift:30:12: warning: actor-isolated property 'state' can not be referenced from a non-isolated context\n\
+             /project/Queue.swift:95:10: warning: data race condition detected in shared memory access\n",
+        )
+        .unwrap();
+        let summary = temp_dir.path().join("step_summary.md");
+        fs::write(&summary, "## Build\n").unwrap();
+
+        let output = Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .arg("-f")
+            .arg(&input)
+            .arg("--github-summary")
+            .env("GITHUB_STEP_SUMMARY", &summary)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        // The normal report still goes to stdout
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap();
+
+        let content = fs::read_to_string(&summary).unwrap();
+        assert!(content.starts_with("## Build\n### SwiftConcur: 2 warnings - ✅ PASS"));
+        assert!(content.contains("**Worst severity:** critical"));
+        assert!(content.contains("`/project/Queue.swift:95` data race condition"));
+
+        // Without the env var there is nothing to write to
+        Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .arg("-f")
+            .arg(&input)
+            .arg("--github-summary")
+            .env_remove("GITHUB_STEP_SUMMARY")
+            .assert()
+            .success();
+        assert_eq!(fs::read_to_string(&summary).unwrap(), content);
+    }
+
+    #[test]
+    fn test_no_fallback_surfaces_xcresult_errors() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let input = temp_dir.path().join("result.json");
+        // Truncated mid-document
+        fs::write(
+            &input,
+            r#"{"issues": {"warningSummaries": {"_values": [{"message": {"_value": "data race"#,
+        )
+        .unwrap();
+
+        // By default the raw log fallback finds nothing and the run passes
+        Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .arg("-f")
+            .arg(&input)
+            .assert()
+            .success();
+
+        Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .arg("-f")
+            .arg(&input)
+            .arg("--no-fallback")
+            .assert()
+            .code(2)
+            .stdout(predicate::str::is_empty())
+            .stderr(predicate::str::contains("Error:"));
+    }
+
+    #[test]
+    fn test_effort_estimates() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let input = temp_dir.path().join("build.log");
+        fs::write(
+            &input,
+            "/project/Actor.swift:30:12: warning: actor-isolated property 'state' can not be referenced from a non-isolated context\n\
+             /project/Queue.swift:95:10: warning: data race condition detected in shared memory access\n",
+        )
+        .unwrap();
+
+        let output = Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .arg("-f")
+            .arg(&input)
+            .output()
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(json["warnings"][0]["effort"], "medium");
+        assert_eq!(json["warnings"][1]["effort"], "high");
+        assert_eq!(json["effort_points"], 8);
+
+        let config = temp_dir.path().join("swiftconcur.toml");
+        fs::write(&config, "[effort]\nactor_isolation = \"low\"\n").unwrap();
+        Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .arg("-f")
+            .arg(&input)
+            .arg("--config")
+            .arg(&config)
+            .args(["--format", "csv", "--columns", "file,effort", "--summary"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("/project/Actor.swift,low"))
+            .stdout(predicate::str::contains("Effort points: 6"));
+    }
+
+    #[test]
+    fn test_category_map_renames_types() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let input = temp_dir.path().join("build.log");
+        fs::write(
+            &input,
+            "/project/Actor.swift:30:12: warning: actor-isolated property 'state' can not be referenced from a non-isolated context\n\
+             /project/Queue.swift:95:10: warning: data race condition detected in shared memory access\n",
+        )
+        .unwrap();
+        let map = temp_dir.path().join("categories.json");
+        fs::write(&map, r#"{"actor_isolation": "CONCURRENCY_ISOLATION"}"#).unwrap();
+
+        let output = Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .arg("-f")
+            .arg(&input)
+            .arg("--category-map")
+            .arg(&map)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(json["warnings"][0]["warning_type"], "CONCURRENCY_ISOLATION");
+        // Unmapped types pass through
+        assert_eq!(json["warnings"][1]["warning_type"], "data_race");
+
+        Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .arg("-f")
+            .arg(&input)
+            .arg("--category-map")
+            .arg(&map)
+            .args(["--format", "csv", "--columns", "file,type"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "/project/Actor.swift,CONCURRENCY_ISOLATION",
+            ));
+    }
+
+    #[test]
+    fn test_keep_raw_records_input_line() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let input = temp_dir.path().join("build.log");
+        let line = "/project/Actor.swift:30:12: warning: main actor-isolated property 'count' can not be mutated from a Sendable closure; this is an error in the Swift 6 language mode";
+        fs::write(&input, format!("Compiling Actor.swift\n{line}\n")).unwrap();
+
+        let output = Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .arg("-f")
+            .arg(&input)
+            .args(["--keep-raw", "--trim-message"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let warning = &json["warnings"][0];
+        assert_eq!(warning["raw"], line);
+        assert_ne!(warning["message"], warning["raw"]);
+
+        // Off by default
+        let output = Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .arg("-f")
+            .arg(&input)
+            .output()
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert!(json["warnings"][0].get("raw").is_none());
+    }
+
+    #[test]
+    fn test_validate_config_and_patterns() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let config = temp_dir.path().join("swiftconcur.toml");
+        fs::write(
+            &config,
+            "[fixes]\nactor_isolation = \"See the wiki\"\n\n[effort]\ndata_race = \"high\"\n",
+        )
+        .unwrap();
+        let patterns = temp_dir.path().join("patterns.toml");
+        fs::write(
+            &patterns,
+            "[[patterns]]\nregex = \"(?i)akteur-isoliert\"\ntype = \"actor_isolation\"\nseverity = \"high\"\n",
+        )
+        .unwrap();
+
+        Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .arg("validate")
+            .arg("--config")
+            .arg(&config)
+            .arg("--patterns")
+            .arg(&patterns)
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Config OK"))
+            .stdout(predicate::str::contains("Patterns OK: 1 pattern(s)"));
+
+        // Classified by the pack, then by the built-in patterns
+        Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .arg("validate")
+            .arg("--patterns")
+            .arg(&patterns)
+            .args(["--test-message", "Akteur-isolierte Eigenschaft 'count'"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "Classification: type=actor_isolation severity=high confidence=1.0",
+            ));
+        Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .args(["validate", "--test-message", "variable 'x' was never used"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("type=unknown"));
+    }
+
+    #[test]
+    fn test_validate_reports_invalid_patterns() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let patterns = temp_dir.path().join("patterns.toml");
+        fs::write(
+            &patterns,
+            "[[patterns]]\nregex = \"(ok)\"\ntype = \"data_race\"\nseverity = \"high\"\n\n\
+             [[patterns]]\nregex = \"(unclosed\"\ntype = \"data_race\"\nseverity = \"high\"\n\n\
+             [[patterns]]\nregex = \"[z-a]\"\ntype = \"data_race\"\nseverity = \"high\"\n",
+        )
+        .unwrap();
+
+        Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .arg("validate")
+            .arg("--patterns")
+            .arg(&patterns)
+            .assert()
+            .code(2)
+            .stderr(predicate::str::contains(
+                "invalid pattern '(unclosed' at line 7, column 9",
+            ))
+            .stderr(predicate::str::contains(
+                "invalid pattern '[z-a]' at line 12, column 9",
+            ));
+    }
+
+    #[test]
+    fn test_print_config_shows_overrides() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let config = temp_dir.path().join("swiftconcur.toml");
+        fs::write(&config, "[effort]\nactor_isolation = \"high\"\n").unwrap();
+
+        let output = Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .arg("--print-config")
+            .arg("--config")
+            .arg(&config)
+            .arg("--threshold")
+            .arg("5")
+            .arg("--format")
+            .arg("markdown")
+            .arg("--test-path")
+            .arg("Specs/**")
+            .env("GITHUB_SHA", "abc123")
+            .env_remove("GITHUB_REF_NAME")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+
+        let printed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(printed["threshold"], 5);
+        assert_eq!(printed["format"], "markdown");
+        assert_eq!(printed["test-path"], serde_json::json!(["Specs/**"]));
+        assert_eq!(printed["commit"], "abc123");
+        assert_eq!(printed["branch"], serde_json::Value::Null);
+        // Defaults are shown too
+        assert_eq!(printed["tab-width"], 4);
+        assert_eq!(printed["threshold-op"], "le");
+        // `--config` tables are merged into the same document
+        assert_eq!(printed["effort"]["actor_isolation"], "high");
+
+        let sources = &printed["sources"];
+        assert_eq!(sources["threshold"], "command line");
+        assert_eq!(sources["commit"], "env GITHUB_SHA");
+        assert_eq!(sources["tab-width"], "default");
+        assert_eq!(sources["branch"], serde_json::Value::Null);
+        assert_eq!(
+            sources["effort"],
+            format!("config {}", config.display()).as_str()
+        );
+    }
+
+    #[test]
+    fn test_keep_raw_conflicts_with_anonymize_paths() {
+        Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .arg("--keep-raw")
+            .arg("--anonymize-paths")
+            .write_stdin("/Users/alice/SecretCorp/App/Store.swift:3:5: warning: actor-isolated property 'state' can not be mutated from a non-isolated context\n")
+            .assert()
+            .code(2)
+            .stdout(predicate::str::contains("SecretCorp").not())
+            .stderr(predicate::str::contains("--anonymize-paths"));
+    }
+
+    #[test]
+    fn test_coarse_taxonomy_only_changes_presentation() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let output = temp_dir.path().join("warnings.json");
+
+        Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .arg("--detect-deprecated")
+            .arg("--taxonomy")
+            .arg("coarse")
+            .arg("--filter")
+            .arg("deprecated")
+            .arg("--output")
+            .arg(&output)
+            .arg("--summary")
+            .write_stdin(
+                "/project/Worker.swift:12:5: warning: 'DispatchSemaphore.wait' is unavailable from asynchronous contexts\n\
+                 /project/Actor.swift:30:12: warning: actor-isolated property 'state' can not be referenced from a non-isolated context\n",
+            )
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("SwiftConcur: 1 warning - PASS"))
+            .stdout(predicate::str::contains("By type: isolation=1"));
+
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+        assert_eq!(
+            json["warnings"][0]["warning_type"],
+            "deprecated_concurrency_api"
+        );
+    }
+
+    #[test]
+    fn test_anonymize_salt_keeps_tokens_stable_across_runs() {
+        let log = "/Users/alice/SecretCorp/App/Store.swift:3:5: warning: actor-isolated property 'state' can not be mutated from a non-isolated context\n";
+        let file_path = |salt: Option<&str>| -> String {
+            let mut command = Command::cargo_bin("swiftconcur-parser").unwrap();
+            command.arg("--anonymize-paths").write_stdin(log);
+            match salt {
+                Some(salt) => command.env("SWIFTCONCUR_ANONYMIZE_SALT", salt),
+                None => command.env_remove("SWIFTCONCUR_ANONYMIZE_SALT"),
+            };
+            let output = command.output().unwrap();
+            let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+            json["warnings"][0]["file_path"]
+                .as_str()
+                .unwrap()
+                .to_string()
+        };
+
+        assert!(!file_path(Some("team secret")).contains("SecretCorp"));
+        assert_eq!(
+            file_path(Some("team secret")),
+            file_path(Some("team secret"))
+        );
+        assert_ne!(file_path(Some("team secret")), file_path(Some("other")));
+        // Without a salt every run gets its own
+        assert_ne!(file_path(None), file_path(None));
+
+        // The salt is never printed
+        Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .args(["--print-config", "--anonymize-salt", "team secret"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("team secret").not());
+    }
+
+    #[test]
+    fn test_expect_min_canary() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let input = temp_dir.path().join("build.log");
+        fs::write(
+            &input,
+            "=== BUILD TARGET App OF PROJECT App WITH CONFIGURATION Debug ===\n\
+             /project/Actor.swift:30:12: warning: actor-isolated property 'state' can not be referenced from a non-isolated context\n\
+             ** BUILD SUCCEEDED **\n",
+        )
+        .unwrap();
+        let empty = temp_dir.path().join("empty.log");
+        fs::write(&empty, "\n").unwrap();
+
+        let run = |input: &std::path::Path, expect_min: &str, threshold: &str| {
+            Command::cargo_bin("swiftconcur-parser")
+                .unwrap()
+                .arg("-f")
+                .arg(input)
+                .arg("--expect-min")
+                .arg(expect_min)
+                .arg("--threshold")
+                .arg(threshold)
+                .assert()
+        };
+
+        // Tripped: distinct from the threshold failure code, and takes precedence
+        run(&input, "2", "5")
+            .code(3)
+            .stderr(predicate::str::contains("expected at least 2"));
+        run(&input, "2", "0").code(3);
+
+        // Not tripped
+        run(&input, "1", "5").success();
+        run(&input, "1", "0").code(1);
+
+        // Empty input can't be a parser regression
+        run(&empty, "1", "5").success();
+    }
+
+    #[test]
+    fn test_profile_lists_each_stage() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let input = temp_dir.path().join("build.log");
+        fs::write(
+            &input,
+            "/project/Actor.swift:30:12: warning: actor-isolated property 'state' can not be referenced from a non-isolated context\n",
+        )
+        .unwrap();
+
+        let assert = Command::cargo_bin("swiftconcur-parser")
+            .unwrap()
+            .arg("-f")
+            .arg(&input)
+            .arg("--profile")
+            .arg("--threshold")
+            .arg("5")
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Profile:").not());
+
+        let stderr = String::from_utf8(assert.get_output().stderr.clone()).unwrap();
+        assert!(stderr.contains("Profile:"));
+        for stage in [
+            "read",
+            "decode",
+            "detect",
+            "parse",
+            "context extraction",
+            "filter",
+            "format",
+            "write",
+        ] {
+            assert!(
+                stderr.lines().any(|l| l.trim_start().starts_with(stage)),
+                "missing stage {stage} in:\n{stderr}"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod parse_str_tests {
+    use swiftconcur_parser::models::WarningType;
+    use swiftconcur_parser::parse_str;
+
+    #[test]
+    fn test_parse_str_xcresult() {
+        let input = include_str!("fixtures/xcresult_multiple_warnings.json");
+
+        let warnings = parse_str(input, 0).unwrap();
+        assert_eq!(warnings.len(), 3);
+        assert_eq!(warnings[0].warning_type, WarningType::ActorIsolation);
+    }
+
+    #[test]
+    fn test_parse_str_xcodebuild_json_lines() {
+        let input = r#"{"type": "warning", "message": "actor-isolated property 'shared' can not be referenced", "file": "/test/A.swift", "line": 42, "column": 15}
+{"type": "warning", "message": "data race detected in shared state", "file": "/test/B.swift", "line": 7}"#;
+
+        let warnings = parse_str(input, 0).unwrap();
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0].column_number, Some(15));
+        assert_eq!(warnings[1].warning_type, WarningType::DataRace);
+    }
+
+    #[test]
+    fn test_parse_str_xcodebuild_json_array() {
+        let input = r#"[
+  {"type": "warning", "message": "Type 'Foo' does not conform to the 'Sendable' protocol", "file": "/test/Foo.swift", "line": 3}
+]"#;
+
+        let warnings = parse_str(input, 0).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].warning_type, WarningType::SendableConformance);
+    }
+
+    #[test]
+    fn test_parse_str_raw_log() {
+        let input = "/project/Actor.swift:30:12: warning: main actor-isolated property 'state' can not be mutated from a Sendable closure\n** BUILD SUCCEEDED **";
+
+        let warnings = parse_str(input, 0).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].line_number, 30);
+        assert_eq!(warnings[0].column_number, Some(12));
+    }
+
+    #[test]
+    fn test_parse_str_mixed_text_and_json_lines() {
+        let input = r#"=== BUILD TARGET App OF PROJECT App WITH CONFIGURATION Debug ===
+/project/Actor.swift:30:12: warning: actor-isolated property 'state' can not be referenced from a non-isolated context
+{"type": "warning", "message": "data race detected in shared state", "file": "/project/Queue.swift", "line": 7}
+** BUILD SUCCEEDED **"#;
+
+        let warnings = parse_str(input, 0).unwrap();
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings
+            .iter()
+            .any(|w| w.warning_type == WarningType::DataRace && w.line_number == 7));
+        assert!(warnings
+            .iter()
+            .any(|w| w.warning_type == WarningType::ActorIsolation && w.line_number == 30));
+    }
+
+    #[test]
+    fn test_parse_str_keeps_repeated_lines() {
+        let line = "/project/Actor.swift:30:12: warning: actor-isolated property 'state' can not be referenced from a non-isolated context\n";
+        let warnings = parse_str(&line.repeat(2), 0).unwrap();
+        assert_eq!(warnings.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_str_no_warnings() {
+        assert!(parse_str("", 0).unwrap().is_empty());
+        assert!(parse_str("nothing to see here", 0).unwrap().is_empty());
+    }
+}
+
+#[cfg(test)]
+mod custom_formatter_tests {
+    use std::fs;
+    use std::io::Write;
+    use swiftconcur_parser::cli::Cli;
+    use swiftconcur_parser::error::{ParseError, Result};
+    use swiftconcur_parser::formatters::Formatter;
+    use swiftconcur_parser::models::WarningRun;
+    use swiftconcur_parser::run_with_formatter;
+
+    struct LineCountFormatter;
+
+    impl Formatter for LineCountFormatter {
+        fn format(&self, run: &WarningRun) -> Result<String> {
+            Ok(run
+                .iter()
+                .map(|w| format!("{}@{}", w.warning_type.as_str(), w.line_number))
+                .collect::<Vec<_>>()
+                .join(","))
+        }
+    }
+
+    #[test]
+    fn test_run_with_custom_formatter() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let input = temp_dir.path().join("build.log");
+        let output = temp_dir.path().join("report.txt");
+        fs::write(
+            &input,
+            "/project/Actor.swift:30:12: warning: actor-isolated property 'state' can not be referenced from a non-isolated context\n\
+             /project/Queue.swift:95:10: warning: data race condition detected in shared memory access\n",
+        )
+        .unwrap();
+
+        let cli = Cli {
+            input: input.to_str().unwrap().to_string(),
+            output: Some(output.clone()),
+            threshold: Some(1),
+            ..Cli::default()
+        };
+        let exit_code = run_with_formatter(cli, Box::new(LineCountFormatter)).unwrap();
+
+        // The threshold gate still applies to custom formatters
+        assert_eq!(exit_code, 1);
+        assert_eq!(
+            fs::read_to_string(&output).unwrap(),
+            "actor_isolation@30,data_race@95\n"
+        );
+    }
+
+    /// Streams part of the report, then fails
+    struct FailingFormatter;
+
+    impl Formatter for FailingFormatter {
+        fn format(&self, _run: &WarningRun) -> Result<String> {
+            unreachable!("write_to is overridden")
+        }
+
+        fn write_to(&self, _run: &WarningRun, writer: &mut dyn Write) -> Result<()> {
+            writer.write_all(b"{\"warnings\": [")?;
+            Err(ParseError::InvalidFormat(
+                "serialization failed".to_string(),
+            ))
+        }
+    }
+
+    #[test]
+    fn test_failed_report_leaves_previous_output() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let input = temp_dir.path().join("build.log");
+        let output = temp_dir.path().join("report.json");
+        fs::write(
+            &input,
+            "/project/Queue.swift:95:10: warning: data race condition detected in shared memory access\n",
+        )
+        .unwrap();
+        fs::write(&output, "previous report\n").unwrap();
+
+        let cli = Cli {
+            input: input.to_str().unwrap().to_string(),
+            output: Some(output.clone()),
+            ..Cli::default()
+        };
+        assert!(run_with_formatter(cli, Box::new(FailingFormatter)).is_err());
+
+        assert_eq!(fs::read_to_string(&output).unwrap(), "previous report\n");
+        let leftovers: Vec<_> = fs::read_dir(temp_dir.path()).unwrap().collect();
+        assert_eq!(leftovers.len(), 2);
+    }
+}
+
+#[cfg(test)]
+mod transform_tests {
+    use std::fs;
+    use std::path::PathBuf;
+    use swiftconcur_parser::cli::{Cli, OutputFormat};
+    use swiftconcur_parser::models::WarningType;
+    use swiftconcur_parser::run_with_transform;
+
+    #[test]
+    fn test_transform_redacts_paths_and_recounts() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let input = temp_dir.path().join("build.log");
+        let output = temp_dir.path().join("report.md");
+        fs::write(
+            &input,
+            "/Users/alice/App/Actor.swift:30:12: warning: actor-isolated property 'state' can not be referenced from a non-isolated context\n\
+             /Users/alice/App/Queue.swift:95:10: warning: data race condition detected in shared memory access\n",
+        )
+        .unwrap();
+
+        let cli = Cli {
+            input: input.to_str().unwrap().to_string(),
+            output: Some(output.clone()),
+            format: OutputFormat::Markdown,
+            threshold: Some(1),
+            ..Cli::default()
+        };
+        let exit_code = run_with_transform(cli, |mut warning| {
+            if warning.warning_type == WarningType::DataRace {
+                return None;
+            }
+            let redacted = warning
+                .file_path
+                .display()
+                .to_string()
+                .replace("/alice/", "/<user>/");
+            warning.file_path = PathBuf::from(redacted);
+            Some(warning)
+        })
+        .unwrap();
+
+        // The gate sees the transformed run
+        assert_eq!(exit_code, 0);
+        let markdown = fs::read_to_string(&output).unwrap();
+        assert!(markdown.contains("**Total Warnings:** 1"));
+        assert!(markdown.contains("/Users/<user>/App/Actor.swift"));
+        assert!(!markdown.contains("alice"));
+        assert!(!markdown.contains("Queue.swift"));
+    }
+
+    #[test]
+    fn test_fail_fast_continues_past_dropped_warnings() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let input = temp_dir.path().join("build.log");
+        fs::write(
+            &input,
+            "/project/Actor.swift:30:12: warning: actor-isolated property 'state' can not be referenced from a non-isolated context\n\
+             /project/Queue.swift:95:10: warning: data race condition detected in shared memory access\n",
+        )
+        .unwrap();
+
+        let cli = Cli {
+            input: input.to_str().unwrap().to_string(),
+            threshold: Some(0),
+            fail_fast: true,
+            ..Cli::default()
+        };
+        let exit_code = run_with_transform(cli, |warning| {
+            (warning.warning_type != WarningType::ActorIsolation).then_some(warning)
+        })
+        .unwrap();
+
+        // Parsing went on to the data race, which the transform keeps
+        assert_eq!(exit_code, 1);
+    }
+}
+
+#[cfg(test)]
+mod detected_format_tests {
+    use std::fs;
+    use std::path::Path;
+    use swiftconcur_parser::{cli::Cli, run};
+
+    fn detected_format(input: &Path) -> serde_json::Value {
+        let output = tempfile::NamedTempFile::new().unwrap();
+        let cli = Cli {
+            input: input.to_str().unwrap().to_string(),
+            output: Some(output.path().to_path_buf()),
+            threshold: Some(100),
+            ..Cli::default()
+        };
+        run(cli).unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(output.path()).unwrap()).unwrap();
+        json["detected_format"].clone()
+    }
+
+    #[test]
+    fn test_detected_format_per_fixture() {
+        let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+        assert_eq!(
+            detected_format(&fixtures.join("xcresult_multiple_warnings.json")),
+            "xcresult"
+        );
+        assert_eq!(
+            detected_format(&fixtures.join("actor_isolation.json")),
+            "xcodebuild"
+        );
+        // Clean inputs are labelled by their shape, not by having warnings
+        assert_eq!(
+            detected_format(&fixtures.join("clean_build.json")),
+            "xcodebuild"
+        );
+        assert_eq!(
+            detected_format(&fixtures.join("xcresult_empty.json")),
+            "xcresult"
+        );
+        assert_eq!(
+            detected_format(&fixtures.join("xcresult_errors_only.json")),
+            "xcresult"
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let raw_log = dir.path().join("build.log");
+        fs::write(
+            &raw_log,
+            "/project/Actor.swift:30:12: warning: actor-isolated property 'state' can not be referenced from a non-isolated context\n",
+        )
+        .unwrap();
+        assert_eq!(detected_format(&raw_log), "rawlog");
+
+        fs::copy(
+            fixtures.join("actor_isolation.json"),
+            dir.path().join("xcodebuild.json"),
+        )
+        .unwrap();
+        assert_eq!(detected_format(dir.path()), "mixed");
+    }
+}
+
+#[cfg(test)]
+mod emit_baseline_tests {
+    use std::fs;
+    use std::path::Path;
+    use swiftconcur_parser::baseline::load_baseline;
+    use swiftconcur_parser::cli::{BaselineFormat, Cli};
+    use swiftconcur_parser::run;
+
+    fn run_with_threshold(input: &Path, baseline: &Path, threshold: usize) -> i32 {
+        let output = tempfile::NamedTempFile::new().unwrap();
+        let cli = Cli {
+            input: input.to_str().unwrap().to_string(),
+            output: Some(output.path().to_path_buf()),
+            threshold: Some(threshold),
+            emit_baseline_on_pass: Some(baseline.to_path_buf()),
+            ..Cli::default()
+        };
+        run(cli).unwrap()
+    }
+
+    fn write_log(dir: &Path) -> std::path::PathBuf {
+        let input = dir.join("build.log");
+        fs::write(
+            &input,
+            "/project/Actor.swift:30:12: warning: actor-isolated property 'state' can not be referenced from a non-isolated context\n\
+             /project/Queue.swift:95:10: warning: data race condition detected in shared memory access\n",
+        )
+        .unwrap();
+        input
+    }
+
+    #[test]
+    fn test_baseline_written_on_pass() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = write_log(dir.path());
+        let baseline = dir.path().join("baseline.json");
+
+        assert_eq!(run_with_threshold(&input, &baseline, 2), 0);
+
+        let saved = load_baseline(&baseline, BaselineFormat::Auto).unwrap();
+        assert_eq!(saved.total_warnings, 2);
+    }
+
+    #[test]
+    fn test_baseline_untouched_on_fail() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = write_log(dir.path());
+        let baseline = dir.path().join("baseline.json");
+        fs::write(&baseline, "previous baseline").unwrap();
+
+        assert_eq!(run_with_threshold(&input, &baseline, 1), 1);
+
+        assert_eq!(fs::read_to_string(&baseline).unwrap(), "previous baseline");
+    }
+}
+
+#[cfg(test)]
+mod chunked_read_tests {
+    use std::io::{BufReader, Read};
+    use std::ops::ControlFlow;
+    use swiftconcur_parser::models::{Warning, WarningType};
+    use swiftconcur_parser::parser::{RawLogParser, XcodeBuildParser};
+
+    /// Hands out at most `chunk` bytes per read, like a pipe under load
+    struct ChunkedReader {
+        data: Vec<u8>,
+        pos: usize,
+        chunk: usize,
+    }
+
+    impl ChunkedReader {
+        fn new(data: &str, chunk: usize) -> BufReader<Self> {
+            // A buffer smaller than any line, so every line spans several fills
+            BufReader::with_capacity(
+                4,
+                Self {
+                    data: data.as_bytes().to_vec(),
+                    pos: 0,
+                    chunk,
+                },
+            )
+        }
+    }
+
+    impl Read for ChunkedReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let end = (self.pos + self.chunk.min(buf.len())).min(self.data.len());
+            let n = end - self.pos;
+            buf[..n].copy_from_slice(&self.data[self.pos..end]);
+            self.pos = end;
+            Ok(n)
+        }
+    }
+
+    fn collect<F>(for_each: F) -> Vec<Warning>
+    where
+        F: FnOnce(
+            &mut dyn FnMut(Warning) -> ControlFlow<()>,
+        ) -> swiftconcur_parser::error::Result<()>,
+    {
+        let mut warnings = Vec::new();
+        for_each(&mut |warning| {
+            warnings.push(warning);
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+        warnings
+    }
+
+    #[test]
+    fn test_rawlog_lines_survive_tiny_reads() {
+        // Longer than `BufReader`'s default 8 KiB buffer, and multi-byte
+        // characters that a chunk boundary splits
+        let long_name = "ä".repeat(6000);
+        let log = format!(
+            "/project/Actor.swift:30:12: warning: actor-isolated property '{long_name}' can not be referenced from a non-isolated context\n\
+             /project/Queue.swift:95:10: warning: data race condition detected in shared memory access\n\
+             \x20   continued message text\n"
+        );
+        let parser = RawLogParser::new(0);
+
+        for chunk in [1, 3, 7] {
+            let warnings = collect(|f| parser.for_each_warning(ChunkedReader::new(&log, chunk), f));
+            assert_eq!(warnings.len(), 2, "chunk {chunk}");
+            assert_eq!(warnings[0].line_number, 30);
+            assert!(warnings[0].message.contains(&long_name));
+            assert_eq!(warnings[1].warning_type, WarningType::DataRace);
+            let whole = parser.parse_stream(log.as_bytes()).unwrap();
+            let messages = |warnings: &[Warning]| -> Vec<String> {
+                warnings.iter().map(|w| w.message.clone()).collect()
+            };
+            assert_eq!(messages(&warnings), messages(&whole), "chunk {chunk}");
+        }
+    }
+
+    #[test]
+    fn test_xcodebuild_json_survives_tiny_reads() {
+        let line = r#"{"type":"warning","message":"actor-isolated property 'state' can not be referenced from a non-isolated context","file":"/project/Actor.swift","line":30,"column":12}"#;
+        let lines = format!("{line}\n{line}\n");
+        let array = format!("[\n  {line},\n  {line}\n]\n");
+        let parser = XcodeBuildParser::new(0);
+
+        for input in [lines, array] {
+            for chunk in [1, 5] {
+                let warnings =
+                    collect(|f| parser.for_each_warning(ChunkedReader::new(&input, chunk), f));
+                assert_eq!(warnings.len(), 2, "chunk {chunk}: {input}");
+                assert_eq!(warnings[0].line_number, 30);
+                assert_eq!(warnings[0].warning_type, WarningType::ActorIsolation);
+            }
+        }
+    }
+}
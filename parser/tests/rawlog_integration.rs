@@ -1,5 +1,6 @@
-use std::io::Write;
-use swiftconcur_parser::{cli::Cli, cli::OutputFormat, run};
+use std::io::{Cursor, Write};
+use swiftconcur_parser::parser::{DetectionOptions, RawLogParser};
+use swiftconcur_parser::{cli::Cli, cli::InputEncoding, cli::OutputFormat, run};
 use tempfile::NamedTempFile;
 
 #[test]
@@ -22,11 +23,7 @@ fn test_parse_github_action_log_warning() {
     let cli = Cli {
         input: temp_path.to_string(),
         format: OutputFormat::Json,
-        baseline: None,
-        threshold: None,
-        filter: None,
-        context: 3,
-        verbose: false,
+        ..Cli::default()
     };
 
     // Capture output
@@ -141,3 +138,205 @@ SwiftCompile normal arm64 /Users/runner/work/ConcurCLIDemo/ConcurCLIDemo/ConcurD
     assert_eq!(warnings.len(), 1);
     assert!(warnings[0].contains("main actor-isolated property"));
 }
+
+#[test]
+fn test_parse_bom_prefixed_log() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    temp_file.write_all(b"\xEF\xBB\xBF").unwrap();
+    writeln!(
+        temp_file,
+        "/project/Actor.swift:30:12: warning: main actor-isolated property 'state' can not be mutated from a Sendable closure"
+    )
+    .unwrap();
+
+    let cli = Cli {
+        input: temp_file.path().to_str().unwrap().to_string(),
+        threshold: Some(0),
+        ..Cli::default()
+    };
+
+    // Exit code 1 means the warning was found and exceeded the threshold
+    assert_eq!(run(cli).unwrap(), 1);
+}
+
+#[test]
+fn test_parse_latin1_log() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    temp_file
+        .write_all(b"/project/Caf\xE9/Actor.swift:30:12: warning: actor-isolated property 'r\xE9sum\xE9' can not be referenced from a non-isolated context\n")
+        .unwrap();
+
+    let utf8_cli = Cli {
+        input: temp_file.path().to_str().unwrap().to_string(),
+        threshold: Some(0),
+        ..Cli::default()
+    };
+    assert!(run(utf8_cli).is_err());
+
+    let latin1_cli = Cli {
+        input: temp_file.path().to_str().unwrap().to_string(),
+        threshold: Some(0),
+        input_encoding: InputEncoding::Latin1,
+        ..Cli::default()
+    };
+    assert_eq!(run(latin1_cli).unwrap(), 1);
+}
+
+#[test]
+fn test_fail_on_swift6_errors() {
+    let raw_log = r#"
+/Users/runner/work/ConcurCLIDemo/ConcurCLIDemo/ConcurDemo/Item.swift:37:24: warning: main actor-isolated property 'count' can not be mutated from a Sendable closure; this is an error in the Swift 6 language mode
+/project/DataManager.swift:42:15: warning: actor-isolated property 'data' can not be referenced from a non-isolated context
+"#
+    .trim();
+
+    let warnings = swiftconcur_parser::parse_str(raw_log, 0).unwrap();
+    let run_summary = swiftconcur_parser::models::WarningRun::new(warnings);
+    assert_eq!(run_summary.total_warnings, 2);
+    assert_eq!(run_summary.swift6_error_count, 1);
+
+    let mut temp_file = NamedTempFile::new().unwrap();
+    writeln!(temp_file, "{}", raw_log).unwrap();
+    let input = temp_file.path().to_str().unwrap().to_string();
+
+    let cli = Cli {
+        input: input.clone(),
+        ..Cli::default()
+    };
+    assert_eq!(run(cli).unwrap(), 0);
+
+    let cli = Cli {
+        input,
+        fail_on_swift6_errors: true,
+        ..Cli::default()
+    };
+    assert_eq!(run(cli).unwrap(), 1);
+}
+
+#[test]
+fn test_trim_message() {
+    let raw_log = "/project/Item.swift:37:24: warning: main actor-isolated property 'count' can not be mutated from a Sendable closure; this is an error in the Swift 6 language mode";
+
+    let untrimmed = RawLogParser::new(0)
+        .parse_stream(Cursor::new(raw_log))
+        .unwrap();
+    let trimmed = RawLogParser::new(0)
+        .with_detection(DetectionOptions {
+            trim_message: true,
+            ..DetectionOptions::default()
+        })
+        .parse_stream(Cursor::new(raw_log))
+        .unwrap();
+
+    assert_eq!(
+        untrimmed[0].message,
+        "main actor-isolated property 'count' can not be mutated from a Sendable closure; this is an error in the Swift 6 language mode"
+    );
+    assert_eq!(
+        trimmed[0].message,
+        "main actor-isolated property 'count' can not be mutated from a Sendable closure"
+    );
+    assert!(untrimmed[0].is_error_in_swift6());
+    assert!(trimmed[0].is_error_in_swift6());
+    assert_ne!(trimmed[0].id, untrimmed[0].id);
+
+    // The id no longer depends on the escalation wording
+    let reworded = raw_log.replace(
+        "this is an error in the Swift 6 language mode",
+        "this is an error in Swift 6",
+    );
+    let retrimmed = RawLogParser::new(0)
+        .with_detection(DetectionOptions {
+            trim_message: true,
+            ..DetectionOptions::default()
+        })
+        .parse_stream(Cursor::new(reworded))
+        .unwrap();
+    assert_eq!(retrimmed[0].id, trimmed[0].id);
+}
+
+#[test]
+fn test_parse_swiftc_llvm_diagnostic_style() {
+    // `swiftc -diagnostic-style=llvm` output: location-less `<unknown>`
+    // diagnostics, echoed source and caret/range lines after each diagnostic
+    let log = include_str!("fixtures/swiftc_llvm_style.log");
+
+    let parser = RawLogParser::new(0).with_detection(DetectionOptions {
+        detect_deprecated: true,
+        ..DetectionOptions::default()
+    });
+    let warnings = parser.parse_stream(Cursor::new(log)).unwrap();
+
+    let locations: Vec<(String, usize, Option<usize>)> = warnings
+        .iter()
+        .map(|w| {
+            (
+                w.file_path.display().to_string(),
+                w.line_number,
+                w.column_number,
+            )
+        })
+        .collect();
+    assert_eq!(
+        locations,
+        [
+            ("Sources/Counter.swift".to_string(), 12, Some(9)),
+            ("Sources/Store.swift".to_string(), 20, Some(17)),
+            ("Sources/Legacy.swift".to_string(), 8, Some(5)),
+        ]
+    );
+
+    // Echoed source never leaks into messages
+    assert!(warnings[0]
+        .message
+        .ends_with("this is an error in the Swift 6 language mode"));
+    assert_eq!(
+        warnings[1].message,
+        "capture of 'store' with non-sendable type 'Store' in a '@Sendable' closure"
+    );
+    assert!(warnings.iter().all(|w| !w.message.contains('^')));
+}
+
+#[test]
+fn test_parse_fastlane_scan_output() {
+    // fastlane `scan` prefixes lines with timestamps and ▸/⚠️ markers, and its
+    // xcpretty formatter drops the `warning:` keyword
+    let log = include_str!("fixtures/fastlane_scan.log");
+
+    let warnings = RawLogParser::new(0).parse_stream(Cursor::new(log)).unwrap();
+
+    let locations: Vec<(String, usize, Option<usize>)> = warnings
+        .iter()
+        .map(|w| {
+            (
+                w.file_path.display().to_string(),
+                w.line_number,
+                w.column_number,
+            )
+        })
+        .collect();
+    assert_eq!(
+        locations,
+        [
+            (
+                "/Users/ci/App/Sources/Store.swift".to_string(),
+                42,
+                Some(17)
+            ),
+            ("/Users/ci/App/Sources/Config.swift".to_string(), 8, Some(5)),
+            (
+                "/Users/ci/App/Sources/Queue.swift".to_string(),
+                95,
+                Some(10)
+            ),
+        ]
+    );
+    assert_eq!(
+        warnings[0].message,
+        "actor-isolated property 'items' can not be referenced from a non-isolated context"
+    );
+    assert_eq!(
+        warnings[1].message,
+        "Type 'Config' does not conform to the 'Sendable' protocol"
+    );
+}